@@ -0,0 +1,706 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+// Analytic hit testing.
+//
+// Fill hit testing counts signed crossings of a horizontal ray with y-monotonic
+// pieces of each segment. Stroke hit testing checks the distance to each segment
+// and tests joins and caps as polygons and circles.
+
+use alloc::vec::Vec;
+
+use crate::{FillRule, LineCap, LineJoin, Path, PathSegment, Point, Stroke};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use crate::NoStdFloat;
+
+impl Path {
+    /// Checks if the point is inside the filled path.
+    ///
+    /// Each contour is implicitly closed, just like during filling.
+    /// Points on the path edge are treated just like pixel centers during rasterization,
+    /// therefore the top-left edges are inside and the bottom-right ones are outside.
+    pub fn contains(&self, p: Point, fill_rule: FillRule) -> bool {
+        let b = self.bounds();
+        if !(p.x >= b.left() && p.x < b.right() && p.y >= b.top() && p.y < b.bottom()) {
+            return false;
+        }
+
+        let mut winding = 0;
+        for contour in contours(self) {
+            for seg in &contour.segments {
+                winding += seg.winding(p);
+            }
+
+            // Close the contour.
+            if !contour.closed {
+                winding += Segment::Line([contour.end(), contour.start]).winding(p);
+            }
+        }
+
+        match fill_rule {
+            FillRule::Winding => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+
+    /// Checks if the point is inside the stroked path.
+    ///
+    /// `tolerance` extends the stroke on each side and is useful for picking hairlines,
+    /// which are tested as zero-width strokes.
+    ///
+    /// The result is computed analytically and matches the stroker output,
+    /// except for curves that are too sharp for their stroke width.
+    pub fn stroke_contains(&self, p: Point, stroke: &Stroke, tolerance: f32) -> bool {
+        if !p.is_finite() || !(stroke.width >= 0.0) || !(tolerance >= 0.0) {
+            return false;
+        }
+
+        let r = stroke.width * 0.5 + tolerance;
+        if let Some(ref dash) = stroke.dash {
+            return match self.dash(dash, 1.0) {
+                Some(path) => stroke_contains(&path, p, stroke, r),
+                None => false,
+            };
+        }
+
+        stroke_contains(self, p, stroke, r)
+    }
+}
+
+fn stroke_contains(path: &Path, p: Point, stroke: &Stroke, r: f32) -> bool {
+    // A quick reject.
+    let b = path.bounds();
+    let outset = r * stroke.miter_limit.max(core::f32::consts::SQRT_2);
+    if p.x < b.left() - outset
+        || p.x > b.right() + outset
+        || p.y < b.top() - outset
+        || p.y > b.bottom() + outset
+    {
+        return false;
+    }
+
+    for contour in contours(path) {
+        let segments = &contour.segments;
+        if segments.is_empty() {
+            // A zero-length contour is drawn only with round and square caps.
+            let d = p - contour.start;
+            let hit = match stroke.line_cap {
+                LineCap::Butt => false,
+                LineCap::Round => d.length() <= r,
+                LineCap::Square => d.x.abs() <= r && d.y.abs() <= r,
+            };
+
+            if hit {
+                return true;
+            }
+
+            continue;
+        }
+
+        if segments.iter().any(|seg| seg.body_contains(p, r)) {
+            return true;
+        }
+
+        for pair in segments.windows(2) {
+            if join_contains(
+                stroke,
+                pair[0].end_tangent(),
+                pair[1].start_tangent(),
+                pair[1].start(),
+                p,
+                r,
+            ) {
+                return true;
+            }
+        }
+
+        let first = &segments[0];
+        let last = &segments[segments.len() - 1];
+        if contour.closed {
+            if join_contains(
+                stroke,
+                last.end_tangent(),
+                first.start_tangent(),
+                first.start(),
+                p,
+                r,
+            ) {
+                return true;
+            }
+        } else {
+            if cap_contains(stroke.line_cap, -first.start_tangent(), first.start(), p, r)
+                || cap_contains(stroke.line_cap, last.end_tangent(), last.end(), p, r)
+            {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+// `u` is the outward unit tangent.
+fn cap_contains(cap: LineCap, u: Point, v: Point, p: Point, r: f32) -> bool {
+    let d = p - v;
+    match cap {
+        LineCap::Butt => false,
+        LineCap::Round => d.length() <= r,
+        LineCap::Square => {
+            let along = d.dot(u);
+            let across = d.cross(u);
+            along >= 0.0 && along <= r && across.abs() <= r
+        }
+    }
+}
+
+// `t1` and `t2` are unit tangents of the incoming and outgoing segments at vertex `v`.
+fn join_contains(stroke: &Stroke, t1: Point, t2: Point, v: Point, p: Point, r: f32) -> bool {
+    let d = p - v;
+    if stroke.line_join == LineJoin::Round {
+        return d.length() <= r;
+    }
+
+    // Normals on the outer side of the join.
+    let cross = t1.cross(t2);
+    let sign = if cross <= 0.0 { 1.0 } else { -1.0 };
+    let n1 = Point::from_xy(-t1.y, t1.x).scaled(sign);
+    let n2 = Point::from_xy(-t2.y, t2.x).scaled(sign);
+
+    let a = v + n1.scaled(r);
+    let b = v + n2.scaled(r);
+
+    let cos = n1.dot(n2);
+    let miter_ratio = (2.0 / (1.0 + cos)).sqrt();
+    let is_miter = matches!(stroke.line_join, LineJoin::Miter | LineJoin::MiterClip);
+    if is_miter && cos > -1.0 {
+        let tip = v + (n1 + n2).scaled(r / (1.0 + cos));
+        if miter_ratio <= stroke.miter_limit {
+            return convex_polygon_contains(&[v, a, tip, b], p);
+        }
+
+        if stroke.line_join == LineJoin::MiterClip {
+            let mut bisector = n1 + n2;
+            bisector.normalize();
+            return convex_polygon_contains(&[v, a, tip, b], p)
+                && d.dot(bisector) <= r * stroke.miter_limit;
+        }
+    }
+
+    // Bevel.
+    convex_polygon_contains(&[v, a, b], p)
+}
+
+fn convex_polygon_contains(points: &[Point], p: Point) -> bool {
+    let mut has_pos = false;
+    let mut has_neg = false;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let c = (b - *a).cross(p - *a);
+        has_pos |= c > 0.0;
+        has_neg |= c < 0.0;
+    }
+
+    // A degenerate polygon has no area.
+    (has_pos || has_neg) && !(has_pos && has_neg)
+}
+
+struct Contour {
+    start: Point,
+    // Zero-length segments are skipped.
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+impl Contour {
+    fn end(&self) -> Point {
+        self.segments.last().map(|s| s.end()).unwrap_or(self.start)
+    }
+}
+
+fn contours(path: &Path) -> Vec<Contour> {
+    let mut contours: Vec<Contour> = Vec::new();
+    let mut last = Point::zero();
+    for segment in path.segments() {
+        let seg = match segment {
+            PathSegment::MoveTo(p) => {
+                contours.push(Contour {
+                    start: p,
+                    segments: Vec::new(),
+                    closed: false,
+                });
+                last = p;
+                continue;
+            }
+            PathSegment::LineTo(p) => Segment::Line([last, p]),
+            PathSegment::QuadTo(p1, p) => Segment::Quad([last, p1, p]),
+            PathSegment::CubicTo(p1, p2, p) => Segment::Cubic([last, p1, p2, p]),
+            PathSegment::Close => {
+                if let Some(contour) = contours.last_mut() {
+                    if contour.end() != contour.start {
+                        contour
+                            .segments
+                            .push(Segment::Line([contour.end(), contour.start]));
+                    }
+
+                    contour.closed = true;
+                    last = contour.start;
+                }
+
+                continue;
+            }
+        };
+
+        last = seg.end();
+        if let Some(contour) = contours.last_mut() {
+            if !seg.is_degenerate() {
+                contour.segments.push(seg);
+            }
+        }
+    }
+
+    contours
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Segment {
+    Line([Point; 2]),
+    Quad([Point; 3]),
+    Cubic([Point; 4]),
+}
+
+impl Segment {
+    fn points(&self) -> &[Point] {
+        match self {
+            Segment::Line(points) => points,
+            Segment::Quad(points) => points,
+            Segment::Cubic(points) => points,
+        }
+    }
+
+    fn start(&self) -> Point {
+        self.points()[0]
+    }
+
+    fn end(&self) -> Point {
+        let points = self.points();
+        points[points.len() - 1]
+    }
+
+    fn is_degenerate(&self) -> bool {
+        let points = self.points();
+        points.iter().all(|p| *p == points[0])
+    }
+
+    // The first non-zero direction from the start point.
+    fn start_tangent(&self) -> Point {
+        let points = self.points();
+        let mut t = Point::zero();
+        for p in &points[1..] {
+            t = *p - points[0];
+            if t.normalize() {
+                break;
+            }
+        }
+
+        t
+    }
+
+    // The last non-zero direction to the end point.
+    fn end_tangent(&self) -> Point {
+        let points = self.points();
+        let end = points[points.len() - 1];
+        let mut t = Point::zero();
+        for p in points[..points.len() - 1].iter().rev() {
+            t = end - *p;
+            if t.normalize() {
+                break;
+            }
+        }
+
+        t
+    }
+
+    fn eval(&self, t: f32) -> Point {
+        let mt = 1.0 - t;
+        match *self {
+            Segment::Line([p0, p1]) => p0.scaled(mt) + p1.scaled(t),
+            Segment::Quad([p0, p1, p2]) => {
+                p0.scaled(mt * mt) + p1.scaled(2.0 * mt * t) + p2.scaled(t * t)
+            }
+            Segment::Cubic([p0, p1, p2, p3]) => {
+                p0.scaled(mt * mt * mt)
+                    + p1.scaled(3.0 * mt * mt * t)
+                    + p2.scaled(3.0 * mt * t * t)
+                    + p3.scaled(t * t * t)
+            }
+        }
+    }
+
+    // The first and the second derivatives.
+    fn derivatives(&self, t: f32) -> (Point, Point) {
+        let mt = 1.0 - t;
+        match *self {
+            Segment::Line([p0, p1]) => (p1 - p0, Point::zero()),
+            Segment::Quad([p0, p1, p2]) => (
+                (p1 - p0).scaled(2.0 * mt) + (p2 - p1).scaled(2.0 * t),
+                (p2 - p1.scaled(2.0) + p0).scaled(2.0),
+            ),
+            Segment::Cubic([p0, p1, p2, p3]) => (
+                (p1 - p0).scaled(3.0 * mt * mt)
+                    + (p2 - p1).scaled(6.0 * mt * t)
+                    + (p3 - p2).scaled(3.0 * t * t),
+                (p2 - p1.scaled(2.0) + p0).scaled(6.0 * mt)
+                    + (p3 - p2.scaled(2.0) + p1).scaled(6.0 * t),
+            ),
+        }
+    }
+
+    // Checks if the point is within `r` from the segment, measured along the segment normal.
+    fn body_contains(&self, p: Point, r: f32) -> bool {
+        if let Segment::Line([p0, p1]) = *self {
+            let d = p1 - p0;
+            let t = (p - p0).dot(d) / d.dot(d);
+            return t >= 0.0 && t <= 1.0 && (p - p0).cross(d).abs() <= r * d.length();
+        }
+
+        // Find local minimums of the distance using samples and refine them
+        // using Newton's method.
+        const SAMPLES: usize = 16;
+        let mut dist = [0.0; SAMPLES + 1];
+        for (i, d) in dist.iter_mut().enumerate() {
+            *d = self.eval(i as f32 / SAMPLES as f32).distance(p);
+        }
+
+        for i in 0..=SAMPLES {
+            if (i > 0 && dist[i] > dist[i - 1]) || (i < SAMPLES && dist[i] > dist[i + 1]) {
+                continue;
+            }
+
+            let mut t = i as f32 / SAMPLES as f32;
+            for _ in 0..8 {
+                let (d1, d2) = self.derivatives(t);
+                let diff = self.eval(t) - p;
+                let g = diff.dot(d1);
+                let dg = d1.dot(d1) + diff.dot(d2);
+                if dg <= 0.0 {
+                    break;
+                }
+
+                t = (t - g / dg).clamp(0.0, 1.0);
+            }
+
+            // Points past the segment ends are handled by joins and caps.
+            if t == 0.0 && (p - self.start()).dot(self.start_tangent()) < 0.0 {
+                continue;
+            }
+
+            if t == 1.0 && (p - self.end()).dot(self.end_tangent()) > 0.0 {
+                continue;
+            }
+
+            if self.eval(t).distance(p) <= r {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // Returns the signed number of crossings of a ray from `p` towards +X.
+    fn winding(&self, p: Point) -> i32 {
+        // Split the segment into y-monotonic pieces.
+        let mut ts = [0.0; 4];
+        let mut len = 0;
+        ts[len] = 0.0;
+        len += 1;
+        let mut extrema = [0.0; 2];
+        for t in self.y_extrema(&mut extrema) {
+            if *t > 0.0 && *t < 1.0 {
+                ts[len] = *t;
+                len += 1;
+            }
+        }
+        ts[len] = 1.0;
+        len += 1;
+        ts[..len].sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+
+        let mut winding = 0;
+        for pair in ts[..len].windows(2) {
+            winding += self.monotonic_winding(pair[0], pair[1], p);
+        }
+
+        winding
+    }
+
+    fn y_extrema<'a>(&self, extrema: &'a mut [f32; 2]) -> &'a [f32] {
+        match *self {
+            Segment::Line(_) => &extrema[..0],
+            Segment::Quad([p0, p1, p2]) => {
+                let denom = p0.y - 2.0 * p1.y + p2.y;
+                if denom == 0.0 {
+                    return &extrema[..0];
+                }
+
+                extrema[0] = (p0.y - p1.y) / denom;
+                &extrema[..1]
+            }
+            Segment::Cubic([p0, p1, p2, p3]) => {
+                // The derivative is a*t^2 + b*t + c.
+                let a = p3.y - 3.0 * p2.y + 3.0 * p1.y - p0.y;
+                let b = 2.0 * (p2.y - 2.0 * p1.y + p0.y);
+                let c = p1.y - p0.y;
+                if a == 0.0 {
+                    if b == 0.0 {
+                        return &extrema[..0];
+                    }
+
+                    extrema[0] = -c / b;
+                    return &extrema[..1];
+                }
+
+                let discr = b * b - 4.0 * a * c;
+                if discr < 0.0 {
+                    return &extrema[..0];
+                }
+
+                let root = discr.sqrt();
+                extrema[0] = (-b - root) / (2.0 * a);
+                extrema[1] = (-b + root) / (2.0 * a);
+                &extrema[..2]
+            }
+        }
+    }
+
+    fn monotonic_winding(&self, t0: f32, t1: f32, p: Point) -> i32 {
+        let y0 = self.eval(t0).y;
+        let y1 = self.eval(t1).y;
+        if y0 == y1 {
+            return 0;
+        }
+
+        let (dir, lo, hi, y_lo, y_hi) = if y0 < y1 {
+            (1, t0, t1, y0, y1)
+        } else {
+            (-1, t1, t0, y1, y0)
+        };
+
+        if p.y < y_lo || p.y >= y_hi {
+            return 0;
+        }
+
+        let x = match *self {
+            Segment::Line([a, b]) => a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y),
+            _ => {
+                // Bisection, since the piece is monotonic.
+                let (mut lo, mut hi) = (lo, hi);
+                for _ in 0..32 {
+                    let mid = (lo + hi) * 0.5;
+                    if self.eval(mid).y < p.y {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+
+                self.eval((lo + hi) * 0.5).x
+            }
+        };
+
+        if x > p.x {
+            dir
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PathBuilder, Rect};
+
+    fn pt(x: f32, y: f32) -> Point {
+        Point::from_xy(x, y)
+    }
+
+    #[test]
+    fn contains_rect() {
+        let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 20.0, 30.0, 40.0).unwrap());
+        assert!(path.contains(pt(15.0, 25.0), FillRule::Winding));
+        assert!(path.contains(pt(10.0, 20.0), FillRule::Winding));
+        assert!(!path.contains(pt(30.0, 25.0), FillRule::Winding));
+        assert!(!path.contains(pt(15.0, 40.0), FillRule::Winding));
+        assert!(!path.contains(pt(5.0, 25.0), FillRule::Winding));
+        assert!(!path.contains(pt(f32::NAN, 25.0), FillRule::Winding));
+    }
+
+    #[test]
+    fn contains_fill_rule() {
+        // Two nested circles with the same direction.
+        let mut pb = PathBuilder::new();
+        pb.push_circle(50.0, 50.0, 40.0);
+        pb.push_circle(50.0, 50.0, 20.0);
+        let path = pb.finish().unwrap();
+
+        assert!(path.contains(pt(50.0, 50.0), FillRule::Winding));
+        assert!(!path.contains(pt(50.0, 50.0), FillRule::EvenOdd));
+        assert!(path.contains(pt(50.0, 20.0), FillRule::EvenOdd));
+        assert!(!path.contains(pt(15.0, 15.0), FillRule::Winding));
+    }
+
+    #[test]
+    fn contains_curves() {
+        let mut pb = PathBuilder::new();
+        pb.move_to(0.0, 0.0);
+        pb.quad_to(50.0, 100.0, 100.0, 0.0);
+        pb.cubic_to(100.0, -50.0, 0.0, -50.0, 0.0, 0.0);
+        let path = pb.finish().unwrap();
+
+        // The quad peaks at y = 50.
+        assert!(path.contains(pt(50.0, 49.0), FillRule::Winding));
+        assert!(!path.contains(pt(50.0, 51.0), FillRule::Winding));
+        assert!(!path.contains(pt(10.0, 30.0), FillRule::Winding));
+        // The cubic peaks at y = -37.5.
+        assert!(path.contains(pt(50.0, -37.0), FillRule::Winding));
+        assert!(!path.contains(pt(50.0, -38.0), FillRule::Winding));
+    }
+
+    #[test]
+    fn contains_unclosed() {
+        let mut pb = PathBuilder::new();
+        pb.move_to(0.0, 0.0);
+        pb.line_to(100.0, 0.0);
+        pb.line_to(100.0, 100.0);
+        let path = pb.finish().unwrap();
+
+        assert!(path.contains(pt(90.0, 10.0), FillRule::Winding));
+        assert!(!path.contains(pt(10.0, 90.0), FillRule::Winding));
+    }
+
+    fn polyline() -> Path {
+        let mut pb = PathBuilder::new();
+        pb.move_to(10.0, 10.0);
+        pb.line_to(90.0, 10.0);
+        pb.line_to(90.0, 90.0);
+        pb.finish().unwrap()
+    }
+
+    #[test]
+    fn stroke_body() {
+        let stroke = Stroke {
+            width: 10.0,
+            ..Stroke::default()
+        };
+        let path = polyline();
+        assert!(path.stroke_contains(pt(50.0, 14.0), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(50.0, 6.0), &stroke, 0.0));
+        assert!(!path.stroke_contains(pt(50.0, 16.0), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(50.0, 16.0), &stroke, 1.5));
+        assert!(path.stroke_contains(pt(94.0, 50.0), &stroke, 0.0));
+        assert!(!path.stroke_contains(pt(50.0, 50.0), &stroke, 0.0));
+    }
+
+    #[test]
+    fn stroke_caps() {
+        let path = polyline();
+        let mut stroke = Stroke {
+            width: 10.0,
+            ..Stroke::default()
+        };
+        assert!(!path.stroke_contains(pt(8.0, 10.0), &stroke, 0.0));
+
+        stroke.line_cap = LineCap::Round;
+        assert!(path.stroke_contains(pt(6.0, 10.0), &stroke, 0.0));
+        assert!(!path.stroke_contains(pt(6.0, 14.0), &stroke, 0.0));
+
+        stroke.line_cap = LineCap::Square;
+        assert!(path.stroke_contains(pt(6.0, 14.0), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(94.0, 94.0), &stroke, 0.0));
+        assert!(!path.stroke_contains(pt(94.0, 96.0), &stroke, 0.0));
+    }
+
+    #[test]
+    fn stroke_joins() {
+        let path = polyline();
+        let mut stroke = Stroke {
+            width: 10.0,
+            ..Stroke::default()
+        };
+        // A miter corner.
+        assert!(path.stroke_contains(pt(94.0, 6.0), &stroke, 0.0));
+
+        stroke.line_join = LineJoin::Round;
+        assert!(!path.stroke_contains(pt(94.0, 6.0), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(93.0, 7.0), &stroke, 0.0));
+
+        stroke.line_join = LineJoin::Bevel;
+        assert!(!path.stroke_contains(pt(93.0, 7.0), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(92.0, 8.0), &stroke, 0.0));
+
+        // Exceeds the miter limit.
+        stroke.line_join = LineJoin::Miter;
+        stroke.miter_limit = 1.2;
+        assert!(!path.stroke_contains(pt(94.0, 6.0), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(92.0, 8.0), &stroke, 0.0));
+
+        stroke.line_join = LineJoin::MiterClip;
+        assert!(!path.stroke_contains(pt(94.5, 5.5), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(93.0, 7.0), &stroke, 0.0));
+    }
+
+    #[test]
+    fn stroke_closed() {
+        let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 90.0, 90.0).unwrap());
+        let stroke = Stroke {
+            width: 10.0,
+            ..Stroke::default()
+        };
+        // The closing join at the start point.
+        assert!(path.stroke_contains(pt(6.0, 6.0), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(10.0, 50.0), &stroke, 0.0));
+    }
+
+    #[test]
+    fn stroke_curves() {
+        let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+        let stroke = Stroke {
+            width: 4.0,
+            ..Stroke::default()
+        };
+        for angle in 0..36 {
+            let a = (angle as f32 * 10.0).to_radians();
+            let (sin, cos) = (a.sin(), a.cos());
+            assert!(path.stroke_contains(pt(50.0 + 41.5 * cos, 50.0 + 41.5 * sin), &stroke, 0.0));
+            assert!(path.stroke_contains(pt(50.0 + 38.5 * cos, 50.0 + 38.5 * sin), &stroke, 0.0));
+            assert!(!path.stroke_contains(pt(50.0 + 42.5 * cos, 50.0 + 42.5 * sin), &stroke, 0.0));
+            assert!(!path.stroke_contains(pt(50.0 + 37.5 * cos, 50.0 + 37.5 * sin), &stroke, 0.0));
+        }
+    }
+
+    #[test]
+    fn stroke_dash() {
+        let path = polyline();
+        let stroke = Stroke {
+            width: 2.0,
+            dash: crate::StrokeDash::new(alloc::vec![10.0, 10.0], 0.0),
+            ..Stroke::default()
+        };
+
+        assert!(path.stroke_contains(pt(15.0, 10.0), &stroke, 0.0));
+        assert!(!path.stroke_contains(pt(25.0, 10.0), &stroke, 0.0));
+    }
+
+    #[test]
+    fn hairline() {
+        let path = polyline();
+        let stroke = Stroke {
+            width: 0.0,
+            ..Stroke::default()
+        };
+        assert!(!path.stroke_contains(pt(50.0, 11.0), &stroke, 0.0));
+        assert!(path.stroke_contains(pt(50.0, 11.0), &stroke, 2.0));
+    }
+}
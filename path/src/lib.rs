@@ -6,7 +6,8 @@
 
 //! A [tiny-skia](https://github.com/RazrFalcon/tiny-skia) Bezier path implementation.
 //!
-//! Provides a memory-efficient Bezier path container, path builder, path stroker and path dasher.
+//! Provides a memory-efficient Bezier path container, path builder, path stroker, path dasher,
+//! path measure and path boolean operations.
 //!
 //! Also provides some basic geometry types, but they will be moved to an external crate eventually.
 //!
@@ -39,24 +40,37 @@ mod dash;
 mod f32x2_t;
 mod f32x4_t;
 mod floating_point;
+mod hit_test;
+mod measure;
 mod path;
 mod path_builder;
+mod path_effect;
 pub mod path_geometry;
+mod path_ops;
+mod perspective;
 mod rect;
+mod rrect;
 mod scalar;
 mod size;
 mod stroker;
+mod svg;
 mod transform;
 
 pub use dash::StrokeDash;
 pub use f32x2_t::f32x2;
 pub use floating_point::*;
+pub use measure::{ContourMeasure, PathMeasure};
 pub use path::*;
 pub use path_builder::*;
+pub use path_effect::{Path1DStyle, PathEffect};
+pub use path_ops::PathOp;
+pub use perspective::PerspectiveTransform;
 pub use rect::*;
+pub use rrect::RRect;
 pub use scalar::*;
 pub use size::*;
 pub use stroker::*;
+pub use svg::{SvgPathError, SvgPathErrorKind};
 pub use transform::*;
 
 /// An integer length that is guarantee to be > 0
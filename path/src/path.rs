@@ -24,6 +24,16 @@ pub enum PathVerb {
     Close,
 }
 
+/// A path filling rule.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum FillRule {
+    /// Specifies that "inside" is computed by a non-zero sum of signed edge crossings.
+    #[default]
+    Winding,
+    /// Specifies that "inside" is computed by an odd number of edge crossings.
+    EvenOdd,
+}
+
 /// A Bezier path.
 ///
 /// Can be created via [`PathBuilder`].
@@ -9,11 +9,14 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use crate::{Path, Point, Rect};
+use crate::{Path, Point, RRect, Rect, Transform};
 
 use crate::path::PathVerb;
 use crate::path_geometry;
-use crate::scalar::{Scalar, SCALAR_ROOT_2_OVER_2};
+use crate::scalar::{Scalar, SCALAR_NEARLY_ZERO, SCALAR_ROOT_2_OVER_2};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use crate::NoStdFloat;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub(crate) enum PathDirection {
@@ -109,6 +112,15 @@ impl PathBuilder {
         b.finish()
     }
 
+    /// Creates a new `Path` from a rounded rectangle.
+    ///
+    /// See [`PathBuilder::push_rrect`] for details.
+    pub fn from_rrect(rrect: RRect) -> Option<Path> {
+        let mut b = Self::new();
+        b.push_rrect(rrect);
+        b.finish()
+    }
+
     pub(crate) fn reserve(&mut self, additional_verbs: usize, additional_points: usize) {
         self.verbs.reserve(additional_verbs);
         self.points.reserve(additional_points);
@@ -233,6 +245,321 @@ impl PathBuilder {
         self.cubic_to(p1.x, p1.y, p2.x, p2.y, p.x, p.y);
     }
 
+    /// Adds an elliptical arc from the last point to `x`, `y`.
+    ///
+    /// Arguments are the same as in the SVG `A` path command.
+    /// `x_axis_rotation` is in degrees. `large_arc` selects the arc
+    /// which is larger than 180 degrees and `sweep` selects the clockwise one.
+    ///
+    /// Radii too small to reach the end point are scaled up, just like in SVG.
+    /// A zero radius or an arc ending at the last point produce a line.
+    ///
+    /// - If `Path` is empty - adds `Move(0, 0)` first.
+    /// - If `Path` ends with Close - adds `Move(last_x, last_y)` first.
+    pub fn arc_to(
+        &mut self,
+        rx: f32,
+        ry: f32,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        x: f32,
+        y: f32,
+    ) {
+        self.inject_move_to_if_needed();
+
+        let src_pts = [self.last_point().unwrap(), Point::from_xy(x, y)];
+        // If rx = 0 or ry = 0 then this arc is treated as a straight line segment
+        // joining the endpoints.
+        // http://www.w3.org/TR/SVG/implnote.html#ArcOutOfRangeParameters
+        if rx == 0.0 || ry == 0.0 || src_pts[0] == src_pts[1] {
+            self.line_to(x, y);
+            return;
+        }
+
+        let mut rx = rx.abs();
+        let mut ry = ry.abs();
+
+        let mid_point_distance = (src_pts[0] - src_pts[1]).scaled(0.5);
+        let mut transformed_mid_point = [mid_point_distance];
+        Transform::from_rotate(-x_axis_rotation).map_points(&mut transformed_mid_point);
+        let transformed_mid_point = transformed_mid_point[0];
+
+        let square_rx = rx * rx;
+        let square_ry = ry * ry;
+        let square_x = transformed_mid_point.x * transformed_mid_point.x;
+        let square_y = transformed_mid_point.y * transformed_mid_point.y;
+
+        // Check if the radii are big enough to draw the arc, scale radii if not.
+        // http://www.w3.org/TR/SVG/implnote.html#ArcCorrectionOutOfRangeRadii
+        let radii_scale = square_x / square_rx + square_y / square_ry;
+        if radii_scale > 1.0 {
+            rx *= radii_scale.sqrt();
+            ry *= radii_scale.sqrt();
+        }
+
+        let ts = Transform::from_scale(rx.invert(), ry.invert()).pre_rotate(-x_axis_rotation);
+        let mut unit_pts = src_pts;
+        ts.map_points(&mut unit_pts);
+
+        let mut delta = unit_pts[1] - unit_pts[0];
+        let d = delta.x * delta.x + delta.y * delta.y;
+        let mut scale_factor = (d.invert() - 0.25).max(0.0).sqrt();
+        if sweep == large_arc {
+            scale_factor = -scale_factor;
+        }
+
+        delta.scale(scale_factor);
+        let mut center_point = (unit_pts[0] + unit_pts[1]).scaled(0.5);
+        center_point.x -= delta.y;
+        center_point.y += delta.x;
+        unit_pts[0] -= center_point;
+        unit_pts[1] -= center_point;
+
+        let theta1 = unit_pts[0].y.atan2(unit_pts[0].x);
+        let theta2 = unit_pts[1].y.atan2(unit_pts[1].x);
+        let mut theta_arc = theta2 - theta1;
+        if theta_arc < 0.0 && sweep {
+            theta_arc += 2.0 * core::f32::consts::PI;
+        } else if theta_arc > 0.0 && !sweep {
+            theta_arc -= 2.0 * core::f32::consts::PI;
+        }
+
+        // Very tiny angles cause our subsequent math to go wonky (skbug.com/9272)
+        // so we do a quick check here. The precise tolerance amount is just made up.
+        if theta_arc.abs() < core::f32::consts::PI / (1000.0 * 1000.0) {
+            self.line_to(x, y);
+            return;
+        }
+
+        let ts = Transform::from_rotate(x_axis_rotation).pre_scale(rx, ry);
+
+        // the arc may be slightly bigger than 1/4 circle, so allow up to 1/3rd
+        let segments = (theta_arc / (2.0 * core::f32::consts::PI / 3.0))
+            .abs()
+            .ceil();
+        let theta_width = theta_arc / segments;
+        let t = (0.5 * theta_width).tan();
+        if !t.is_finite() {
+            return;
+        }
+
+        let mut start_theta = theta1;
+        let w = (0.5 + theta_width.cos() * 0.5).sqrt();
+        let is_integer = |v: f32| v == v.floor();
+        let expect_integers = (core::f32::consts::FRAC_PI_2 - theta_width.abs()).is_nearly_zero()
+            && is_integer(rx)
+            && is_integer(ry)
+            && is_integer(x)
+            && is_integer(y);
+
+        for _ in 0..segments as usize {
+            let end_theta = start_theta + theta_width;
+            let sin_end_theta = sin_snap_to_zero(end_theta);
+            let cos_end_theta = cos_snap_to_zero(end_theta);
+
+            let mut conic_pts = [
+                Point::from_xy(cos_end_theta, sin_end_theta) + center_point,
+                Point::zero(),
+            ];
+            conic_pts[1] = conic_pts[0];
+            conic_pts[0].x += t * sin_end_theta;
+            conic_pts[0].y -= t * cos_end_theta;
+            ts.map_points(&mut conic_pts);
+
+            if expect_integers {
+                for p in &mut conic_pts {
+                    p.x = (p.x + 0.5).floor();
+                    p.y = (p.y + 0.5).floor();
+                }
+            }
+
+            self.conic_points_to(conic_pts[0], conic_pts[1], w);
+            start_theta = end_theta;
+        }
+
+        // finish at the exact end point
+        self.set_last_point(src_pts[1]);
+    }
+
+    /// Adds a circular arc of the specified radius, tangent to the lines
+    /// from the last point to `x1`, `y1` and from `x1`, `y1` to `x2`, `y2`.
+    ///
+    /// Just like `arcTo` in HTML Canvas, a line from the last point
+    /// to the arc start is added as well.
+    /// When the arc cannot be built, because the radius is zero or the points
+    /// are collinear, adds a line to `x1`, `y1` instead.
+    ///
+    /// - If `Path` is empty - adds `Move(0, 0)` first.
+    /// - If `Path` ends with Close - adds `Move(last_x, last_y)` first.
+    pub fn tangent_arc_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, radius: f32) {
+        self.inject_move_to_if_needed();
+
+        if !(radius > 0.0) {
+            self.line_to(x1, y1);
+            return;
+        }
+
+        // need to know our prev pt so we can construct tangent vectors
+        let start = self.last_point().unwrap();
+
+        // need double precision for these calcs.
+        let normalize = |x: f64, y: f64| {
+            let len = (x * x + y * y).sqrt();
+            (x / len, y / len)
+        };
+        let before = normalize(f64::from(x1 - start.x), f64::from(y1 - start.y));
+        let after = normalize(f64::from(x2 - x1), f64::from(y2 - y1));
+        let is_finite = before.0.is_finite()
+            && before.1.is_finite()
+            && after.0.is_finite()
+            && after.1.is_finite();
+        if !is_finite {
+            self.line_to(x1, y1);
+            return;
+        }
+
+        let cosh = before.0 * after.0 + before.1 * after.1;
+        let sinh = before.0 * after.1 - before.1 * after.0;
+
+        // If the previous point equals the first point, or the points are collinear,
+        // we just draw a line.
+        if (sinh as f32).is_nearly_zero() {
+            self.line_to(x1, y1);
+            return;
+        }
+
+        // safe to convert back to floats now
+        let dist = (f64::from(radius) * (1.0 - cosh) / sinh).abs() as f32;
+        let xx = x1 - dist * before.0 as f32;
+        let yy = y1 - dist * before.1 as f32;
+
+        let mut after = Point::from_xy(after.0 as f32, after.1 as f32);
+        after.set_length(dist);
+
+        self.line_to(xx, yy);
+        let weight = (0.5 + cosh as f32 * 0.5).sqrt();
+        self.conic_to(x1, y1, x1 + after.x, y1 + after.y, weight);
+    }
+
+    /// Adds a circular arc centered at `cx`, `cy`.
+    ///
+    /// Just like `arc` in HTML Canvas, the arc goes from `start_angle` to `end_angle`
+    /// clockwise or counter-clockwise when `ccw` is set.
+    /// Angles are in degrees, starting from the positive X axis.
+    /// When `end_angle` is 360 degrees or more past `start_angle` in the arc direction,
+    /// a full circle is produced. Otherwise, the angle difference is taken modulo 360 degrees,
+    /// so `start_angle = 360` and `end_angle = 0` produce a zero-length arc.
+    ///
+    /// If `Path` is empty - starts a new contour at the arc start.
+    /// Otherwise, adds a line from the last point to the arc start.
+    ///
+    /// Does nothing when `radius` is negative or any argument is not finite.
+    pub fn arc(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        ccw: bool,
+    ) {
+        if !(radius >= 0.0 && start_angle.is_finite() && end_angle.is_finite()) {
+            return;
+        }
+
+        let oval = match Rect::from_ltrb(cx - radius, cy - radius, cx + radius, cy + radius) {
+            Some(v) => v,
+            None => return,
+        };
+
+        let sweep_angle = if !ccw && end_angle - start_angle >= 360.0 {
+            360.0
+        } else if ccw && start_angle - end_angle >= 360.0 {
+            -360.0
+        } else if !ccw {
+            (end_angle - start_angle).rem_euclid(360.0)
+        } else {
+            -(start_angle - end_angle).rem_euclid(360.0)
+        };
+
+        let force_move_to = self.is_empty();
+        if sweep_angle.abs() >= 360.0 {
+            // A single arc would end at its start, so we use two half arcs instead.
+            let half_sweep_angle = sweep_angle.half();
+            self.push_arc(oval, start_angle, half_sweep_angle, force_move_to);
+            self.push_arc(
+                oval,
+                start_angle + half_sweep_angle,
+                half_sweep_angle,
+                false,
+            );
+        } else {
+            self.push_arc(oval, start_angle, sweep_angle, force_move_to);
+        }
+    }
+
+    // Adds an arc of an oval, like SkPath::arcTo(oval, startAngle, sweepAngle, forceMoveTo).
+    fn push_arc(&mut self, oval: Rect, start_angle: f32, sweep_angle: f32, force_move_to: bool) {
+        let force_move_to = force_move_to || self.is_empty();
+        let cx = oval.left().half() + oval.right().half();
+        let cy = oval.top().half() + oval.bottom().half();
+
+        let mut add_pt = |pt: Point| {
+            if force_move_to {
+                self.move_to(pt.x, pt.y);
+            } else {
+                let is_same = match self.last_point() {
+                    Some(last) => last.x.is_nearly_equal(pt.x) && last.y.is_nearly_equal(pt.y),
+                    None => false,
+                };
+
+                if !is_same {
+                    self.line_to(pt.x, pt.y);
+                }
+            }
+        };
+
+        if sweep_angle == 0.0 && (start_angle == 0.0 || start_angle == 360.0) {
+            add_pt(Point::from_xy(oval.right(), cy));
+            return;
+        } else if oval.width() == 0.0 && oval.height() == 0.0 {
+            add_pt(Point::from_xy(oval.right(), oval.top()));
+            return;
+        }
+
+        let (start_v, stop_v, dir) = angles_to_unit_vectors(start_angle, sweep_angle);
+
+        // At this point, we know that the arc is not a lone point, but start_v == stop_v
+        // indicates that the sweep angle is too small such that angles_to_unit_vectors
+        // cannot handle it.
+        if start_v == stop_v {
+            let end_angle = (start_angle + sweep_angle).to_radians();
+            add_pt(Point::from_xy(
+                cx + oval.width().half() * end_angle.cos(),
+                cy + oval.height().half() * end_angle.sin(),
+            ));
+            return;
+        }
+
+        let ts = Transform::from_row(oval.width().half(), 0.0, 0.0, oval.height().half(), cx, cy);
+
+        let mut conics = [path_geometry::Conic::default(); 5];
+        match path_geometry::Conic::build_unit_arc(start_v, stop_v, dir, ts, &mut conics) {
+            Some(conics) => {
+                add_pt(conics[0].points[0]);
+                for conic in conics {
+                    self.conic_points_to(conic.points[1], conic.points[2], conic.weight);
+                }
+            }
+            None => {
+                let mut pts = [stop_v];
+                ts.map_points(&mut pts);
+                add_pt(pts[0]);
+            }
+        }
+    }
+
     /// Closes the current contour.
     ///
     /// A closed contour connects the first and the last Point
@@ -335,6 +662,63 @@ impl PathBuilder {
         }
     }
 
+    /// Adds a rounded rectangle contour.
+    ///
+    /// The contour is closed and has a clock-wise direction,
+    /// starting at the end of the upper-left corner.
+    pub fn push_rrect(&mut self, rrect: RRect) {
+        let rect = rrect.rect();
+        let [ul, ur, lr, ll] = rrect.radii();
+        if rrect.is_rect() {
+            self.push_rect(rect);
+            return;
+        }
+
+        let (l, t, r, b) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+
+        // Each side with its end corner: side end point, corner control point and corner end point.
+        let sides = [
+            (
+                Point::from_xy(r - ur.x, t),
+                Point::from_xy(r, t),
+                Point::from_xy(r, t + ur.y),
+            ),
+            (
+                Point::from_xy(r, b - lr.y),
+                Point::from_xy(r, b),
+                Point::from_xy(r - lr.x, b),
+            ),
+            (
+                Point::from_xy(l + ll.x, b),
+                Point::from_xy(l, b),
+                Point::from_xy(l, b - ll.y),
+            ),
+            (
+                Point::from_xy(l, t + ul.y),
+                Point::from_xy(l, t),
+                Point::from_xy(l + ul.x, t),
+            ),
+        ];
+
+        let weight = SCALAR_ROOT_2_OVER_2;
+        self.move_to(l + ul.x, t);
+        for (i, (line_end, ctrl, corner_end)) in sides.iter().enumerate() {
+            if self.last_point() != Some(*line_end) {
+                self.line_to(line_end.x, line_end.y);
+            }
+
+            // The last corner ends at the contour start, so the last segment
+            // is added by close() when the corner is square.
+            let is_last = i == sides.len() - 1;
+            if line_end != corner_end {
+                self.conic_points_to(*ctrl, *corner_end, weight);
+            } else if is_last {
+                break;
+            }
+        }
+        self.close();
+    }
+
     /// Adds a path.
     pub fn push_path(&mut self, other: &Path) {
         self.last_move_to_index = self.points.len();
@@ -427,3 +811,58 @@ impl PathBuilder {
         })
     }
 }
+
+fn angles_to_unit_vectors(start_angle: f32, sweep_angle: f32) -> (Point, Point, PathDirection) {
+    let start_rad = start_angle.to_radians();
+    let mut stop_rad = (start_angle + sweep_angle).to_radians();
+
+    let start_v = Point::from_xy(cos_snap_to_zero(start_rad), sin_snap_to_zero(start_rad));
+    let mut stop_v = Point::from_xy(cos_snap_to_zero(stop_rad), sin_snap_to_zero(stop_rad));
+
+    // If the sweep angle is nearly (but less than) 360, then due to precision
+    // loss in radians-conversion and/or sin/cos, we may end up with coincident
+    // vectors, which will fool build_unit_arc into doing nothing (bad) instead
+    // of drawing a nearly complete circle (good).
+    if start_v == stop_v {
+        let sw = sweep_angle.abs();
+        if sw < 360.0 && sw > 359.0 {
+            // make a guess at a tiny angle (in radians) to tweak by
+            let delta_rad = if sweep_angle < 0.0 {
+                -1.0 / 512.0
+            } else {
+                1.0 / 512.0
+            };
+            // not sure how much will be enough, so we use a loop
+            while start_v == stop_v {
+                stop_rad -= delta_rad;
+                stop_v = Point::from_xy(cos_snap_to_zero(stop_rad), sin_snap_to_zero(stop_rad));
+            }
+        }
+    }
+
+    let dir = if sweep_angle > 0.0 {
+        PathDirection::CW
+    } else {
+        PathDirection::CCW
+    };
+
+    (start_v, stop_v, dir)
+}
+
+fn sin_snap_to_zero(radians: f32) -> f32 {
+    let v = radians.sin();
+    if v.abs() <= SCALAR_NEARLY_ZERO {
+        0.0
+    } else {
+        v
+    }
+}
+
+fn cos_snap_to_zero(radians: f32) -> f32 {
+    let v = radians.cos();
+    if v.abs() <= SCALAR_NEARLY_ZERO {
+        0.0
+    } else {
+        v
+    }
+}
@@ -0,0 +1,746 @@
+// Copyright 2006 The Android Open Source Project
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+// This module is a mix of SkCornerPathEffect, SkDiscretePathEffect, SkPath1DPathEffect,
+// SkTrimPathEffect and SkPathEffect composition.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::{Path, PathBuilder, PathMeasure, PathSegment, Point, StrokeDash, Transform};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use crate::NoStdFloat;
+
+// Protect against faulty input.
+const MAX_REASONABLE_ITERATIONS: usize = 100000;
+
+/// A path stamping style used by [`PathEffect::path_1d`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Path1DStyle {
+    /// Translates the stamp to each position.
+    Translate,
+    /// Rotates the stamp along the contour tangent at each position.
+    Rotate,
+    /// Bends each stamp point along the contour.
+    Morph,
+}
+
+/// A path effect.
+///
+/// Path effects alter the path geometry before stroking or filling.
+/// Can be applied via [`Path::apply_effect`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct PathEffect {
+    kind: PathEffectKind,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum PathEffectKind {
+    Dash(StrokeDash),
+    Corner {
+        radius: f32,
+    },
+    Discrete {
+        segment_length: f32,
+        deviation: f32,
+        seed: u32,
+    },
+    Path1D {
+        path: Path,
+        advance: f32,
+        initial_offset: f32,
+        style: Path1DStyle,
+    },
+    Trim {
+        start: f32,
+        end: f32,
+    },
+    Compose(Box<PathEffect>, Box<PathEffect>),
+    Sum(Box<PathEffect>, Box<PathEffect>),
+}
+
+impl PathEffect {
+    /// Creates a dashing effect.
+    pub fn dash(dash: StrokeDash) -> Self {
+        PathEffect {
+            kind: PathEffectKind::Dash(dash),
+        }
+    }
+
+    /// Creates an effect that replaces sharp corners between lines with quadratic curves.
+    ///
+    /// Curves are left as is.
+    ///
+    /// Returns `None` when `radius` is not finite or not positive.
+    pub fn corner(radius: f32) -> Option<Self> {
+        if !(radius.is_finite() && radius > 0.0) {
+            return None;
+        }
+
+        Some(PathEffect {
+            kind: PathEffectKind::Corner { radius },
+        })
+    }
+
+    /// Creates an effect that chops the path into lines of roughly `segment_length`
+    /// and randomly moves their ends by up to `deviation` along the normal.
+    ///
+    /// The same `seed` always produces the same output.
+    ///
+    /// Returns `None` when values are not finite or `segment_length` is nearly zero.
+    pub fn discrete(segment_length: f32, deviation: f32, seed: u32) -> Option<Self> {
+        if !(segment_length.is_finite() && deviation.is_finite()) {
+            return None;
+        }
+
+        if segment_length <= crate::SCALAR_NEARLY_ZERO {
+            return None;
+        }
+
+        Some(PathEffect {
+            kind: PathEffectKind::Discrete {
+                segment_length,
+                deviation,
+                seed,
+            },
+        })
+    }
+
+    /// Creates an effect that stamps `path` along each contour every `advance` units,
+    /// starting at `phase`.
+    ///
+    /// The result should be filled and not stroked.
+    ///
+    /// Returns `None` when `advance` is not finite or not positive or when `phase` is not finite.
+    pub fn path_1d(path: Path, advance: f32, phase: f32, style: Path1DStyle) -> Option<Self> {
+        if !(advance.is_finite() && advance > 0.0 && phase.is_finite()) {
+            return None;
+        }
+
+        // Cleanup the phase parameter, inverting it so that it becomes an
+        // offset along the path (to match the interpretation in PostScript).
+        let mut phase = phase;
+        if phase < 0.0 {
+            phase = -phase;
+            if phase > advance {
+                phase %= advance;
+            }
+        } else {
+            if phase > advance {
+                phase %= advance;
+            }
+
+            phase = advance - phase;
+        }
+
+        // Now catch the edge case where phase == advance (within epsilon).
+        if phase >= advance {
+            phase = 0.0;
+        }
+
+        Some(PathEffect {
+            kind: PathEffectKind::Path1D {
+                path,
+                advance,
+                initial_offset: phase,
+                style,
+            },
+        })
+    }
+
+    /// Creates an effect that keeps only the part of the path between
+    /// `start` and `end` fractions of its total length.
+    ///
+    /// Fractions are clamped to the 0..=1 range.
+    /// The effect produces an empty path when `start` is greater than or equal to `end`.
+    ///
+    /// Returns `None` when values are not finite.
+    pub fn trim(start: f32, end: f32) -> Option<Self> {
+        if !(start.is_finite() && end.is_finite()) {
+            return None;
+        }
+
+        Some(PathEffect {
+            kind: PathEffectKind::Trim {
+                start: start.clamp(0.0, 1.0),
+                end: end.clamp(0.0, 1.0),
+            },
+        })
+    }
+
+    /// Creates an effect that applies `inner` first and then `outer` to its result.
+    pub fn compose(outer: PathEffect, inner: PathEffect) -> Self {
+        PathEffect {
+            kind: PathEffectKind::Compose(Box::new(outer), Box::new(inner)),
+        }
+    }
+
+    /// Creates an effect that applies both effects to the original path
+    /// and combines the results.
+    pub fn sum(first: PathEffect, second: PathEffect) -> Self {
+        PathEffect {
+            kind: PathEffectKind::Sum(Box::new(first), Box::new(second)),
+        }
+    }
+}
+
+impl Path {
+    /// Applies a path effect to the current path.
+    ///
+    /// `resolution_scale` can be obtained via
+    /// [`compute_resolution_scale`](crate::PathStroker::compute_resolution_scale).
+    ///
+    /// Returns `None` when the resulting path is empty or has an invalid bounding box
+    /// or when the effect had to produce too many segments.
+    pub fn apply_effect(&self, effect: &PathEffect, resolution_scale: f32) -> Option<Path> {
+        match effect.kind {
+            PathEffectKind::Dash(ref dash) => self.dash(dash, resolution_scale),
+            PathEffectKind::Corner { radius } => corner(self, radius),
+            PathEffectKind::Discrete {
+                segment_length,
+                deviation,
+                seed,
+            } => discrete(self, segment_length, deviation, seed, resolution_scale),
+            PathEffectKind::Path1D {
+                ref path,
+                advance,
+                initial_offset,
+                style,
+            } => path_1d(self, path, advance, initial_offset, style, resolution_scale),
+            PathEffectKind::Trim { start, end } => trim(self, start, end, resolution_scale),
+            PathEffectKind::Compose(ref outer, ref inner) => self
+                .apply_effect(inner, resolution_scale)?
+                .apply_effect(outer, resolution_scale),
+            PathEffectKind::Sum(ref first, ref second) => {
+                let first = self.apply_effect(first, resolution_scale);
+                let second = self.apply_effect(second, resolution_scale);
+                match (first, second) {
+                    (Some(first), Some(second)) => {
+                        let mut pb = PathBuilder::new();
+                        pb.push_path(&first);
+                        pb.push_path(&second);
+                        pb.finish()
+                    }
+                    (Some(path), None) | (None, Some(path)) => Some(path),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+}
+
+fn corner(src: &Path, radius: f32) -> Option<Path> {
+    fn compute_step(a: Point, b: Point, radius: f32) -> (Point, bool) {
+        let dist = a.distance(b);
+        let step = b - a;
+        if dist <= radius * 2.0 {
+            (step.scaled(0.5), false)
+        } else {
+            (step.scaled(radius / dist), true)
+        }
+    }
+
+    let segments: Vec<PathSegment> = {
+        let mut iter = src.segments();
+        iter.set_auto_close(true);
+        iter.collect()
+    };
+
+    let mut pb = PathBuilder::new();
+    let mut prev_is_line = false;
+    let mut prev_is_move = false;
+    let mut prev_is_valid = true;
+    let mut move_to = Point::zero();
+    let mut last_point = Point::zero();
+    let mut last_corner = Point::zero();
+    let mut first_step = Point::zero();
+    let mut step = Point::zero();
+    for (i, segment) in segments.iter().enumerate() {
+        match *segment {
+            PathSegment::MoveTo(p) => {
+                // Close out the previous (open) contour.
+                if prev_is_line {
+                    pb.line_to(last_corner.x, last_corner.y);
+                }
+
+                let is_closed = segments[i + 1..]
+                    .iter()
+                    .take_while(|s| !matches!(s, PathSegment::MoveTo(_)))
+                    .any(|s| *s == PathSegment::Close);
+
+                if is_closed {
+                    move_to = p;
+                    prev_is_valid = false;
+                } else {
+                    pb.move_to(p.x, p.y);
+                    prev_is_valid = true;
+                }
+
+                last_point = p;
+            }
+            PathSegment::LineTo(p) => {
+                let (line_step, draw_segment) = compute_step(last_point, p, radius);
+                step = line_step;
+
+                // Previous corner.
+                if !prev_is_valid {
+                    pb.move_to(move_to.x + step.x, move_to.y + step.y);
+                    prev_is_valid = true;
+                } else {
+                    pb.quad_to(
+                        last_point.x,
+                        last_point.y,
+                        last_point.x + step.x,
+                        last_point.y + step.y,
+                    );
+                }
+
+                if draw_segment {
+                    pb.line_to(p.x - step.x, p.y - step.y);
+                }
+
+                last_corner = p;
+                last_point = p;
+            }
+            PathSegment::QuadTo(p1, p) => {
+                // Just replicate the curve for now.
+                if !prev_is_valid {
+                    pb.move_to(last_point.x, last_point.y);
+                    prev_is_valid = true;
+                }
+
+                pb.quad_to(p1.x, p1.y, p.x, p.y);
+                last_corner = p;
+                last_point = p;
+                first_step = Point::zero();
+            }
+            PathSegment::CubicTo(p1, p2, p) => {
+                if !prev_is_valid {
+                    pb.move_to(last_point.x, last_point.y);
+                    prev_is_valid = true;
+                }
+
+                pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p.x, p.y);
+                last_corner = p;
+                last_point = p;
+                first_step = Point::zero();
+            }
+            PathSegment::Close => {
+                if first_step.x != 0.0 || first_step.y != 0.0 {
+                    pb.quad_to(
+                        last_corner.x,
+                        last_corner.y,
+                        last_corner.x + first_step.x,
+                        last_corner.y + first_step.y,
+                    );
+                }
+
+                pb.close();
+                prev_is_valid = false;
+            }
+        }
+
+        if prev_is_move {
+            first_step = step;
+        }
+
+        prev_is_line = matches!(segment, PathSegment::LineTo(_));
+        prev_is_move = matches!(segment, PathSegment::MoveTo(_));
+    }
+
+    if prev_is_valid {
+        pb.line_to(last_corner.x, last_corner.y);
+    }
+
+    pb.finish()
+}
+
+// A linear congruential generator, so the output doesn't depend on a platform.
+struct LcgRandom {
+    seed: u32,
+}
+
+impl LcgRandom {
+    // Returns a number in the [-1..1) range.
+    fn next_signed_unit(&mut self) -> f32 {
+        self.seed = self.seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        // A signed 16.16 fixed point number.
+        ((self.seed as i32) >> 15) as f32 / 65536.0
+    }
+}
+
+fn discrete(
+    src: &Path,
+    segment_length: f32,
+    deviation: f32,
+    seed: u32,
+    res_scale: f32,
+) -> Option<Path> {
+    fn perturb(p: Point, tangent: Point, scale: f32) -> Point {
+        let mut normal = Point::from_xy(tangent.y, -tangent.x);
+        normal.set_length(scale);
+        p + normal
+    }
+
+    let contours: Vec<_> = PathMeasure::new(src, false, res_scale).collect();
+    let first_length = contours.first().map(|c| c.length()).unwrap_or(0.0);
+    let seed = seed ^ ((first_length + 0.5).floor() as i32 as u32);
+    let mut rand = LcgRandom {
+        seed: seed ^ seed.rotate_left(16),
+    };
+
+    let mut pb = PathBuilder::new();
+    for contour in &contours {
+        let length = contour.length();
+        if segment_length * 2.0 > length {
+            // Too short for us to mangle.
+            contour.push_segment(0.0, length, true, &mut pb);
+            continue;
+        }
+
+        let mut n =
+            ((length / segment_length + 0.5).floor() as usize).min(MAX_REASONABLE_ITERATIONS);
+        let delta = length / n as f32;
+        let mut distance = 0.0;
+        if contour.is_closed() {
+            n -= 1;
+            distance += delta * 0.5;
+        }
+
+        if let Some((p, v)) = contour.pos_tan(distance) {
+            let p = perturb(p, v, rand.next_signed_unit() * deviation);
+            pb.move_to(p.x, p.y);
+        }
+
+        for _ in 0..n {
+            distance += delta;
+            if let Some((p, v)) = contour.pos_tan(distance) {
+                let p = perturb(p, v, rand.next_signed_unit() * deviation);
+                pb.line_to(p.x, p.y);
+            }
+        }
+
+        if contour.is_closed() {
+            pb.close();
+        }
+    }
+
+    pb.finish()
+}
+
+fn path_1d(
+    src: &Path,
+    stamp: &Path,
+    advance: f32,
+    initial_offset: f32,
+    style: Path1DStyle,
+    res_scale: f32,
+) -> Option<Path> {
+    let mut pb = PathBuilder::new();
+    for contour in PathMeasure::new(src, false, res_scale) {
+        let length = contour.length();
+        let mut distance = initial_offset;
+        let mut governor = MAX_REASONABLE_ITERATIONS;
+        while distance < length {
+            if governor == 0 {
+                return None;
+            }
+            governor -= 1;
+
+            let (pos, tangent) = contour.pos_tan(distance)?;
+            match style {
+                Path1DStyle::Translate => {
+                    let ts = Transform::from_translate(pos.x, pos.y);
+                    pb.push_path(&stamp.clone().transform(ts)?);
+                }
+                Path1DStyle::Rotate => {
+                    let ts = Transform::from_row(
+                        tangent.x, tangent.y, -tangent.y, tangent.x, pos.x, pos.y,
+                    );
+                    pb.push_path(&stamp.clone().transform(ts)?);
+                }
+                Path1DStyle::Morph => morph_path(stamp, &contour, distance, &mut pb),
+            }
+
+            distance += advance;
+        }
+    }
+
+    pb.finish()
+}
+
+// Bends each stamp point, treating its x coordinate as a distance along the contour
+// and its y coordinate as an offset along the normal.
+fn morph_path(stamp: &Path, contour: &crate::ContourMeasure, distance: f32, pb: &mut PathBuilder) {
+    let morph = |p: Point| -> Option<Point> {
+        let (pos, tangent) = contour.pos_tan(distance + p.x)?;
+        Some(Point::from_xy(
+            pos.x - tangent.y * p.y,
+            pos.y + tangent.x * p.y,
+        ))
+    };
+
+    let mut iter = stamp.segments();
+    iter.set_auto_close(true);
+    let mut last = Point::zero();
+    for segment in iter {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                if let Some(p) = morph(p) {
+                    pb.move_to(p.x, p.y);
+                }
+
+                last = p;
+            }
+            PathSegment::LineTo(p) => {
+                // Lines are converted into quads, so they can bend.
+                let mid = Point::from_xy((last.x + p.x) * 0.5, (last.y + p.y) * 0.5);
+                if let (Some(p1), Some(p2)) = (morph(mid), morph(p)) {
+                    pb.quad_to(p1.x, p1.y, p2.x, p2.y);
+                }
+
+                last = p;
+            }
+            PathSegment::QuadTo(p1, p) => {
+                if let (Some(p1), Some(p2)) = (morph(p1), morph(p)) {
+                    pb.quad_to(p1.x, p1.y, p2.x, p2.y);
+                }
+
+                last = p;
+            }
+            PathSegment::CubicTo(p1, p2, p) => {
+                if let (Some(p1), Some(p2), Some(p3)) = (morph(p1), morph(p2), morph(p)) {
+                    pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+                }
+
+                last = p;
+            }
+            PathSegment::Close => pb.close(),
+        }
+    }
+}
+
+fn trim(src: &Path, start: f32, end: f32, res_scale: f32) -> Option<Path> {
+    if start >= end {
+        return None;
+    }
+
+    let contours: Vec<_> = PathMeasure::new(src, false, res_scale).collect();
+    let length: f32 = contours.iter().map(|c| c.length()).sum();
+    let start = length * start;
+    let end = length * end;
+
+    let mut pb = PathBuilder::new();
+    let mut offset = 0.0;
+    for contour in &contours {
+        let next_offset = offset + contour.length();
+        if start < next_offset {
+            contour.push_segment(start - offset, end - offset, true, &mut pb);
+            if end < next_offset {
+                break;
+            }
+        }
+
+        offset = next_offset;
+    }
+
+    pb.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rect;
+
+    fn polyline() -> Path {
+        let mut pb = PathBuilder::new();
+        pb.move_to(0.0, 0.0);
+        pb.line_to(100.0, 0.0);
+        pb.line_to(100.0, 100.0);
+        pb.finish().unwrap()
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(PathEffect::corner(0.0).is_none());
+        assert!(PathEffect::corner(f32::NAN).is_none());
+        assert!(PathEffect::discrete(0.0, 1.0, 0).is_none());
+        assert!(PathEffect::discrete(1.0, f32::INFINITY, 0).is_none());
+        assert!(PathEffect::path_1d(polyline(), 0.0, 0.0, Path1DStyle::Translate).is_none());
+        assert!(PathEffect::path_1d(polyline(), 1.0, f32::NAN, Path1DStyle::Translate).is_none());
+        assert!(PathEffect::trim(f32::NAN, 1.0).is_none());
+    }
+
+    #[test]
+    fn corner_open() {
+        let effect = PathEffect::corner(10.0).unwrap();
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(
+            path.to_svg_data(),
+            "M 0 0 Q 0 0 10 0 L 90 0 Q 100 0 100 10 L 100 90 L 100 100"
+        );
+    }
+
+    #[test]
+    fn corner_closed() {
+        let rect = PathBuilder::from_rect(Rect::from_ltrb(0.0, 0.0, 100.0, 100.0).unwrap());
+        let effect = PathEffect::corner(10.0).unwrap();
+        let path = rect.apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(
+            path.to_svg_data(),
+            "M 10 0 L 90 0 Q 100 0 100 10 L 100 90 Q 100 100 90 100 L 10 100 Q 0 100 0 90 L 0 10 \
+             Q 0 0 10 0 Z"
+        );
+    }
+
+    #[test]
+    fn corner_short_lines() {
+        let mut pb = PathBuilder::new();
+        pb.move_to(0.0, 0.0);
+        pb.line_to(10.0, 0.0);
+        pb.line_to(10.0, 10.0);
+        let path = pb.finish().unwrap();
+
+        let effect = PathEffect::corner(20.0).unwrap();
+        let path = path.apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 0 0 Q 0 0 5 0 Q 10 0 10 5 L 10 10");
+    }
+
+    #[test]
+    fn discrete() {
+        let effect = PathEffect::discrete(10.0, 3.0, 1).unwrap();
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        // 200 units long contour split into 20 lines.
+        assert_eq!(path.len(), 21);
+        assert_eq!(path.points().len(), 21);
+        for p in path.points() {
+            let dist_x = p.x.min(100.0 - p.x).abs();
+            let dist_y = p.y.abs().min((p.x - 100.0).abs());
+            assert!(dist_x <= 3.0 || dist_y <= 3.0);
+        }
+
+        // Deterministic.
+        assert_eq!(path, polyline().apply_effect(&effect, 1.0).unwrap());
+
+        let other = PathEffect::discrete(10.0, 3.0, 2).unwrap();
+        assert_ne!(path, polyline().apply_effect(&other, 1.0).unwrap());
+    }
+
+    #[test]
+    fn discrete_short() {
+        let effect = PathEffect::discrete(150.0, 3.0, 1).unwrap();
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 0 0 L 100 0 L 100 100");
+    }
+
+    fn dot() -> Path {
+        PathBuilder::from_rect(Rect::from_ltrb(-1.0, -1.0, 1.0, 1.0).unwrap())
+    }
+
+    #[test]
+    fn path_1d_translate() {
+        let effect = PathEffect::path_1d(dot(), 50.0, 0.0, Path1DStyle::Translate).unwrap();
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        // Stamps at 0, 50, 100 and 150.
+        assert_eq!(path.len(), 4 * 5);
+        assert_eq!(
+            path.bounds(),
+            Rect::from_ltrb(-1.0, -1.0, 101.0, 51.0).unwrap()
+        );
+    }
+
+    #[test]
+    fn path_1d_phase() {
+        let effect = PathEffect::path_1d(dot(), 50.0, 40.0, Path1DStyle::Translate).unwrap();
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        // Stamps at 10, 60, 110 and 160.
+        let bounds = path.bounds();
+        assert_eq!(bounds.left(), 9.0);
+        assert!((bounds.bottom() - 61.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn path_1d_rotate() {
+        let mut pb = PathBuilder::new();
+        pb.move_to(0.0, 0.0);
+        pb.line_to(0.0, 5.0);
+        let stamp = pb.finish().unwrap();
+
+        let effect = PathEffect::path_1d(stamp, 150.0, 0.0, Path1DStyle::Rotate).unwrap();
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 0 0 L 0 5 M 100 50 L 95 50");
+    }
+
+    #[test]
+    fn path_1d_morph() {
+        let mut pb = PathBuilder::new();
+        pb.move_to(90.0, 0.0);
+        pb.line_to(110.0, 0.0);
+        let stamp = pb.finish().unwrap();
+
+        let effect = PathEffect::path_1d(stamp, 200.0, 0.0, Path1DStyle::Morph).unwrap();
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 90 0 Q 100 0 100 10");
+    }
+
+    #[test]
+    fn trim() {
+        let effect = PathEffect::trim(0.25, 0.75).unwrap();
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 50 0 L 100 0 L 100 50");
+
+        let effect = PathEffect::trim(0.5, 0.5).unwrap();
+        assert!(polyline().apply_effect(&effect, 1.0).is_none());
+    }
+
+    #[test]
+    fn trim_multiple_contours() {
+        let mut pb = PathBuilder::new();
+        pb.move_to(0.0, 0.0);
+        pb.line_to(100.0, 0.0);
+        pb.move_to(0.0, 10.0);
+        pb.line_to(100.0, 10.0);
+        let path = pb.finish().unwrap();
+
+        let effect = PathEffect::trim(0.25, 0.75).unwrap();
+        let path = path.apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 50 0 L 100 0 M 0 10 L 50 10");
+    }
+
+    #[test]
+    fn compose() {
+        let trim = PathEffect::trim(0.0, 0.5).unwrap();
+        let corner = PathEffect::corner(10.0).unwrap();
+
+        // Round the corner first and then trim away the rounded part.
+        let effect = PathEffect::compose(trim.clone(), corner.clone());
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert!(path.bounds().right() < 100.0);
+
+        // Trim first, so there are no corners to round.
+        let effect = PathEffect::compose(corner, trim);
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 0 0 Q 0 0 10 0 L 90 0 L 100 0");
+    }
+
+    #[test]
+    fn sum() {
+        let first = PathEffect::trim(0.0, 0.25).unwrap();
+        let second = PathEffect::trim(0.75, 1.0).unwrap();
+        let effect = PathEffect::sum(first, second);
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 0 0 L 50 0 M 100 50 L 100 100");
+    }
+
+    #[test]
+    fn dash() {
+        let dash = StrokeDash::new(alloc::vec![10.0, 90.0], 0.0).unwrap();
+        let effect = PathEffect::dash(dash);
+        let path = polyline().apply_effect(&effect, 1.0).unwrap();
+        assert_eq!(path.to_svg_data(), "M 0 0 L 10 0 M 100 0 L 100 0 L 100 10");
+    }
+}
@@ -0,0 +1,775 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+// Unlike Skia's PathOps, curves are flattened first and the operation
+// is performed on polygons, which is way simpler.
+//
+// The algorithm:
+// 1. Flatten both paths into closed polygons with vertices snapped to a grid.
+// 2. Split all edges at their intersections. Overlapping edges are merged,
+//    while their winding contributions are accumulated. Since intersection points
+//    are snapped to the grid as well, the resulting edges can cross again,
+//    so this step is repeated until there are no new intersections.
+// 3. For each edge, compute the winding numbers of both paths on each side of it
+//    and keep the edge only when the operation result differs between sides.
+// 4. Link the remaining edges into contours and remove redundant vertices.
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{FillRule, Path, PathBuilder, PathSegment, Point};
+
+// Snapping moves points by less than a grid cell, so a couple of passes is usually enough.
+const MAX_SPLIT_PASSES: usize = 8;
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use crate::NoStdFloat;
+
+/// A path boolean operation.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PathOp {
+    /// Subtracts the second path from the first one.
+    Difference,
+    /// Intersects both paths.
+    Intersect,
+    /// Unions both paths.
+    Union,
+    /// Keeps the area covered by exactly one path.
+    Xor,
+    /// Subtracts the first path from the second one.
+    ReverseDifference,
+}
+
+impl PathOp {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            PathOp::Difference => a && !b,
+            PathOp::Intersect => a && b,
+            PathOp::Union => a || b,
+            PathOp::Xor => a != b,
+            PathOp::ReverseDifference => b && !a,
+        }
+    }
+}
+
+impl Path {
+    /// Applies a boolean operation to two paths.
+    ///
+    /// `fill_rule` and `other_fill_rule` define the filled area of `self` and `other`
+    /// respectively. Open contours are treated as closed, just like during filling.
+    ///
+    /// The resulting path is simplified, see [`Path::simplify`] for details.
+    ///
+    /// Returns `None` when the resulting area is empty.
+    pub fn op(
+        &self,
+        other: &Path,
+        op: PathOp,
+        fill_rule: FillRule,
+        other_fill_rule: FillRule,
+        resolution_scale: f32,
+    ) -> Option<Path> {
+        boolean(
+            &[(self, fill_rule), (other, other_fill_rule)],
+            op,
+            resolution_scale,
+        )
+    }
+
+    /// Resolves self-intersections and overlapping contours.
+    ///
+    /// The resulting path consists only of closed, non-intersecting contours,
+    /// therefore it fills the same area using any `FillRule`.
+    /// Outer contours are clockwise, just like the ones produced by
+    /// [`PathBuilder::from_rect`], while holes are counter-clockwise.
+    ///
+    /// Quadratic and cubic curves are flattened, therefore the result contains only lines.
+    /// Just like with stroking, the flattening precision depends on `resolution_scale`,
+    /// which can be obtained via
+    /// [`compute_resolution_scale`](crate::PathStroker::compute_resolution_scale).
+    /// Make sure to use the transform the result will be drawn with,
+    /// otherwise scaled up curves will look faceted.
+    ///
+    /// Returns `None` when the resulting area is empty.
+    pub fn simplify(&self, fill_rule: FillRule, resolution_scale: f32) -> Option<Path> {
+        boolean(&[(self, fill_rule)], PathOp::Union, resolution_scale)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+struct Vec2 {
+    x: f64,
+    y: f64,
+}
+
+impl Vec2 {
+    fn new(x: f64, y: f64) -> Self {
+        Vec2 { x, y }
+    }
+
+    fn swapped(self) -> Self {
+        Vec2::new(self.y, self.x)
+    }
+
+    fn dot(self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    fn cross(self, other: Self) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+}
+
+impl From<Point> for Vec2 {
+    fn from(p: Point) -> Self {
+        Vec2::new(f64::from(p.x), f64::from(p.y))
+    }
+}
+
+impl core::ops::Add for Vec2 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl core::ops::Sub for Vec2 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl core::ops::Mul<f64> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, s: f64) -> Self {
+        Vec2::new(self.x * s, self.y * s)
+    }
+}
+
+// Vertices snapped to a grid, so nearly identical points become the same vertex.
+struct Vertices {
+    grid: f64,
+    points: Vec<Vec2>,
+    map: BTreeMap<(i64, i64), usize>,
+}
+
+impl Vertices {
+    fn insert(&mut self, p: Vec2) -> usize {
+        let key = (
+            (p.x / self.grid).round() as i64,
+            (p.y / self.grid).round() as i64,
+        );
+
+        // Points close to a cell boundary may end up in a neighbouring cell.
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                if let Some(idx) = self.map.get(&(key.0 + dx, key.1 + dy)) {
+                    if (self.points[*idx] - p).length() <= self.grid {
+                        return *idx;
+                    }
+                }
+            }
+        }
+
+        let points = &mut self.points;
+        let grid = self.grid;
+        *self.map.entry(key).or_insert_with(|| {
+            points.push(Vec2::new(key.0 as f64 * grid, key.1 as f64 * grid));
+            points.len() - 1
+        })
+    }
+}
+
+struct Segment {
+    from: usize,
+    to: usize,
+    // Winding contribution of each operand in the `from` -> `to` direction.
+    wind: [i32; 2],
+    // Vertices lying on the segment, with their position along it.
+    splits: Vec<(f64, usize)>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    from: usize,
+    to: usize,
+    // Winding contribution of each operand in the `from` -> `to` direction.
+    wind: [i32; 2],
+}
+
+fn boolean(inputs: &[(&Path, FillRule)], op: PathOp, resolution_scale: f32) -> Option<Path> {
+    if !(resolution_scale > 0.0 && resolution_scale.is_finite()) {
+        return None;
+    }
+
+    let mut left = f64::MAX;
+    let mut top = f64::MAX;
+    let mut right = f64::MIN;
+    let mut bottom = f64::MIN;
+    for (path, _) in inputs {
+        let bounds = path.bounds();
+        left = left.min(f64::from(bounds.left()));
+        top = top.min(f64::from(bounds.top()));
+        right = right.max(f64::from(bounds.right()));
+        bottom = bottom.max(f64::from(bounds.bottom()));
+    }
+
+    let size = (right - left).max(bottom - top);
+    if !(size > 0.0 && size.is_finite()) {
+        return None;
+    }
+
+    // A twentieth of a pixel, but not too coarse for tiny paths either.
+    let tolerance = (size * 1e-3).min(0.05 / f64::from(resolution_scale));
+    // Round down to a power of two, so integer coordinates stay exact.
+    let grid = f64::from_bits((size * 1e-7).to_bits() & 0xFFF0_0000_0000_0000);
+
+    let mut vertices = Vertices {
+        grid,
+        points: Vec::new(),
+        map: BTreeMap::new(),
+    };
+
+    let mut segments = Vec::new();
+    for (operand, (path, _)) in inputs.iter().enumerate() {
+        for contour in flatten(path, tolerance) {
+            let mut indices: Vec<usize> = Vec::with_capacity(contour.len());
+            for p in contour {
+                let idx = vertices.insert(p);
+                if indices.last() != Some(&idx) {
+                    indices.push(idx);
+                }
+            }
+
+            for (i, from) in indices.iter().enumerate() {
+                let to = indices[(i + 1) % indices.len()];
+                if *from != to {
+                    let mut wind = [0; 2];
+                    wind[operand] = 1;
+                    segments.push(Segment {
+                        from: *from,
+                        to,
+                        wind,
+                        splits: Vec::new(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut edges;
+    let mut pass = 1;
+    loop {
+        let has_splits = split_segments(&mut segments, &mut vertices);
+        edges = merge_segments(&segments, &vertices.points);
+        if !has_splits || pass == MAX_SPLIT_PASSES {
+            break;
+        }
+
+        // Check that snapped intersection points didn't introduce new crossings.
+        segments = edges
+            .iter()
+            .map(|edge| Segment {
+                from: edge.from,
+                to: edge.to,
+                wind: edge.wind,
+                splits: Vec::new(),
+            })
+            .collect();
+        pass += 1;
+    }
+
+    let directed = select_edges(&edges, &vertices.points, inputs, op);
+    let contours = link_edges(&directed, &vertices.points, grid * 2.0);
+
+    let mut pb = PathBuilder::new();
+    for contour in contours {
+        pb.move_to(contour[0].x as f32, contour[0].y as f32);
+        for p in &contour[1..] {
+            pb.line_to(p.x as f32, p.y as f32);
+        }
+        pb.close();
+    }
+
+    pb.finish()
+}
+
+// Converts a path into a list of implicitly closed polygons.
+fn flatten(path: &Path, tolerance: f64) -> Vec<Vec<Vec2>> {
+    // Prevents excessive subdivision of huge curves.
+    const MAX_STEPS: f64 = 1000.0;
+
+    let mut contours = Vec::new();
+    let mut contour = Vec::new();
+    for segment in path.segments() {
+        let p0 = contour.last().copied().unwrap_or_default();
+        match segment {
+            PathSegment::MoveTo(p) => {
+                if contour.len() > 1 {
+                    contours.push(core::mem::take(&mut contour));
+                }
+
+                contour.clear();
+                contour.push(Vec2::from(p));
+            }
+            PathSegment::LineTo(p) => contour.push(Vec2::from(p)),
+            PathSegment::QuadTo(p1, p2) => {
+                let (p1, p2) = (Vec2::from(p1), Vec2::from(p2));
+                let dd = (p0 - p1 * 2.0 + p2).length();
+                let steps = (dd / (4.0 * tolerance)).sqrt().ceil().clamp(1.0, MAX_STEPS);
+                for i in 1..=steps as usize {
+                    let t = i as f64 / steps;
+                    let mt = 1.0 - t;
+                    contour.push(p0 * (mt * mt) + p1 * (2.0 * mt * t) + p2 * (t * t));
+                }
+            }
+            PathSegment::CubicTo(p1, p2, p3) => {
+                let (p1, p2, p3) = (Vec2::from(p1), Vec2::from(p2), Vec2::from(p3));
+                let dd = (p0 - p1 * 2.0 + p2)
+                    .length()
+                    .max((p1 - p2 * 2.0 + p3).length());
+                let steps = (dd * 3.0 / (4.0 * tolerance))
+                    .sqrt()
+                    .ceil()
+                    .clamp(1.0, MAX_STEPS);
+                for i in 1..=steps as usize {
+                    let t = i as f64 / steps;
+                    let mt = 1.0 - t;
+                    contour.push(
+                        p0 * (mt * mt * mt)
+                            + p1 * (3.0 * mt * mt * t)
+                            + p2 * (3.0 * mt * t * t)
+                            + p3 * (t * t * t),
+                    );
+                }
+            }
+            PathSegment::Close => {
+                if contour.len() > 1 {
+                    contours.push(core::mem::take(&mut contour));
+                }
+
+                contour.clear();
+            }
+        }
+    }
+
+    if contour.len() > 1 {
+        contours.push(contour);
+    }
+
+    contours
+}
+
+// Finds all intersections between segments and records them as split vertices.
+//
+// Returns `false` when there are no intersections.
+fn split_segments(segments: &mut [Segment], vertices: &mut Vertices) -> bool {
+    let grid = vertices.grid;
+    let points = &vertices.points;
+    let min_x = |s: &Segment| points[s.from].x.min(points[s.to].x);
+
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by(|a, b| {
+        min_x(&segments[*a])
+            .partial_cmp(&min_x(&segments[*b]))
+            .unwrap_or(core::cmp::Ordering::Equal)
+    });
+
+    // (segment, position, vertex or a new point)
+    let mut splits: Vec<(usize, f64, Result<usize, Vec2>)> = Vec::new();
+    for (i, a) in order.iter().enumerate() {
+        let (p0, p1) = (points[segments[*a].from], points[segments[*a].to]);
+        let max_x = p0.x.max(p1.x) + grid;
+        for b in &order[i + 1..] {
+            let (q0, q1) = (points[segments[*b].from], points[segments[*b].to]);
+            if q0.x.min(q1.x) > max_x {
+                break;
+            }
+
+            if q0.y.min(q1.y) > p0.y.max(p1.y) + grid || q0.y.max(q1.y) + grid < p0.y.min(p1.y) {
+                continue;
+            }
+
+            let (sa, sb) = (&segments[*a], &segments[*b]);
+            let vertices_a = [sa.from, sa.to];
+            let vertices_b = [sb.from, sb.to];
+
+            // Signed distances of each segment endpoints to the other segment line.
+            let dist_a = [line_dist(q0, q1, p0), line_dist(q0, q1, p1)];
+            let dist_b = [line_dist(p0, p1, q0), line_dist(p0, p1, q1)];
+
+            // Endpoints touching the other segment, which also covers collinear segments.
+            for (k, d) in dist_b.iter().enumerate() {
+                if d.abs() <= grid {
+                    if let Some(t) = inner_position(p0, p1, points[vertices_b[k]], grid) {
+                        splits.push((*a, t, Ok(vertices_b[k])));
+                    }
+                }
+            }
+
+            for (k, d) in dist_a.iter().enumerate() {
+                if d.abs() <= grid {
+                    if let Some(t) = inner_position(q0, q1, points[vertices_a[k]], grid) {
+                        splits.push((*b, t, Ok(vertices_a[k])));
+                    }
+                }
+            }
+
+            // A proper crossing.
+            let min_dist = dist_a[0]
+                .abs()
+                .min(dist_a[1].abs())
+                .min(dist_b[0].abs())
+                .min(dist_b[1].abs());
+            if min_dist > grid
+                && (dist_a[0] < 0.0) != (dist_a[1] < 0.0)
+                && (dist_b[0] < 0.0) != (dist_b[1] < 0.0)
+            {
+                let t = dist_a[0] / (dist_a[0] - dist_a[1]);
+                let u = dist_b[0] / (dist_b[0] - dist_b[1]);
+                let p = p0 + (p1 - p0) * t;
+                splits.push((*a, t, Err(p)));
+                splits.push((*b, u, Err(p)));
+            }
+        }
+    }
+
+    let has_splits = !splits.is_empty();
+
+    // Crossing points are shared by two splits, therefore must be inserted in pairs.
+    let mut last_point = None;
+    for (segment, t, vertex) in splits {
+        let idx = match vertex {
+            Ok(idx) => idx,
+            Err(p) => match last_point {
+                Some((q, idx)) if q == p => idx,
+                _ => {
+                    let idx = vertices.insert(p);
+                    last_point = Some((p, idx));
+                    idx
+                }
+            },
+        };
+
+        segments[segment].splits.push((t, idx));
+    }
+
+    has_splits
+}
+
+// Returns a signed distance from `p` to the line going through `a` and `b`.
+fn line_dist(a: Vec2, b: Vec2, p: Vec2) -> f64 {
+    (b - a).cross(p - a) / (b - a).length()
+}
+
+// Returns the position of `p` projected onto a segment,
+// unless it's outside of the segment or too close to its ends.
+fn inner_position(a: Vec2, b: Vec2, p: Vec2, grid: f64) -> Option<f64> {
+    let d = b - a;
+    let len = d.length();
+    let pos = d.dot(p - a) / len;
+    if pos > grid && pos < len - grid {
+        Some(pos / len)
+    } else {
+        None
+    }
+}
+
+// Splits segments into edges, while merging the overlapping ones.
+fn merge_segments(segments: &[Segment], points: &[Vec2]) -> Vec<Edge> {
+    let mut map: BTreeMap<(usize, usize), [i32; 2]> = BTreeMap::new();
+    let mut chain = Vec::new();
+    for segment in segments {
+        let mut splits = segment.splits.clone();
+        splits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(core::cmp::Ordering::Equal));
+
+        chain.clear();
+        chain.push(segment.from);
+        chain.extend(splits.iter().map(|s| s.1));
+        chain.push(segment.to);
+        chain.dedup();
+
+        for pair in chain.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let (key, sign) = if from < to {
+                ((from, to), 1)
+            } else {
+                ((to, from), -1)
+            };
+            let wind = map.entry(key).or_insert([0, 0]);
+            wind[0] += sign * segment.wind[0];
+            wind[1] += sign * segment.wind[1];
+        }
+    }
+
+    map.into_iter()
+        .filter(|(_, wind)| *wind != [0, 0])
+        .filter(|((from, to), _)| points[*from] != points[*to])
+        .map(|((from, to), wind)| Edge { from, to, wind })
+        .collect()
+}
+
+// Returns edges separating the resulting area from the outside,
+// oriented so the area is on the right side (in a Y-down coordinate system).
+fn select_edges(
+    edges: &[Edge],
+    points: &[Vec2],
+    inputs: &[(&Path, FillRule)],
+    op: PathOp,
+) -> Vec<(usize, usize)> {
+    let is_inside = |winding: [i32; 2]| -> bool {
+        let mut inside = [false; 2];
+        for (i, (_, fill_rule)) in inputs.iter().enumerate() {
+            inside[i] = match fill_rule {
+                FillRule::Winding => winding[i] != 0,
+                FillRule::EvenOdd => winding[i] % 2 != 0,
+            };
+        }
+
+        op.apply(inside[0], inside[1])
+    };
+
+    let horizontal_rays = EdgeIndex::new(edges, points, false);
+    let vertical_rays = EdgeIndex::new(edges, points, true);
+
+    let mut directed = Vec::new();
+    for (i, edge) in edges.iter().enumerate() {
+        let (a, b) = (points[edge.from], points[edge.to]);
+        let mid = (a + b) * 0.5;
+        let dir = b - a;
+
+        // A ray cast from the edge midpoint gives the winding number on the side
+        // the ray goes into. The other side differs by the edge winding.
+        let (winding, is_left) = if dir.y.abs() >= dir.x.abs() {
+            (horizontal_rays.winding(edges, points, mid, i), dir.y < 0.0)
+        } else {
+            (vertical_rays.winding(edges, points, mid, i), dir.x > 0.0)
+        };
+
+        let mut left = winding;
+        let mut right = winding;
+        for k in 0..2 {
+            if is_left {
+                right[k] -= edge.wind[k];
+            } else {
+                left[k] += edge.wind[k];
+            }
+        }
+
+        match (is_inside(left), is_inside(right)) {
+            (true, false) => directed.push((edge.from, edge.to)),
+            (false, true) => directed.push((edge.to, edge.from)),
+            _ => {}
+        }
+    }
+
+    directed
+}
+
+// Edges grouped into bands, to speed up ray casting.
+struct EdgeIndex {
+    // Cast vertical rays instead of horizontal ones, by swapping coordinates.
+    swap: bool,
+    min: f64,
+    band_size: f64,
+    bands: Vec<Vec<usize>>,
+}
+
+impl EdgeIndex {
+    fn new(edges: &[Edge], points: &[Vec2], swap: bool) -> Self {
+        let coord = |idx: usize| -> f64 {
+            if swap {
+                points[idx].x
+            } else {
+                points[idx].y
+            }
+        };
+
+        let mut min = f64::MAX;
+        let mut max = f64::MIN;
+        for edge in edges {
+            min = min.min(coord(edge.from)).min(coord(edge.to));
+            max = max.max(coord(edge.from)).max(coord(edge.to));
+        }
+
+        let count = ((edges.len() as f64).sqrt() as usize).max(1);
+        let band_size = ((max - min) / count as f64).max(f64::MIN_POSITIVE);
+        let mut index = EdgeIndex {
+            swap,
+            min,
+            band_size,
+            bands: vec![Vec::new(); count],
+        };
+
+        for (i, edge) in edges.iter().enumerate() {
+            let (a, b) = (coord(edge.from), coord(edge.to));
+            for band in index.band(a.min(b))..=index.band(a.max(b)) {
+                index.bands[band].push(i);
+            }
+        }
+
+        index
+    }
+
+    fn band(&self, v: f64) -> usize {
+        let band = ((v - self.min) / self.band_size) as isize;
+        band.clamp(0, self.bands.len() as isize - 1) as usize
+    }
+
+    // Computes winding numbers at `p` using a ray going to the positive X (or Y) direction.
+    fn winding(&self, edges: &[Edge], points: &[Vec2], p: Vec2, skip: usize) -> [i32; 2] {
+        let map = |p: Vec2| if self.swap { p.swapped() } else { p };
+        let p = map(p);
+
+        let mut winding = [0; 2];
+        for i in &self.bands[self.band(p.y)] {
+            if *i == skip {
+                continue;
+            }
+
+            let edge = &edges[*i];
+            let (a, b) = (map(points[edge.from]), map(points[edge.to]));
+            let side = (b - a).cross(p - a);
+            let dir = if a.y <= p.y && b.y > p.y && side > 0.0 {
+                1
+            } else if b.y <= p.y && a.y > p.y && side < 0.0 {
+                -1
+            } else {
+                continue;
+            };
+
+            winding[0] += dir * edge.wind[0];
+            winding[1] += dir * edge.wind[1];
+        }
+
+        // Swapping coordinates flips the orientation.
+        if self.swap {
+            winding[0] = -winding[0];
+            winding[1] = -winding[1];
+        }
+
+        winding
+    }
+}
+
+// Links directed edges into contours.
+fn link_edges(directed: &[(usize, usize)], points: &[Vec2], tolerance: f64) -> Vec<Vec<Vec2>> {
+    let mut outgoing = vec![Vec::new(); points.len()];
+    for (i, (from, _)) in directed.iter().enumerate() {
+        outgoing[*from].push(i);
+    }
+
+    let mut used = vec![false; directed.len()];
+    let mut contours = Vec::new();
+    for start in 0..directed.len() {
+        if used[start] {
+            continue;
+        }
+
+        let first = directed[start].0;
+        let mut contour = Vec::new();
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let (from, to) = directed[current];
+            contour.push(points[from]);
+            if to == first {
+                break;
+            }
+
+            // On a vertex shared by multiple contours, take the sharpest turn
+            // towards the filled area.
+            let dir = points[to] - points[from];
+            let next = outgoing[to]
+                .iter()
+                .filter(|i| !used[**i])
+                .map(|i| (*i, turn_angle(dir, points[directed[*i].1] - points[to])))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(core::cmp::Ordering::Equal));
+
+            match next {
+                Some((i, _)) => current = i,
+                None => break, // should not happen
+            }
+        }
+
+        remove_redundant_points(&mut contour, tolerance);
+        if contour.len() < 3 {
+            continue;
+        }
+
+        // Start from the top-left point to make the output stable.
+        let mut first = 0;
+        for (i, p) in contour.iter().enumerate() {
+            let f = contour[first];
+            if p.y < f.y || (p.y == f.y && p.x < f.x) {
+                first = i;
+            }
+        }
+        contour.rotate_left(first);
+
+        contours.push(contour);
+    }
+
+    contours.sort_by(|a, b| {
+        (a[0].y, a[0].x)
+            .partial_cmp(&(b[0].y, b[0].x))
+            .unwrap_or(core::cmp::Ordering::Equal)
+    });
+
+    contours
+}
+
+// Returns a value monotonic to the angle between two vectors, in a (-2, 2] range.
+//
+// Positive values are turns in the positive Y direction, when moving along `a`.
+fn turn_angle(a: Vec2, b: Vec2) -> f64 {
+    let x = a.dot(b);
+    let y = a.cross(b);
+    let len = x.abs() + y.abs();
+    if len == 0.0 {
+        return 0.0;
+    }
+
+    if y >= 0.0 {
+        // [0, 2]
+        1.0 - x / len
+    } else {
+        // (-2, 0)
+        x / len - 1.0
+    }
+}
+
+// Removes points lying on a straight line between their neighbours.
+fn remove_redundant_points(points: &mut Vec<Vec2>, tolerance: f64) {
+    let mut i = 0;
+    while points.len() >= 3 && i < points.len() {
+        let len = points.len();
+        let prev = points[(i + len - 1) % len];
+        let curr = points[i];
+        let next = points[(i + 1) % len];
+
+        let d = next - prev;
+        let is_redundant = curr == prev
+            || ((d.cross(curr - prev).abs() <= tolerance * d.length())
+                && (curr - prev).dot(d) > 0.0
+                && (next - curr).dot(d) > 0.0);
+
+        if is_redundant {
+            points.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+}
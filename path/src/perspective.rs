@@ -0,0 +1,714 @@
+// Copyright 2006 The Android Open Source Project
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use crate::path_geometry;
+use crate::scalar::{Scalar, SCALAR_NEARLY_ZERO};
+use crate::{NormalizedF32Exclusive, Path, PathBuilder, PathSegment, Point, Transform};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use crate::NoStdFloat;
+
+// The maximum allowed deviation of a projected cubic in device pixels.
+const CUBIC_TOLERANCE: f32 = 0.25;
+const MAX_CUBIC_SUBDIVISIONS: usize = 64;
+// Paths are clipped to `w >= W_CLIP` before projection, just like `kW0PlaneDistance` in Skia.
+const W_CLIP: f32 = 1.0 / 16384.0;
+
+/// A projective transformation matrix.
+///
+/// Extends [`Transform`] with a perspective row, so a point is mapped as:
+///
+/// ```text
+/// w  = px * x + py * y + pz
+/// x' = (sx * x + kx * y + tx) / w
+/// y' = (ky * x + sy * y + ty) / w
+/// ```
+///
+/// Just like `Transform`, doesn't guarantee to be valid.
+#[allow(missing_docs)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PerspectiveTransform {
+    pub sx: f32,
+    pub kx: f32,
+    pub ky: f32,
+    pub sy: f32,
+    pub tx: f32,
+    pub ty: f32,
+    pub px: f32,
+    pub py: f32,
+    pub pz: f32,
+}
+
+impl Default for PerspectiveTransform {
+    fn default() -> Self {
+        Transform::default().into()
+    }
+}
+
+impl From<Transform> for PerspectiveTransform {
+    fn from(ts: Transform) -> Self {
+        Self::from_row(ts.sx, ts.ky, ts.kx, ts.sy, ts.tx, ts.ty, 0.0, 0.0, 1.0)
+    }
+}
+
+impl PerspectiveTransform {
+    /// Creates an identity transform.
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `PerspectiveTransform`.
+    ///
+    /// Uses the same order as [`Transform::from_row`], followed by the perspective row.
+    pub const fn from_row(
+        sx: f32,
+        ky: f32,
+        kx: f32,
+        sy: f32,
+        tx: f32,
+        ty: f32,
+        px: f32,
+        py: f32,
+        pz: f32,
+    ) -> Self {
+        Self {
+            sx,
+            kx,
+            ky,
+            sy,
+            tx,
+            ty,
+            px,
+            py,
+            pz,
+        }
+    }
+
+    /// Creates a transform that maps one quadrilateral onto another.
+    ///
+    /// Points are matched by index. Returns `None` when any of the quadrilaterals
+    /// is degenerate, i.e. has three collinear points.
+    pub fn from_quad_to_quad(src: &[Point; 4], dst: &[Point; 4]) -> Option<Self> {
+        let src = square_to_quad(src)?.invert()?;
+        let dst = square_to_quad(dst)?;
+        let ts = dst.pre_concat(src);
+        if ts.is_finite() {
+            Some(ts)
+        } else {
+            None
+        }
+    }
+
+    /// Checks that transform is finite.
+    pub fn is_finite(&self) -> bool {
+        self.sx.is_finite()
+            && self.ky.is_finite()
+            && self.kx.is_finite()
+            && self.sy.is_finite()
+            && self.tx.is_finite()
+            && self.ty.is_finite()
+            && self.px.is_finite()
+            && self.py.is_finite()
+            && self.pz.is_finite()
+    }
+
+    /// Checks that transform is identity.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Checks that transform doesn't have a perspective component.
+    pub fn is_affine(&self) -> bool {
+        self.px == 0.0 && self.py == 0.0 && self.pz == 1.0
+    }
+
+    /// Returns an affine part of the transform.
+    ///
+    /// Returns `None` when the transform has a perspective component.
+    pub fn to_affine(&self) -> Option<Transform> {
+        if self.is_affine() {
+            Some(Transform::from_row(
+                self.sx, self.ky, self.kx, self.sy, self.tx, self.ty,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Pre-concats the current transform.
+    #[must_use]
+    pub fn pre_concat(&self, other: Self) -> Self {
+        concat(*self, other)
+    }
+
+    /// Post-concats the current transform.
+    #[must_use]
+    pub fn post_concat(&self, other: Self) -> Self {
+        concat(other, *self)
+    }
+
+    /// Returns a homogeneous `w` coordinate of a mapped point.
+    ///
+    /// Points with a non-positive `w` are behind the viewer
+    /// and cannot be mapped in a meaningful way.
+    pub fn map_w(&self, point: Point) -> f32 {
+        self.px * point.x + self.py * point.y + self.pz
+    }
+
+    /// Transforms a point using the current transform.
+    pub fn map_point(&self, point: &mut Point) {
+        self.map_points(core::slice::from_mut(point));
+    }
+
+    /// Transforms a slice of points using the current transform.
+    pub fn map_points(&self, points: &mut [Point]) {
+        if let Some(ts) = self.to_affine() {
+            ts.map_points(points);
+            return;
+        }
+
+        for p in points {
+            let w = self.map_w(*p);
+            let w = if w != 0.0 { w.invert() } else { w };
+            let x = (p.x * self.sx + p.y * self.kx + self.tx) * w;
+            let y = (p.x * self.ky + p.y * self.sy + self.ty) * w;
+            p.x = x;
+            p.y = y;
+        }
+    }
+
+    /// Returns an inverted transform.
+    pub fn invert(&self) -> Option<Self> {
+        if let Some(ts) = self.to_affine() {
+            return ts.invert().map(Self::from);
+        }
+
+        let m = self.to_f64();
+        let det = determinant(&m)?;
+        let [a, b, c, d, e, f, g, h, i] = m;
+
+        let inv_det = 1.0 / det;
+        let ts = Self::from_f64([
+            (e * i - f * h) * inv_det,
+            (c * h - b * i) * inv_det,
+            (b * f - c * e) * inv_det,
+            (f * g - d * i) * inv_det,
+            (a * i - c * g) * inv_det,
+            (c * d - a * f) * inv_det,
+            (d * h - e * g) * inv_det,
+            (b * g - a * h) * inv_det,
+            (a * e - b * d) * inv_det,
+        ]);
+
+        if ts.is_finite() {
+            Some(ts)
+        } else {
+            None
+        }
+    }
+
+    // Returns the matrix in the row-major order.
+    fn to_f64(self) -> [f64; 9] {
+        [
+            f64::from(self.sx),
+            f64::from(self.kx),
+            f64::from(self.tx),
+            f64::from(self.ky),
+            f64::from(self.sy),
+            f64::from(self.ty),
+            f64::from(self.px),
+            f64::from(self.py),
+            f64::from(self.pz),
+        ]
+    }
+
+    fn from_f64(m: [f64; 9]) -> Self {
+        Self::from_row(
+            m[0] as f32,
+            m[3] as f32,
+            m[1] as f32,
+            m[4] as f32,
+            m[2] as f32,
+            m[5] as f32,
+            m[6] as f32,
+            m[7] as f32,
+            m[8] as f32,
+        )
+    }
+}
+
+// Returns a matrix determinant, unless it's nearly zero.
+fn determinant(m: &[f64; 9]) -> Option<f64> {
+    let [a, b, c, d, e, f, g, h, i] = *m;
+    let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+
+    // See `Transform::invert` for the tolerance explanation.
+    let tolerance = SCALAR_NEARLY_ZERO * SCALAR_NEARLY_ZERO * SCALAR_NEARLY_ZERO;
+    if (det as f32).is_nearly_zero_within_tolerance(tolerance) {
+        None
+    } else {
+        Some(det)
+    }
+}
+
+fn concat(a: PerspectiveTransform, b: PerspectiveTransform) -> PerspectiveTransform {
+    if let (Some(a), Some(b)) = (a.to_affine(), b.to_affine()) {
+        return a.pre_concat(b).into();
+    }
+
+    let a = a.to_f64();
+    let b = b.to_f64();
+    let mut m = [0.0; 9];
+    for row in 0..3 {
+        for col in 0..3 {
+            m[row * 3 + col] =
+                a[row * 3] * b[col] + a[row * 3 + 1] * b[3 + col] + a[row * 3 + 2] * b[6 + col];
+        }
+    }
+
+    PerspectiveTransform::from_f64(m)
+}
+
+// Maps a unit square onto a quadrilateral.
+//
+// Based on "Fundamentals of Texture Mapping and Image Warping" by Paul Heckbert.
+fn square_to_quad(quad: &[Point; 4]) -> Option<PerspectiveTransform> {
+    let [p0, p1, p2, p3] = quad.map(|p| (f64::from(p.x), f64::from(p.y)));
+
+    let dx1 = p1.0 - p2.0;
+    let dy1 = p1.1 - p2.1;
+    let dx2 = p3.0 - p2.0;
+    let dy2 = p3.1 - p2.1;
+    let dx3 = p0.0 - p1.0 + p2.0 - p3.0;
+    let dy3 = p0.1 - p1.1 + p2.1 - p3.1;
+
+    let det = dx1 * dy2 - dx2 * dy1;
+    if (det as f32).is_nearly_zero() {
+        return None;
+    }
+
+    let g = (dx3 * dy2 - dx2 * dy3) / det;
+    let h = (dx1 * dy3 - dx3 * dy1) / det;
+
+    let m = [
+        p1.0 - p0.0 + g * p1.0,
+        p3.0 - p0.0 + h * p3.0,
+        p0.0,
+        p1.1 - p0.1 + g * p1.1,
+        p3.1 - p0.1 + h * p3.1,
+        p0.1,
+        g,
+        h,
+        1.0,
+    ];
+
+    // Three collinear points produce a singular matrix.
+    determinant(&m)?;
+    Some(PerspectiveTransform::from_f64(m))
+}
+
+impl Path {
+    /// Returns a path projected using the specified perspective transform.
+    ///
+    /// Lines are mapped exactly, quadratic curves are converted into conics
+    /// and cubic curves are subdivided until the error is below a quarter of a pixel.
+    ///
+    /// Parts of the path behind the viewer are clipped away. Closed contours stay closed,
+    /// while open ones are split.
+    ///
+    /// Returns `None` when the path is completely behind the viewer
+    /// or when the resulting path is invalid.
+    pub fn transform_perspective(self, ts: PerspectiveTransform) -> Option<Self> {
+        if let Some(ts) = ts.to_affine() {
+            return self.transform(ts);
+        }
+
+        // `w` is interpolated just like points, therefore when all points are
+        // in front of the viewer, so are the curves.
+        let path = if self.points.iter().all(|p| ts.map_w(*p) >= W_CLIP) {
+            self
+        } else {
+            clip_to_w(&self, ts)?
+        };
+
+        let map = |p: Point| -> Option<(Point, f32)> {
+            let w = ts.map_w(p);
+            if !(w > 0.0) {
+                return None;
+            }
+
+            let mut p = p;
+            ts.map_point(&mut p);
+            Some((p, w))
+        };
+
+        let mut pb = PathBuilder::with_capacity(path.verbs.len(), path.points.len());
+        let mut last = Point::zero();
+        for segment in path.segments() {
+            match segment {
+                PathSegment::MoveTo(p) => {
+                    let (mp, _) = map(p)?;
+                    pb.move_to(mp.x, mp.y);
+                    last = p;
+                }
+                PathSegment::LineTo(p) => {
+                    let (mp, _) = map(p)?;
+                    pb.line_to(mp.x, mp.y);
+                    last = p;
+                }
+                PathSegment::QuadTo(p1, p2) => {
+                    // A projected quad is a conic.
+                    let (_, w0) = map(last)?;
+                    let (mp1, w1) = map(p1)?;
+                    let (mp2, w2) = map(p2)?;
+                    let weight = w1 / (w0 * w2).sqrt();
+                    pb.conic_points_to(mp1, mp2, weight);
+                    last = p2;
+                }
+                PathSegment::CubicTo(p1, p2, p3) => {
+                    push_cubic(&mut pb, [last, p1, p2, p3], &map)?;
+                    last = p3;
+                }
+                PathSegment::Close => pb.close(),
+            }
+        }
+
+        pb.finish()
+    }
+}
+
+// A projected cubic is a rational cubic, which we do not support.
+// Instead, it is split into pieces small enough to be approximated with regular cubics.
+fn push_cubic(
+    pb: &mut PathBuilder,
+    points: [Point; 4],
+    map: &dyn Fn(Point) -> Option<(Point, f32)>,
+) -> Option<()> {
+    let mut mapped = [Point::zero(); 4];
+    let mut min_w = f32::MAX;
+    let mut max_w = 0.0f32;
+    for (i, p) in points.iter().enumerate() {
+        let (mp, w) = map(*p)?;
+        mapped[i] = mp;
+        min_w = min_w.min(w);
+        max_w = max_w.max(w);
+    }
+
+    // The error of mapping just the control points is roughly proportional
+    // to the curve size and the `w` variation and decreases quadratically with subdivision.
+    let len = mapped[0].distance(mapped[1])
+        + mapped[1].distance(mapped[2])
+        + mapped[2].distance(mapped[3]);
+    let error = len * (max_w / min_w - 1.0);
+    let count = (error / CUBIC_TOLERANCE).sqrt().ceil();
+    let count = if count.is_finite() {
+        (count as usize).clamp(1, MAX_CUBIC_SUBDIVISIONS)
+    } else {
+        MAX_CUBIC_SUBDIVISIONS
+    };
+
+    let mut rest = points;
+    for i in 0..count {
+        let piece = if i + 1 == count {
+            rest
+        } else {
+            let t = NormalizedF32Exclusive::new_bounded(1.0 / (count - i) as f32);
+            let mut chopped = [Point::zero(); 7];
+            path_geometry::chop_cubic_at2(&rest, t, &mut chopped);
+            rest = [chopped[3], chopped[4], chopped[5], chopped[6]];
+            [chopped[0], chopped[1], chopped[2], chopped[3]]
+        };
+
+        let (p1, _) = map(piece[1])?;
+        let (p2, _) = map(piece[2])?;
+        let (p3, _) = map(piece[3])?;
+        pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+    }
+
+    Some(())
+}
+
+// Clips the path to the `w >= W_CLIP` half-plane, which is bounded by a line
+// in the path coordinates.
+//
+// Parts of closed contours behind the viewer are replaced with lines along that line,
+// so the visible part of the fill is preserved. Open contours are split instead.
+fn clip_to_w(path: &Path, ts: PerspectiveTransform) -> Option<Path> {
+    let mut pb = PathBuilder::new();
+    let mut segments: Vec<([Point; 4], usize)> = Vec::new();
+    let mut start = Point::zero();
+    let mut last = Point::zero();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                clip_contour(&mut pb, &segments, false, ts);
+                segments.clear();
+                start = p;
+                last = p;
+            }
+            PathSegment::LineTo(p) => {
+                segments.push(([last, p, p, p], 2));
+                last = p;
+            }
+            PathSegment::QuadTo(p1, p2) => {
+                segments.push(([last, p1, p2, p2], 3));
+                last = p2;
+            }
+            PathSegment::CubicTo(p1, p2, p3) => {
+                segments.push(([last, p1, p2, p3], 4));
+                last = p3;
+            }
+            PathSegment::Close => {
+                if last != start {
+                    segments.push(([last, start, start, start], 2));
+                }
+
+                clip_contour(&mut pb, &segments, true, ts);
+                segments.clear();
+                last = start;
+            }
+        }
+    }
+
+    clip_contour(&mut pb, &segments, false, ts);
+    pb.finish()
+}
+
+fn clip_contour(
+    pb: &mut PathBuilder,
+    segments: &[([Point; 4], usize)],
+    closed: bool,
+    ts: PerspectiveTransform,
+) {
+    let mut is_started = false;
+    let mut is_inside = false;
+    let mut first = Point::zero();
+    for (index, (points, count)) in segments.iter().enumerate() {
+        let points = &points[..*count];
+        let mut w = [0.0; 4];
+        for (w, p) in w.iter_mut().zip(points) {
+            *w = ts.map_w(*p) - W_CLIP;
+        }
+
+        let (roots, roots_count) = find_bezier_roots(&w[..*count]);
+        let mut rest = [Point::zero(); 4];
+        rest[..*count].copy_from_slice(points);
+        let mut prev_t = 0.0;
+        for i in 0..=roots_count {
+            let piece = if let Some(t) = roots[..roots_count].get(i) {
+                let (left, right) = chop_bezier(&rest[..*count], (t - prev_t) / (1.0 - prev_t));
+                prev_t = *t;
+                rest = right;
+                left
+            } else {
+                rest
+            };
+            let piece = &piece[..*count];
+
+            // Roots are the only points where the curve crosses the clipping line,
+            // so the middle point is enough to check the whole piece.
+            let w_mid = ts.map_w(eval_bezier(piece, 0.5));
+            if !(w_mid >= W_CLIP) {
+                is_inside = false;
+                continue;
+            }
+
+            if !is_inside {
+                if is_started && closed {
+                    // Follow the clipping line.
+                    pb.line_to(piece[0].x, piece[0].y);
+                } else {
+                    pb.move_to(piece[0].x, piece[0].y);
+                    first = piece[0];
+                    is_started = true;
+                }
+                is_inside = true;
+            }
+
+            let is_last = index + 1 == segments.len() && i == roots_count;
+            match piece {
+                // Leave the closing line to `close`.
+                [_, p1] if closed && is_last && *p1 == first => {}
+                [_, p1] => pb.line_to(p1.x, p1.y),
+                [_, p1, p2] => pb.quad_to(p1.x, p1.y, p2.x, p2.y),
+                [_, p1, p2, p3] => pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y),
+                _ => {}
+            }
+        }
+    }
+
+    if is_started && closed {
+        pb.close();
+    }
+}
+
+// Finds sorted `t` values in the (0, 1) range where a 1D Bezier curve crosses zero.
+fn find_bezier_roots(values: &[f32]) -> ([f32; 3], usize) {
+    // Split the curve into monotonic intervals first.
+    let mut bounds = [0.0; 4];
+    let mut bounds_count = 1;
+    match *values {
+        [a, b, c] => {
+            if let Some(t) = path_geometry::valid_unit_divide(a - b, a - b - b + c) {
+                bounds[bounds_count] = t.get();
+                bounds_count += 1;
+            }
+        }
+        [a, b, c, d] => {
+            // The derivative is a quadratic Bezier curve with `b - a`, `c - b` and `d - c` values.
+            let (d0, d1, d2) = (b - a, c - b, d - c);
+            let mut extrema = path_geometry::new_t_values();
+            let count = path_geometry::find_unit_quad_roots(
+                d0 - 2.0 * d1 + d2,
+                2.0 * (d1 - d0),
+                d0,
+                &mut extrema,
+            );
+            for t in &extrema[..count] {
+                bounds[bounds_count] = t.get();
+                bounds_count += 1;
+            }
+        }
+        _ => {}
+    }
+    bounds[bounds_count] = 1.0;
+
+    let mut roots = [0.0; 3];
+    let mut roots_count = 0;
+    for interval in bounds[..=bounds_count].windows(2) {
+        let (mut lo, mut hi) = (interval[0], interval[1]);
+        let (v_lo, v_hi) = (eval_bezier_1d(values, lo), eval_bezier_1d(values, hi));
+        if v_lo == 0.0 || (v_lo < 0.0) == (v_hi < 0.0) {
+            continue;
+        }
+
+        // A simple bisection, since the interval is monotonic.
+        for _ in 0..32 {
+            let mid = (lo + hi) * 0.5;
+            if (eval_bezier_1d(values, mid) < 0.0) == (v_lo < 0.0) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let t = (lo + hi) * 0.5;
+        if t > 0.0 && t < 1.0 && roots_count < roots.len() {
+            roots[roots_count] = t;
+            roots_count += 1;
+        }
+    }
+
+    (roots, roots_count)
+}
+
+fn eval_bezier_1d(values: &[f32], t: f32) -> f32 {
+    let mut tmp = [0.0; 4];
+    tmp[..values.len()].copy_from_slice(values);
+    for level in 1..values.len() {
+        for i in 0..values.len() - level {
+            tmp[i] += (tmp[i + 1] - tmp[i]) * t;
+        }
+    }
+
+    tmp[0]
+}
+
+fn eval_bezier(points: &[Point], t: f32) -> Point {
+    let mut x = [0.0; 4];
+    let mut y = [0.0; 4];
+    for (i, p) in points.iter().enumerate() {
+        x[i] = p.x;
+        y[i] = p.y;
+    }
+
+    let n = points.len();
+    Point::from_xy(eval_bezier_1d(&x[..n], t), eval_bezier_1d(&y[..n], t))
+}
+
+// Splits a Bezier curve of any degree using de Casteljau's algorithm.
+fn chop_bezier(points: &[Point], t: f32) -> ([Point; 4], [Point; 4]) {
+    let n = points.len();
+    let mut tmp = [Point::zero(); 4];
+    tmp[..n].copy_from_slice(points);
+
+    let mut left = [Point::zero(); 4];
+    let mut right = [Point::zero(); 4];
+    left[0] = tmp[0];
+    right[n - 1] = tmp[n - 1];
+    for level in 1..n {
+        for i in 0..n - level {
+            tmp[i] = Point::from_xy(
+                tmp[i].x + (tmp[i + 1].x - tmp[i].x) * t,
+                tmp[i].y + (tmp[i + 1].y - tmp[i].y) * t,
+            );
+        }
+
+        left[level] = tmp[0];
+        right[n - 1 - level] = tmp[n - 1 - level];
+    }
+
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bezier_roots() {
+        let (roots, count) = find_bezier_roots(&[1.0, -1.0]);
+        assert_eq!(count, 1);
+        assert!((roots[0] - 0.5).abs() < 0.0001);
+
+        // Crosses zero twice.
+        let (roots, count) = find_bezier_roots(&[1.0, -3.0, 1.0]);
+        assert_eq!(count, 2);
+        for t in &roots[..count] {
+            assert!(eval_bezier_1d(&[1.0, -3.0, 1.0], *t).abs() < 0.0001);
+        }
+
+        assert_eq!(find_bezier_roots(&[1.0, -4.0, 4.0, -1.0]).1, 3);
+        assert_eq!(find_bezier_roots(&[1.0, 0.5, 0.5, 1.0]).1, 0);
+    }
+
+    #[test]
+    fn chop_bezier_at_half() {
+        let points = [
+            Point::from_xy(0.0, 0.0),
+            Point::from_xy(10.0, 20.0),
+            Point::from_xy(30.0, 20.0),
+            Point::from_xy(40.0, 0.0),
+        ];
+        let (left, right) = chop_bezier(&points, 0.5);
+        let mid = eval_bezier(&points, 0.5);
+        assert_eq!(left[0], points[0]);
+        assert_eq!(left[3], mid);
+        assert_eq!(right[0], mid);
+        assert_eq!(right[3], points[3]);
+    }
+
+    #[test]
+    fn invert_round_trip() {
+        let ts = PerspectiveTransform::from_row(1.5, 0.2, -0.3, 0.8, 10.0, 20.0, 0.001, 0.002, 1.0);
+        let inv = ts.invert().unwrap();
+        let mut p = Point::from_xy(30.0, 40.0);
+        ts.map_point(&mut p);
+        inv.map_point(&mut p);
+        assert!((p.x - 30.0).abs() < 0.001);
+        assert!((p.y - 40.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn affine_concat() {
+        let a = Transform::from_row(2.0, 0.0, 0.0, 3.0, 5.0, 7.0);
+        let b = Transform::from_rotate(30.0);
+        let ts = PerspectiveTransform::from(a).pre_concat(b.into());
+        assert_eq!(ts.to_affine(), Some(a.pre_concat(b)));
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright 2012 Google Inc.
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use crate::{Point, Rect, Transform};
+
+/// A rounded rectangle.
+///
+/// Each corner is a quarter of an ellipse with its own X and Y radii.
+///
+/// # Guarantees
+///
+/// - All radii are finite and non-negative.
+/// - A corner has either both radii positive or both zero.
+/// - Radii of adjacent corners fit the rectangle side.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RRect {
+    rect: Rect,
+    // Upper-left, upper-right, lower-right, lower-left.
+    radii: [Point; 4],
+}
+
+impl RRect {
+    /// Creates a new `RRect` with square corners.
+    pub fn from_rect(rect: Rect) -> Self {
+        RRect {
+            rect,
+            radii: [Point::zero(); 4],
+        }
+    }
+
+    /// Creates a new `RRect` with the same radii for all corners.
+    ///
+    /// See [`RRect::from_rect_radii`] for details.
+    pub fn from_rect_xy(rect: Rect, rx: f32, ry: f32) -> Option<Self> {
+        Self::from_rect_radii(rect, [Point::from_xy(rx, ry); 4])
+    }
+
+    /// Creates a new `RRect` from an oval bounds.
+    pub fn from_oval(oval: Rect) -> Self {
+        let radii = Point::from_xy(oval.width() * 0.5, oval.height() * 0.5);
+        Self::from_rect_radii(oval, [radii; 4]).unwrap_or(Self::from_rect(oval))
+    }
+
+    /// Creates a new `RRect` with per-corner radii.
+    ///
+    /// `radii` are in the upper-left, upper-right, lower-right and lower-left order,
+    /// where `x` is the horizontal radius and `y` is the vertical one.
+    ///
+    /// A corner with a zero or negative radius becomes square.
+    /// When the radii of adjacent corners do not fit the rectangle side,
+    /// all radii are scaled down proportionally, just like in CSS.
+    ///
+    /// Returns `None` when any radius is not finite.
+    pub fn from_rect_radii(rect: Rect, radii: [Point; 4]) -> Option<Self> {
+        if !radii.iter().all(|r| r.is_finite()) {
+            return None;
+        }
+
+        let mut rrect = RRect { rect, radii };
+        if rect.width() == 0.0 || rect.height() == 0.0 {
+            rrect.radii = [Point::zero(); 4];
+            return Some(rrect);
+        }
+
+        if clamp_to_zero(&mut rrect.radii) {
+            return Some(rrect);
+        }
+
+        rrect.scale_radii();
+        Some(rrect)
+    }
+
+    // Based on SkRRect::scaleRadii.
+    fn scale_radii(&mut self) {
+        // Proportionally scale down all radii to fit. Find the minimum ratio
+        // of a side and the radii on that side (for all four sides) and use
+        // that to scale down _all_ the radii. This algorithm is from the
+        // W3 spec (http://www.w3.org/TR/css3-background/) section 5.5.
+        let width = self.rect.right() as f64 - self.rect.left() as f64;
+        let height = self.rect.bottom() as f64 - self.rect.top() as f64;
+
+        let r = &mut self.radii;
+        let mut scale = 1.0;
+        scale = compute_min_scale(r[0].x, r[1].x, width, scale);
+        scale = compute_min_scale(r[1].y, r[2].y, height, scale);
+        scale = compute_min_scale(r[2].x, r[3].x, width, scale);
+        scale = compute_min_scale(r[3].y, r[0].y, height, scale);
+
+        (r[0].x, r[1].x) = flush_to_zero(r[0].x, r[1].x);
+        (r[1].y, r[2].y) = flush_to_zero(r[1].y, r[2].y);
+        (r[2].x, r[3].x) = flush_to_zero(r[2].x, r[3].x);
+        (r[3].y, r[0].y) = flush_to_zero(r[3].y, r[0].y);
+
+        if scale < 1.0 {
+            (r[0].x, r[1].x) = adjust_radii(width, scale, r[0].x, r[1].x);
+            (r[1].y, r[2].y) = adjust_radii(height, scale, r[1].y, r[2].y);
+            (r[2].x, r[3].x) = adjust_radii(width, scale, r[2].x, r[3].x);
+            (r[3].y, r[0].y) = adjust_radii(height, scale, r[3].y, r[0].y);
+        }
+
+        clamp_to_zero(r);
+    }
+
+    /// Returns the bounds.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns corner radii.
+    ///
+    /// In the upper-left, upper-right, lower-right and lower-left order.
+    pub fn radii(&self) -> [Point; 4] {
+        self.radii
+    }
+
+    /// Checks that all corners are square.
+    pub fn is_rect(&self) -> bool {
+        self.radii.iter().all(|r| *r == Point::zero())
+    }
+
+    /// Checks that the point is inside the rounded rectangle, including its edges.
+    pub fn contains(&self, p: Point) -> bool {
+        let r = &self.rect;
+        if !(p.x >= r.left() && p.x <= r.right() && p.y >= r.top() && p.y <= r.bottom()) {
+            return false;
+        }
+
+        self.check_corner_containment(p.x, p.y)
+    }
+
+    /// Checks that the rectangle is fully inside the rounded rectangle.
+    pub fn contains_rect(&self, rect: &Rect) -> bool {
+        let r = &self.rect;
+        if !(rect.left() >= r.left()
+            && rect.top() >= r.top()
+            && rect.right() <= r.right()
+            && rect.bottom() <= r.bottom())
+        {
+            return false;
+        }
+
+        // We know that rect is inside the bounds, so we only need to check the corners.
+        self.check_corner_containment(rect.left(), rect.top())
+            && self.check_corner_containment(rect.right(), rect.top())
+            && self.check_corner_containment(rect.right(), rect.bottom())
+            && self.check_corner_containment(rect.left(), rect.bottom())
+    }
+
+    // Assumes that the point is inside the bounds.
+    fn check_corner_containment(&self, x: f32, y: f32) -> bool {
+        let r = &self.rect;
+        let [ul, ur, lr, ll] = self.radii;
+
+        let (index, center) = if x < r.left() + ul.x && y < r.top() + ul.y {
+            (0, Point::from_xy(r.left() + ul.x, r.top() + ul.y))
+        } else if x > r.right() - ur.x && y < r.top() + ur.y {
+            (1, Point::from_xy(r.right() - ur.x, r.top() + ur.y))
+        } else if x > r.right() - lr.x && y > r.bottom() - lr.y {
+            (2, Point::from_xy(r.right() - lr.x, r.bottom() - lr.y))
+        } else if x < r.left() + ll.x && y > r.bottom() - ll.y {
+            (3, Point::from_xy(r.left() + ll.x, r.bottom() - ll.y))
+        } else {
+            // Not in any of the corners.
+            return true;
+        };
+
+        let canonical = Point::from_xy(x - center.x, y - center.y);
+
+        // A point is in an ellipse (in standard position) if:
+        //      x^2 / a^2 + y^2 / b^2 <= 1
+        // or   x^2 * b^2 + y^2 * a^2 <= a^2 * b^2
+        let radii = self.radii[index];
+        let dist = canonical.x * canonical.x * radii.y * radii.y
+            + canonical.y * canonical.y * radii.x * radii.x;
+        dist <= (radii.x * radii.y) * (radii.x * radii.y)
+    }
+
+    /// Insets the rounded rectangle by the specified offset.
+    ///
+    /// Rounded corners radii are reduced by the same offset
+    /// and become square when reaching zero. Square corners stay square.
+    ///
+    /// Returns `None` when the rectangle becomes inverted.
+    pub fn inset(&self, dx: f32, dy: f32) -> Option<Self> {
+        let rect = self.rect.inset(dx, dy)?;
+
+        let mut radii = self.radii;
+        for r in &mut radii {
+            if r.x != 0.0 {
+                r.x -= dx;
+            }
+
+            if r.y != 0.0 {
+                r.y -= dy;
+            }
+        }
+
+        Self::from_rect_radii(rect, radii)
+    }
+
+    /// Outsets the rounded rectangle by the specified offset.
+    ///
+    /// Rounded corners radii are increased by the same offset.
+    /// Square corners stay square.
+    pub fn outset(&self, dx: f32, dy: f32) -> Option<Self> {
+        self.inset(-dx, -dy)
+    }
+
+    /// Transforms the rounded rectangle using the provided `Transform`.
+    ///
+    /// Only scale and translate transforms are supported,
+    /// since anything else cannot be represented by an `RRect`.
+    pub fn transform(&self, ts: Transform) -> Option<Self> {
+        if ts.is_identity() {
+            return Some(*self);
+        }
+
+        if ts.has_skew() {
+            return None;
+        }
+
+        let mut points = [
+            Point::from_xy(self.rect.left(), self.rect.top()),
+            Point::from_xy(self.rect.right(), self.rect.bottom()),
+        ];
+        ts.map_points(&mut points);
+        let rect = Rect::from_points(&points)?;
+
+        let mut radii = self.radii;
+        for r in &mut radii {
+            r.x *= ts.sx.abs();
+            r.y *= ts.sy.abs();
+        }
+
+        // Negative scale flips corners.
+        if ts.sx < 0.0 {
+            radii.swap(0, 1);
+            radii.swap(2, 3);
+        }
+
+        if ts.sy < 0.0 {
+            radii.swap(0, 3);
+            radii.swap(1, 2);
+        }
+
+        Self::from_rect_radii(rect, radii)
+    }
+}
+
+// Sets both radii of a corner to zero when any of them is not positive.
+// Returns true when all corners are square.
+fn clamp_to_zero(radii: &mut [Point; 4]) -> bool {
+    let mut all_corners_square = true;
+    for r in radii {
+        if !(r.x > 0.0 && r.y > 0.0) {
+            *r = Point::zero();
+        } else {
+            all_corners_square = false;
+        }
+    }
+
+    all_corners_square
+}
+
+fn compute_min_scale(rad1: f32, rad2: f32, limit: f64, cur_min: f64) -> f64 {
+    let sum = rad1 as f64 + rad2 as f64;
+    if sum > limit {
+        cur_min.min(limit / sum)
+    } else {
+        cur_min
+    }
+}
+
+fn flush_to_zero(a: f32, b: f32) -> (f32, f32) {
+    debug_assert!(a >= 0.0);
+    debug_assert!(b >= 0.0);
+    if a + b == a {
+        (a, 0.0)
+    } else if a + b == b {
+        (0.0, b)
+    } else {
+        (a, b)
+    }
+}
+
+// Scales radii, making sure that their sum doesn't exceed the limit.
+//
+// Based on SkScaleToSides::AdjustRadii.
+fn adjust_radii(limit: f64, scale: f64, a: f32, b: f32) -> (f32, f32) {
+    let a = (a as f64 * scale) as f32;
+    let b = (b as f64 * scale) as f32;
+    if a as f64 + b as f64 <= limit {
+        return (a, b);
+    }
+
+    // Shrink the larger radius until the sum fits.
+    let min_radius = a.min(b);
+    let mut max_radius = (limit - min_radius as f64) as f32;
+    while max_radius as f64 + min_radius as f64 > limit {
+        max_radius = f32::from_bits(max_radius.to_bits() - 1);
+    }
+
+    if a > b {
+        (max_radius, min_radius)
+    } else {
+        (min_radius, max_radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect::from_xywh(x, y, w, h).unwrap()
+    }
+
+    #[test]
+    fn square_corners() {
+        let radii = [
+            Point::from_xy(10.0, 0.0),
+            Point::from_xy(-10.0, 10.0),
+            Point::from_xy(10.0, 10.0),
+            Point::from_xy(0.0, 0.0),
+        ];
+        let rrect = RRect::from_rect_radii(rect(0.0, 0.0, 100.0, 50.0), radii).unwrap();
+        assert_eq!(
+            rrect.radii(),
+            [
+                Point::zero(),
+                Point::zero(),
+                Point::from_xy(10.0, 10.0),
+                Point::zero()
+            ]
+        );
+    }
+
+    #[test]
+    fn non_finite_radii() {
+        assert!(RRect::from_rect_xy(rect(0.0, 0.0, 10.0, 10.0), f32::NAN, 1.0).is_none());
+        assert!(RRect::from_rect_xy(rect(0.0, 0.0, 10.0, 10.0), 1.0, f32::INFINITY).is_none());
+    }
+
+    #[test]
+    fn flipped_transform() {
+        let radii = [
+            Point::from_xy(1.0, 1.0),
+            Point::from_xy(2.0, 2.0),
+            Point::from_xy(3.0, 3.0),
+            Point::from_xy(4.0, 4.0),
+        ];
+        let rrect = RRect::from_rect_radii(rect(0.0, 0.0, 20.0, 20.0), radii).unwrap();
+        let rrect = rrect.transform(Transform::from_scale(-2.0, 1.0)).unwrap();
+        assert_eq!(rrect.rect(), rect(-40.0, 0.0, 40.0, 20.0));
+        assert_eq!(
+            rrect.radii(),
+            [
+                Point::from_xy(4.0, 2.0),
+                Point::from_xy(2.0, 1.0),
+                Point::from_xy(8.0, 4.0),
+                Point::from_xy(6.0, 3.0)
+            ]
+        );
+    }
+}
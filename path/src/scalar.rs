@@ -89,6 +89,11 @@ pub trait NoStdFloat {
     fn round(self) -> Self;
     fn powf(self, y: Self) -> Self;
     fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn tan(self) -> Self;
+    fn exp(self) -> Self;
+    fn log2(self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
 }
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
@@ -123,6 +128,26 @@ impl NoStdFloat for f32 {
     fn acos(self) -> Self {
         libm::acosf(self)
     }
+    fn atan2(self, other: Self) -> Self {
+        libm::atan2f(self, other)
+    }
+    fn tan(self) -> Self {
+        libm::tanf(self)
+    }
+    fn exp(self) -> Self {
+        libm::expf(self)
+    }
+    fn log2(self) -> Self {
+        libm::log2f(self)
+    }
+    fn rem_euclid(self, rhs: Self) -> Self {
+        let r = libm::fmodf(self, rhs);
+        if r < 0.0 {
+            r + rhs.abs()
+        } else {
+            r
+        }
+    }
 }
 
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
@@ -157,6 +182,26 @@ impl NoStdFloat for f64 {
     fn acos(self) -> Self {
         libm::acos(self)
     }
+    fn atan2(self, other: Self) -> Self {
+        libm::atan2(self, other)
+    }
+    fn tan(self) -> Self {
+        libm::tan(self)
+    }
+    fn exp(self) -> Self {
+        libm::exp(self)
+    }
+    fn log2(self) -> Self {
+        libm::log2(self)
+    }
+    fn rem_euclid(self, rhs: Self) -> Self {
+        let r = libm::fmod(self, rhs);
+        if r < 0.0 {
+            r + rhs.abs()
+        } else {
+            r
+        }
+    }
 }
 
 #[cfg(test)]
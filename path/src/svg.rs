@@ -0,0 +1,451 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::string::String;
+
+use crate::{Path, PathBuilder, PathSegment, Point};
+
+/// An SVG path data parsing error.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SvgPathError {
+    /// Error kind.
+    pub kind: SvgPathErrorKind,
+    /// A byte offset in the path data at which the error occurred.
+    pub position: usize,
+}
+
+/// An SVG path data parsing error kind.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SvgPathErrorKind {
+    /// The path data doesn't start with a `M` or `m` command.
+    MissingMoveTo,
+    /// An unknown command or an unexpected character.
+    UnexpectedCharacter,
+    /// A number was expected.
+    ExpectedNumber,
+    /// A number cannot be represented as a finite `f32`.
+    InvalidNumber,
+    /// An arc flag, which is either `0` or `1`, was expected.
+    ExpectedFlag,
+    /// The path has no segments besides `MoveTo`.
+    EmptyPath,
+}
+
+impl core::fmt::Display for SvgPathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let msg = match self.kind {
+            SvgPathErrorKind::MissingMoveTo => "path data must start with a MoveTo command",
+            SvgPathErrorKind::UnexpectedCharacter => "unexpected character",
+            SvgPathErrorKind::ExpectedNumber => "expected a number",
+            SvgPathErrorKind::InvalidNumber => "invalid number",
+            SvgPathErrorKind::ExpectedFlag => "expected an arc flag",
+            SvgPathErrorKind::EmptyPath => "path has no segments",
+        };
+
+        write!(f, "{} at position {}", msg, self.position)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SvgPathError {}
+
+impl Path {
+    /// Parses SVG path data, aka the `d` attribute.
+    ///
+    /// All SVG path commands are supported, including relative, smooth curve and arc ones.
+    /// Arcs are converted into quadratic curves.
+    ///
+    /// Unlike SVG, which renders a path up to the first error,
+    /// returns an error when the path data is malformed.
+    pub fn from_svg_data(text: &str) -> Result<Self, SvgPathError> {
+        let mut s = Stream {
+            data: text.as_bytes(),
+            pos: 0,
+        };
+
+        let mut pb = PathBuilder::new();
+        // The current point and the start of the current subpath.
+        let mut current = Point::zero();
+        let mut start = Point::zero();
+        // The second control point of the previous segment when it was a cubic or a quad.
+        let mut prev_cubic: Option<Point> = None;
+        let mut prev_quad: Option<Point> = None;
+        let mut prev_cmd: Option<u8> = None;
+
+        loop {
+            s.skip_spaces();
+            let Some(c) = s.curr() else {
+                break;
+            };
+
+            let cmd_pos = s.pos;
+            let cmd = if is_command(c) {
+                s.pos += 1;
+                c
+            } else if is_number_start(c) {
+                // Repeated command arguments.
+                match prev_cmd {
+                    Some(b'M') => b'L',
+                    Some(b'm') => b'l',
+                    Some(b'Z') | Some(b'z') | None => {
+                        return Err(s.error(SvgPathErrorKind::UnexpectedCharacter))
+                    }
+                    Some(cmd) => cmd,
+                }
+            } else {
+                return Err(s.error(SvgPathErrorKind::UnexpectedCharacter));
+            };
+
+            if prev_cmd.is_none() && cmd != b'M' && cmd != b'm' {
+                return Err(SvgPathError {
+                    kind: SvgPathErrorKind::MissingMoveTo,
+                    position: cmd_pos,
+                });
+            }
+
+            let relative = cmd.is_ascii_lowercase();
+            let offset = if relative { current } else { Point::zero() };
+            let mut cubic_ctrl = None;
+            let mut quad_ctrl = None;
+            match cmd.to_ascii_uppercase() {
+                b'M' => {
+                    let p = s.parse_point(offset)?;
+                    pb.move_to(p.x, p.y);
+                    current = p;
+                    start = p;
+                }
+                b'L' => {
+                    current = s.parse_point(offset)?;
+                    pb.line_to(current.x, current.y);
+                }
+                b'H' => {
+                    current.x = s.parse_number()? + offset.x;
+                    pb.line_to(current.x, current.y);
+                }
+                b'V' => {
+                    current.y = s.parse_number()? + offset.y;
+                    pb.line_to(current.x, current.y);
+                }
+                b'C' => {
+                    let p1 = s.parse_point(offset)?;
+                    let p2 = s.parse_point(offset)?;
+                    current = s.parse_point(offset)?;
+                    pb.cubic_to(p1.x, p1.y, p2.x, p2.y, current.x, current.y);
+                    cubic_ctrl = Some(p2);
+                }
+                b'S' => {
+                    let p1 = reflect(prev_cubic, current);
+                    let p2 = s.parse_point(offset)?;
+                    current = s.parse_point(offset)?;
+                    pb.cubic_to(p1.x, p1.y, p2.x, p2.y, current.x, current.y);
+                    cubic_ctrl = Some(p2);
+                }
+                b'Q' => {
+                    let p1 = s.parse_point(offset)?;
+                    current = s.parse_point(offset)?;
+                    pb.quad_to(p1.x, p1.y, current.x, current.y);
+                    quad_ctrl = Some(p1);
+                }
+                b'T' => {
+                    let p1 = reflect(prev_quad, current);
+                    current = s.parse_point(offset)?;
+                    pb.quad_to(p1.x, p1.y, current.x, current.y);
+                    quad_ctrl = Some(p1);
+                }
+                b'A' => {
+                    let rx = s.parse_number()?;
+                    let ry = s.parse_number()?;
+                    let x_axis_rotation = s.parse_number()?;
+                    let large_arc = s.parse_flag()?;
+                    let sweep = s.parse_flag()?;
+                    current = s.parse_point(offset)?;
+                    pb.arc_to(
+                        rx,
+                        ry,
+                        x_axis_rotation,
+                        large_arc,
+                        sweep,
+                        current.x,
+                        current.y,
+                    );
+                }
+                _ => {
+                    // Z
+                    pb.close();
+                    current = start;
+                }
+            }
+
+            prev_cubic = cubic_ctrl;
+            prev_quad = quad_ctrl;
+            prev_cmd = Some(cmd);
+        }
+
+        pb.finish().ok_or(SvgPathError {
+            kind: SvgPathErrorKind::EmptyPath,
+            position: text.len(),
+        })
+    }
+
+    /// Writes the path as SVG path data.
+    ///
+    /// Only absolute commands are used. Numbers are written with enough precision
+    /// to be parsed back into the same path using [`Path::from_svg_data`].
+    pub fn to_svg_data(&self) -> String {
+        use core::fmt::Write;
+
+        let mut s = String::new();
+        for segment in self.segments() {
+            // Writing to a `String` never fails.
+            let _ = match segment {
+                PathSegment::MoveTo(p) => write!(s, "M {} {} ", p.x, p.y),
+                PathSegment::LineTo(p) => write!(s, "L {} {} ", p.x, p.y),
+                PathSegment::QuadTo(p1, p) => write!(s, "Q {} {} {} {} ", p1.x, p1.y, p.x, p.y),
+                PathSegment::CubicTo(p1, p2, p) => {
+                    write!(s, "C {} {} {} {} {} {} ", p1.x, p1.y, p2.x, p2.y, p.x, p.y)
+                }
+                PathSegment::Close => write!(s, "Z "),
+            };
+        }
+
+        s.pop(); // ' '
+        s
+    }
+}
+
+fn is_command(c: u8) -> bool {
+    matches!(
+        c.to_ascii_uppercase(),
+        b'M' | b'L' | b'H' | b'V' | b'C' | b'S' | b'Q' | b'T' | b'A' | b'Z'
+    )
+}
+
+fn is_number_start(c: u8) -> bool {
+    c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.')
+}
+
+// Reflects the previous control point around the current point.
+fn reflect(ctrl: Option<Point>, current: Point) -> Point {
+    match ctrl {
+        Some(p) => Point::from_xy(2.0 * current.x - p.x, 2.0 * current.y - p.y),
+        None => current,
+    }
+}
+
+struct Stream<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Stream<'_> {
+    fn curr(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn error(&self, kind: SvgPathErrorKind) -> SvgPathError {
+        SvgPathError {
+            kind,
+            position: self.pos,
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0C') = self.curr() {
+            self.pos += 1;
+        }
+    }
+
+    // Skips spaces and an optional comma.
+    fn skip_separator(&mut self) {
+        self.skip_spaces();
+        if self.curr() == Some(b',') {
+            self.pos += 1;
+            self.skip_spaces();
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.curr(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.pos - start
+    }
+
+    fn parse_number(&mut self) -> Result<f32, SvgPathError> {
+        self.skip_separator();
+        let start = self.pos;
+
+        if let Some(b'-' | b'+') = self.curr() {
+            self.pos += 1;
+        }
+
+        let mut digits = self.skip_digits();
+        if self.curr() == Some(b'.') {
+            self.pos += 1;
+            digits += self.skip_digits();
+        }
+
+        if digits == 0 {
+            self.pos = start;
+            return Err(self.error(SvgPathErrorKind::ExpectedNumber));
+        }
+
+        // An exponent is parsed only when followed by digits.
+        if let Some(b'e' | b'E') = self.curr() {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if let Some(b'-' | b'+') = self.curr() {
+                self.pos += 1;
+            }
+
+            if self.skip_digits() == 0 {
+                self.pos = mantissa_end;
+            }
+        }
+
+        // Contains only ASCII characters, therefore slicing is safe.
+        let text = core::str::from_utf8(&self.data[start..self.pos]).unwrap_or_default();
+        match text.parse::<f32>() {
+            Ok(n) if n.is_finite() => Ok(n),
+            _ => Err(SvgPathError {
+                kind: SvgPathErrorKind::InvalidNumber,
+                position: start,
+            }),
+        }
+    }
+
+    fn parse_point(&mut self, offset: Point) -> Result<Point, SvgPathError> {
+        let x = self.parse_number()?;
+        let y = self.parse_number()?;
+        Ok(Point::from_xy(x + offset.x, y + offset.y))
+    }
+
+    // Flags don't require separators, so `a1 1 0 00 1 1` is valid.
+    fn parse_flag(&mut self) -> Result<bool, SvgPathError> {
+        self.skip_separator();
+        let flag = match self.curr() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error(SvgPathErrorKind::ExpectedFlag)),
+        };
+
+        self.pos += 1;
+        Ok(flag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> String {
+        Path::from_svg_data(text).unwrap().to_svg_data()
+    }
+
+    fn error(text: &str) -> (SvgPathErrorKind, usize) {
+        let e = Path::from_svg_data(text).unwrap_err();
+        (e.kind, e.position)
+    }
+
+    #[test]
+    fn absolute() {
+        assert_eq!(
+            parse("M10 20 L30 40 H50 V60 Q1 2 3 4 C5 6 7 8 9 10 Z"),
+            "M 10 20 L 30 40 L 50 40 L 50 60 Q 1 2 3 4 C 5 6 7 8 9 10 Z"
+        );
+    }
+
+    #[test]
+    fn relative() {
+        assert_eq!(
+            parse("m10 20 l10 10 h5 v-5 q1 2 3 4 c1 1 2 2 3 3 z l1 1"),
+            "M 10 20 L 20 30 L 25 30 L 25 25 Q 26 27 28 29 C 29 30 30 31 31 32 Z M 10 20 L 11 21"
+        );
+    }
+
+    #[test]
+    fn implicit_commands() {
+        assert_eq!(parse("M10,20 30,40 50 60"), "M 10 20 L 30 40 L 50 60");
+        assert_eq!(parse("m10 20 5 5"), "M 10 20 L 15 25");
+        assert_eq!(parse("M0 0L1 1 2 2"), "M 0 0 L 1 1 L 2 2");
+    }
+
+    #[test]
+    fn compact_numbers() {
+        assert_eq!(
+            parse("M.5.5L-1-1e1 1E+1,2e-1"),
+            "M 0.5 0.5 L -1 -10 L 10 0.2"
+        );
+    }
+
+    #[test]
+    fn smooth_curves() {
+        assert_eq!(
+            parse("M0 0 C10 0 20 10 20 20 S30 40 40 40"),
+            "M 0 0 C 10 0 20 10 20 20 C 20 30 30 40 40 40"
+        );
+        assert_eq!(
+            parse("M0 0 Q10 0 10 10 T20 20 t0 10"),
+            "M 0 0 Q 10 0 10 10 Q 10 20 20 20 Q 30 20 20 30"
+        );
+        // Without a previous curve the current point is used.
+        assert_eq!(
+            parse("M0 0 L5 5 S10 10 20 20"),
+            "M 0 0 L 5 5 C 5 5 10 10 20 20"
+        );
+        assert_eq!(parse("M0 0 T10 10"), "M 0 0 Q 0 0 10 10");
+    }
+
+    #[test]
+    fn arcs() {
+        let path = Path::from_svg_data("M10 50 A40 40 0 0 1 90 50").unwrap();
+        let bounds = path.bounds();
+        assert!((bounds.left() - 10.0).abs() < 0.01);
+        assert!((bounds.top() - 10.0).abs() < 0.01);
+        assert!((bounds.right() - 90.0).abs() < 0.01);
+        assert!((bounds.bottom() - 50.0).abs() < 0.01);
+
+        // Flags without separators.
+        assert_eq!(
+            Path::from_svg_data("M10 50a40 40 0 0180 0"),
+            Path::from_svg_data("M10 50 a 40, 40, 0, 0, 1, 80, 0"),
+        );
+
+        // Zero radius.
+        assert_eq!(parse("M0 0 A0 10 0 1 1 10 10"), "M 0 0 L 10 10");
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "M 0.1 -3.3333333 L 1e-7 123456.79 Q 1 2 3 4 Z M 5 5 C 1 2 3 4 5 6 M 7 8";
+        let path = Path::from_svg_data(text).unwrap();
+        assert_eq!(
+            path.to_svg_data(),
+            "M 0.1 -3.3333333 L 0.0000001 123456.79 Q 1 2 3 4 Z M 5 5 C 1 2 3 4 5 6 M 7 8"
+        );
+        assert_eq!(Path::from_svg_data(&path.to_svg_data()).unwrap(), path);
+    }
+
+    #[test]
+    fn errors() {
+        use SvgPathErrorKind::*;
+
+        assert_eq!(error(""), (EmptyPath, 0));
+        assert_eq!(error("  M 10 20 "), (EmptyPath, 10));
+        assert_eq!(error("L 10 20"), (MissingMoveTo, 0));
+        assert_eq!(error("  10 20"), (UnexpectedCharacter, 2));
+        assert_eq!(error("M 10 20 L 30"), (ExpectedNumber, 12));
+        assert_eq!(error("M 10 20 L 30,,40"), (ExpectedNumber, 13));
+        assert_eq!(error("M 10 20 L 30 -"), (ExpectedNumber, 13));
+        assert_eq!(error("M 10 20 X 30 40"), (UnexpectedCharacter, 8));
+        assert_eq!(error("M 10 20 L 30 40e"), (UnexpectedCharacter, 15));
+        assert_eq!(error("M 10 20 L 1e39 0"), (InvalidNumber, 10));
+        assert_eq!(error("M 10 20 A 1 1 0 2 0 5 5"), (ExpectedFlag, 16));
+        assert_eq!(error("M 10 20 L 5 5 Z 1 1"), (UnexpectedCharacter, 16));
+        assert_eq!(error("M 10 20 L 5 5 ж"), (UnexpectedCharacter, 14));
+    }
+}
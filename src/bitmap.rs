@@ -0,0 +1,706 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+use crate::{Color, FillRule, Mask, Paint, Path, Pixmap, PixmapMut, PixmapPaint, PixmapRef};
+use crate::{Rect, Stroke, Transform};
+
+use crate::color::PremultipliedColor;
+
+/// A pixel format.
+///
+/// Color channels are always premultiplied.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum ColorType {
+    /// 8-bit unsigned normalized channels.
+    ///
+    /// The same format `Pixmap` uses.
+    #[default]
+    Rgba8,
+
+    /// 8-bit unsigned normalized channels in BGRA order.
+    Bgra8,
+
+    /// 5-bit red, 6-bit green and 5-bit blue packed into a native endian `u16`,
+    /// with red in the most significant bits.
+    ///
+    /// Has no alpha and therefore always opaque.
+    Rgb565,
+
+    /// 8-bit luminance.
+    ///
+    /// Has no alpha and therefore always opaque. Colors are converted using Rec. 709 luma
+    /// coefficients.
+    Gray8,
+
+    /// 8-bit alpha only.
+    ///
+    /// Color channels are ignored and always read as zero.
+    A8,
+
+    /// 16-bit unsigned normalized channels in native endianness.
+    Rgba16,
+
+    /// 16-bit floating point (half precision) channels in native endianness.
+    RgbaF16,
+
+    /// 32-bit floating point channels in native endianness.
+    RgbaF32,
+}
+
+impl ColorType {
+    /// Returns the number of bytes per pixel.
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            ColorType::Gray8 | ColorType::A8 => 1,
+            ColorType::Rgb565 => 2,
+            ColorType::Rgba8 | ColorType::Bgra8 => 4,
+            ColorType::Rgba16 | ColorType::RgbaF16 => 8,
+            ColorType::RgbaF32 => 16,
+        }
+    }
+
+    /// Reads a premultiplied color from the beginning of `data`.
+    #[inline(always)]
+    pub(crate) fn read(self, data: &[u8]) -> [f32; 4] {
+        let unorm8 = |i: usize| data[i] as f32 * (1.0 / 255.0);
+        match self {
+            ColorType::Rgba8 => [unorm8(0), unorm8(1), unorm8(2), unorm8(3)],
+            ColorType::Bgra8 => [unorm8(2), unorm8(1), unorm8(0), unorm8(3)],
+            ColorType::Rgb565 => {
+                let n = u16::from_ne_bytes([data[0], data[1]]);
+                [
+                    (n >> 11) as f32 * (1.0 / 31.0),
+                    ((n >> 5) & 63) as f32 * (1.0 / 63.0),
+                    (n & 31) as f32 * (1.0 / 31.0),
+                    1.0,
+                ]
+            }
+            ColorType::Gray8 => {
+                let v = unorm8(0);
+                [v, v, v, 1.0]
+            }
+            ColorType::A8 => [0.0, 0.0, 0.0, unorm8(0)],
+            ColorType::Rgba16 => {
+                let n = |i: usize| u16::from_ne_bytes([data[i * 2], data[i * 2 + 1]]);
+                let unorm16 = |i: usize| n(i) as f32 * (1.0 / 65535.0);
+                [unorm16(0), unorm16(1), unorm16(2), unorm16(3)]
+            }
+            ColorType::RgbaF16 => {
+                let f = |i: usize| f16_to_f32(u16::from_ne_bytes([data[i * 2], data[i * 2 + 1]]));
+                [f(0), f(1), f(2), f(3)]
+            }
+            ColorType::RgbaF32 => {
+                let f = |i: usize| {
+                    let b = &data[i * 4..];
+                    f32::from_ne_bytes([b[0], b[1], b[2], b[3]])
+                };
+                [f(0), f(1), f(2), f(3)]
+            }
+        }
+    }
+
+    /// Writes a premultiplied color to the beginning of `data`.
+    ///
+    /// Integer formats are clamped to the 0..=1 range. Floating point ones are stored as is.
+    #[inline(always)]
+    pub(crate) fn write(self, c: [f32; 4], data: &mut [u8]) {
+        let unorm = |v: f32, max: f32| (v.clamp(0.0, 1.0) * max + 0.5) as u16;
+        match self {
+            ColorType::Rgba8 => {
+                for (d, v) in data.iter_mut().zip(c.iter()) {
+                    *d = unorm(*v, 255.0) as u8;
+                }
+            }
+            ColorType::Bgra8 => {
+                data[0] = unorm(c[2], 255.0) as u8;
+                data[1] = unorm(c[1], 255.0) as u8;
+                data[2] = unorm(c[0], 255.0) as u8;
+                data[3] = unorm(c[3], 255.0) as u8;
+            }
+            ColorType::Rgb565 => {
+                let n = unorm(c[0], 31.0) << 11 | unorm(c[1], 63.0) << 5 | unorm(c[2], 31.0);
+                data[..2].copy_from_slice(&n.to_ne_bytes());
+            }
+            ColorType::Gray8 => {
+                let luma = c[0] * 0.2126 + c[1] * 0.7152 + c[2] * 0.0722;
+                data[0] = unorm(luma, 255.0) as u8;
+            }
+            ColorType::A8 => {
+                data[0] = unorm(c[3], 255.0) as u8;
+            }
+            ColorType::Rgba16 => {
+                for (d, v) in data.chunks_exact_mut(2).zip(c.iter()) {
+                    d.copy_from_slice(&unorm(*v, 65535.0).to_ne_bytes());
+                }
+            }
+            ColorType::RgbaF16 => {
+                for (d, v) in data.chunks_exact_mut(2).zip(c.iter()) {
+                    d.copy_from_slice(&f32_to_f16(*v).to_ne_bytes());
+                }
+            }
+            ColorType::RgbaF32 => {
+                for (d, v) in data.chunks_exact_mut(4).zip(c.iter()) {
+                    d.copy_from_slice(&v.to_ne_bytes());
+                }
+            }
+        }
+    }
+}
+
+/// A container that owns premultiplied pixels of any [`ColorType`].
+///
+/// Unlike `Pixmap`, which is always RGBA8, can store other channel orders, packed formats
+/// and 16-bit and floating point channels, which avoids banding during compositing.
+///
+/// Supports the same drawing methods as `Pixmap`. 16-bit and floating point formats
+/// are always rendered using the high quality pipeline. Cannot be used as a pattern
+/// source directly, use [`Bitmap::to_pixmap`] instead.
+///
+/// The data is not aligned, therefore width == stride.
+///
+/// Essentially a `SkBitmap`.
+#[derive(Clone, PartialEq)]
+pub struct Bitmap {
+    data: Vec<u8>,
+    size: IntSize,
+    color_type: ColorType,
+}
+
+impl Bitmap {
+    /// Allocates a new bitmap.
+    ///
+    /// A bitmap is filled with transparent black by default.
+    ///
+    /// Zero size in an error.
+    ///
+    /// Row size in bytes is limited by `i32::MAX`.
+    pub fn new(width: u32, height: u32, color_type: ColorType) -> Option<Self> {
+        let size = IntSize::from_wh(width, height)?;
+        let data_len = data_len_for_size(size, color_type)?;
+
+        Some(Bitmap {
+            data: vec![0; data_len],
+            size,
+            color_type,
+        })
+    }
+
+    /// Creates a new bitmap by taking ownership over an image buffer.
+    ///
+    /// The size needs to match the data provided.
+    pub fn from_vec(data: Vec<u8>, size: IntSize, color_type: ColorType) -> Option<Self> {
+        let data_len = data_len_for_size(size, color_type)?;
+        if data.len() != data_len {
+            return None;
+        }
+
+        Some(Bitmap {
+            data,
+            size,
+            color_type,
+        })
+    }
+
+    /// Creates a new bitmap by converting an 8-bit pixmap.
+    pub fn from_pixmap(pixmap: PixmapRef, color_type: ColorType) -> Self {
+        let mut bitmap = Bitmap::new(pixmap.width(), pixmap.height(), color_type).unwrap();
+        let bpp = color_type.bytes_per_pixel();
+        for (c, data) in pixmap
+            .rows()
+            .flatten()
+            .zip(bitmap.data.chunks_exact_mut(bpp))
+        {
+            let c = ColorType::Rgba8.read(&[c.red(), c.green(), c.blue(), c.alpha()]);
+            color_type.write(c, data);
+        }
+
+        bitmap
+    }
+
+    /// Converts the bitmap into an 8-bit pixmap.
+    ///
+    /// Values outside the 0..=1 range are clamped.
+    pub fn to_pixmap(&self) -> Pixmap {
+        let mut pixmap = Pixmap::new(self.width(), self.height()).unwrap();
+        let bpp = self.color_type.bytes_per_pixel();
+        for (p, data) in pixmap
+            .pixels_mut()
+            .iter_mut()
+            .zip(self.data.chunks_exact(bpp))
+        {
+            let c = self.color_type.read(data);
+            *p = premultiplied_color(c).to_color_u8();
+        }
+
+        pixmap
+    }
+
+    /// Returns bitmap's width.
+    #[inline]
+    pub const fn width(&self) -> u32 {
+        self.size.width()
+    }
+
+    /// Returns bitmap's height.
+    #[inline]
+    pub const fn height(&self) -> u32 {
+        self.size.height()
+    }
+
+    /// Returns bitmap's color type.
+    #[inline]
+    pub const fn color_type(&self) -> ColorType {
+        self.color_type
+    }
+
+    /// Fills the entire bitmap with a specified color.
+    pub fn fill(&mut self, color: Color) {
+        self.as_mut().fill(color);
+    }
+
+    /// Returns the internal data.
+    pub fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    /// Returns the mutable internal data.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.data.as_mut_slice()
+    }
+
+    /// Consumes the internal data.
+    pub fn take(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns a pixel color.
+    ///
+    /// Values outside the 0..=1 range are clamped.
+    ///
+    /// Returns `None` when position is out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<PremultipliedColor> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        let bpp = self.color_type.bytes_per_pixel();
+        let idx = (y as usize * self.width() as usize + x as usize) * bpp;
+        Some(premultiplied_color(self.color_type.read(&self.data[idx..])))
+    }
+
+    /// Draws a filled rectangle onto the bitmap.
+    ///
+    /// See [`PixmapMut::fill_rect`](crate::PixmapMut::fill_rect) for details.
+    pub fn fill_rect(
+        &mut self,
+        rect: Rect,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut().fill_rect(rect, paint, transform, mask);
+    }
+
+    /// Draws a filled path onto the bitmap.
+    ///
+    /// See [`PixmapMut::fill_path`](crate::PixmapMut::fill_path) for details.
+    pub fn fill_path(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut()
+            .fill_path(path, paint, fill_rule, transform, mask);
+    }
+
+    /// Strokes a path.
+    ///
+    /// See [`PixmapMut::stroke_path`](crate::PixmapMut::stroke_path) for details.
+    pub fn stroke_path(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        stroke: &Stroke,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut()
+            .stroke_path(path, paint, stroke, transform, mask);
+    }
+
+    /// Draws a `Pixmap` on top of the current `Bitmap`.
+    ///
+    /// See [`PixmapMut::draw_pixmap`](crate::PixmapMut::draw_pixmap) for details.
+    pub fn draw_pixmap(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: PixmapRef,
+        paint: &PixmapPaint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut()
+            .draw_pixmap(x, y, pixmap, paint, transform, mask);
+    }
+
+    /// Applies a mask.
+    ///
+    /// See [`PixmapMut::apply_mask`](crate::PixmapMut::apply_mask) for details.
+    pub fn apply_mask(&mut self, mask: &Mask) {
+        self.as_mut().apply_mask(mask);
+    }
+
+    /// Returns a container that references Bitmap's data.
+    pub fn as_mut(&mut self) -> BitmapMut<'_> {
+        BitmapMut {
+            data: &mut self.data,
+            size: self.size,
+            stride: self.size.width() as usize * self.color_type.bytes_per_pixel(),
+            color_type: self.color_type,
+        }
+    }
+}
+
+impl core::fmt::Debug for Bitmap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Bitmap")
+            .field("data", &"...")
+            .field("width", &self.size.width())
+            .field("height", &self.size.height())
+            .field("color_type", &self.color_type)
+            .finish()
+    }
+}
+
+/// A container that references mutable premultiplied pixels of any [`ColorType`].
+///
+/// Can be created from `Bitmap` or from a user provided data,
+/// like a window or a display framebuffer.
+///
+/// Unless created using [`BitmapMut::from_bytes_with_stride`], width == stride.
+#[derive(PartialEq)]
+pub struct BitmapMut<'a> {
+    data: &'a mut [u8],
+    size: IntSize,
+    // In bytes.
+    stride: usize,
+    color_type: ColorType,
+}
+
+impl<'a> BitmapMut<'a> {
+    /// Creates a new `BitmapMut` from bytes.
+    ///
+    /// The size must be at least `width * height * color_type.bytes_per_pixel()`.
+    /// Zero size in an error.
+    ///
+    /// Row size in bytes is limited by `i32::MAX`.
+    pub fn from_bytes(
+        data: &'a mut [u8],
+        width: u32,
+        height: u32,
+        color_type: ColorType,
+    ) -> Option<Self> {
+        let size = IntSize::from_wh(width, height)?;
+        let data_len = data_len_for_size(size, color_type)?;
+        if data.len() < data_len {
+            return None;
+        }
+
+        Some(BitmapMut {
+            data: &mut data[..data_len],
+            size,
+            stride: size.width() as usize * color_type.bytes_per_pixel(),
+            color_type,
+        })
+    }
+
+    /// Creates a new `BitmapMut` from bytes with an explicit row stride.
+    ///
+    /// `stride` is the distance between rows in bytes. It must be a multiple of
+    /// `color_type.bytes_per_pixel()` and at least `width * color_type.bytes_per_pixel()`.
+    /// Padding is never modified.
+    ///
+    /// The size must be at least `(height - 1) * stride + width * color_type.bytes_per_pixel()`.
+    /// Zero size in an error. Stride is limited by `i32::MAX`.
+    pub fn from_bytes_with_stride(
+        data: &'a mut [u8],
+        width: u32,
+        height: u32,
+        stride: usize,
+        color_type: ColorType,
+    ) -> Option<Self> {
+        let size = IntSize::from_wh(width, height)?;
+        let data_len = data_len_for_stride(size, stride, color_type)?;
+        if data.len() < data_len {
+            return None;
+        }
+
+        Some(BitmapMut {
+            data: &mut data[..data_len],
+            size,
+            stride,
+            color_type,
+        })
+    }
+
+    /// Returns bitmap's width.
+    #[inline]
+    pub const fn width(&self) -> u32 {
+        self.size.width()
+    }
+
+    /// Returns bitmap's height.
+    #[inline]
+    pub const fn height(&self) -> u32 {
+        self.size.height()
+    }
+
+    /// Returns bitmap's color type.
+    #[inline]
+    pub const fn color_type(&self) -> ColorType {
+        self.color_type
+    }
+
+    /// Returns the distance between rows in bytes.
+    #[inline]
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Fills the entire bitmap with a specified color.
+    pub fn fill(&mut self, color: Color) {
+        let c = color.premultiply();
+        let mut pixel = [0; 16];
+        self.color_type
+            .write([c.red(), c.green(), c.blue(), c.alpha()], &mut pixel);
+
+        let bpp = self.color_type.bytes_per_pixel();
+        let row_bytes = self.width() as usize * bpp;
+        for row in self.data.chunks_mut(self.stride) {
+            for data in row[..row_bytes].chunks_exact_mut(bpp) {
+                data.copy_from_slice(&pixel[..bpp]);
+            }
+        }
+    }
+
+    /// Returns the mutable internal data.
+    ///
+    /// Includes row padding, if any.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    /// Draws a filled rectangle onto the bitmap.
+    ///
+    /// See [`PixmapMut::fill_rect`](crate::PixmapMut::fill_rect) for details.
+    pub fn fill_rect(
+        &mut self,
+        rect: Rect,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_pixmap().fill_rect(rect, paint, transform, mask);
+    }
+
+    /// Draws a filled path onto the bitmap.
+    ///
+    /// See [`PixmapMut::fill_path`](crate::PixmapMut::fill_path) for details.
+    pub fn fill_path(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_pixmap()
+            .fill_path(path, paint, fill_rule, transform, mask);
+    }
+
+    /// Strokes a path.
+    ///
+    /// See [`PixmapMut::stroke_path`](crate::PixmapMut::stroke_path) for details.
+    pub fn stroke_path(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        stroke: &Stroke,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_pixmap()
+            .stroke_path(path, paint, stroke, transform, mask);
+    }
+
+    /// Draws a `Pixmap` on top of the current `BitmapMut`.
+    ///
+    /// See [`PixmapMut::draw_pixmap`](crate::PixmapMut::draw_pixmap) for details.
+    pub fn draw_pixmap(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: PixmapRef,
+        paint: &PixmapPaint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_pixmap()
+            .draw_pixmap(x, y, pixmap, paint, transform, mask);
+    }
+
+    /// Applies a mask.
+    ///
+    /// See [`PixmapMut::apply_mask`](crate::PixmapMut::apply_mask) for details.
+    pub fn apply_mask(&mut self, mask: &Mask) {
+        self.as_pixmap().apply_mask(mask);
+    }
+
+    fn as_pixmap(&mut self) -> PixmapMut<'_> {
+        PixmapMut::from_bitmap_data(self.data, self.size, self.stride, self.color_type)
+    }
+}
+
+impl core::fmt::Debug for BitmapMut<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BitmapMut")
+            .field("data", &"...")
+            .field("width", &self.size.width())
+            .field("height", &self.size.height())
+            .field("color_type", &self.color_type)
+            .finish()
+    }
+}
+
+fn premultiplied_color(c: [f32; 4]) -> PremultipliedColor {
+    PremultipliedColor::from_rgba_clamped(c[0], c[1], c[2], c[3])
+}
+
+fn min_row_bytes(width: u32, color_type: ColorType) -> Option<usize> {
+    let row_bytes = (width as usize).checked_mul(color_type.bytes_per_pixel())?;
+    if row_bytes > i32::MAX as usize {
+        return None;
+    }
+
+    Some(row_bytes)
+}
+
+fn data_len_for_size(size: IntSize, color_type: ColorType) -> Option<usize> {
+    min_row_bytes(size.width(), color_type)?.checked_mul(size.height() as usize)
+}
+
+fn data_len_for_stride(size: IntSize, stride: usize, color_type: ColorType) -> Option<usize> {
+    let row_bytes = min_row_bytes(size.width(), color_type)?;
+    if stride < row_bytes
+        // Bytes per pixel is always a power of two.
+        || stride & (color_type.bytes_per_pixel() - 1) != 0
+        || stride > i32::MAX as usize
+    {
+        return None;
+    }
+
+    (size.height() as usize - 1)
+        .checked_mul(stride)?
+        .checked_add(row_bytes)
+}
+
+/// Converts a half precision float into a single precision one.
+pub(crate) fn f16_to_f32(h: u16) -> f32 {
+    let sign = u32::from(h & 0x8000) << 16;
+    let exp = u32::from((h >> 10) & 0x1F);
+    let man = u32::from(h & 0x3FF);
+
+    let bits = match exp {
+        0 => {
+            // Zero or a subnormal number, which is `man * 2^-24`.
+            let v = man as f32 * (1.0 / 16_777_216.0);
+            return if sign != 0 { -v } else { v };
+        }
+        0x1F => sign | 0x7F80_0000 | (man << 13), // Infinity or NaN.
+        _ => sign | ((exp + 112) << 23) | (man << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+/// Converts a single precision float into a half precision one.
+///
+/// Rounds to nearest even. Values that are too large become infinity.
+pub(crate) fn f32_to_f16(v: f32) -> u16 {
+    let x = v.to_bits();
+    let sign = ((x >> 16) & 0x8000) as u16;
+    let exp = ((x >> 23) & 0xFF) as i32;
+    let man = x & 0x7F_FFFF;
+
+    if exp == 0xFF {
+        // Infinity or NaN. Keep NaN a NaN.
+        return sign | 0x7C00 | if man != 0 { 0x200 } else { 0 };
+    }
+
+    let e = exp - 127 + 15;
+    if e >= 0x1F {
+        return sign | 0x7C00;
+    }
+
+    let (half, rem, halfway) = if e <= 0 {
+        if e < -10 {
+            // Too small even for a subnormal.
+            return sign;
+        }
+
+        // A subnormal number. Restore the implicit bit and shift.
+        let man = man | 0x80_0000;
+        let shift = (14 - e) as u32;
+        (man >> shift, man & ((1 << shift) - 1), 1 << (shift - 1))
+    } else {
+        ((e as u32) << 10 | (man >> 13), man & 0x1FFF, 0x1000)
+    };
+
+    // Rounding can overflow into the exponent, which is exactly what we want.
+    let round_up = rem > halfway || (rem == halfway && half & 1 != 0);
+    sign | (half + u32::from(round_up)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f16_roundtrip() {
+        for h in 0..=0xFFFFu16 {
+            let v = f16_to_f32(h);
+            if v.is_nan() {
+                assert!(f16_to_f32(f32_to_f16(v)).is_nan());
+            } else {
+                assert_eq!(f32_to_f16(v), h);
+            }
+        }
+    }
+
+    #[test]
+    fn f16_values() {
+        assert_eq!(f32_to_f16(0.0), 0x0000);
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert_eq!(f32_to_f16(1.0), 0x3C00);
+        assert_eq!(f32_to_f16(0.5), 0x3800);
+        assert_eq!(f32_to_f16(65504.0), 0x7BFF);
+        assert_eq!(f32_to_f16(65520.0), 0x7C00);
+        assert_eq!(f32_to_f16(1e-8), 0x0000);
+        assert_eq!(f32_to_f16(f32::INFINITY), 0x7C00);
+        // Ties to even.
+        assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3C00);
+        assert_eq!(f32_to_f16(1.0 + 3.0 / 2048.0), 0x3C02);
+        assert_eq!(f16_to_f32(0x0001), 1.0 / 16_777_216.0);
+    }
+}
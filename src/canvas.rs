@@ -0,0 +1,421 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use tiny_skia_path::{IntRect, Path, PathBuilder, Point, RRect, Rect, Stroke, Transform};
+
+use crate::{BlendMode, Color, FillRule, Mask, Paint, Pixmap, PixmapMut, PixmapPaint, PixmapRef};
+
+#[derive(Clone, Debug)]
+struct CanvasState {
+    transform: Transform,
+    // `None` means no clipping. Allocated only on the first clip.
+    // Has the same size as the current layer.
+    // Shared with saved states and copied only when the clip changes.
+    clip_mask: Option<Arc<Mask>>,
+}
+
+#[derive(Debug)]
+struct Layer {
+    // `None` when the layer bounds are empty. Everything drawn onto such layer is discarded.
+    pixmap: Option<Pixmap>,
+    // Layer position in the canvas coordinates.
+    x: i32,
+    y: i32,
+    paint: PixmapPaint,
+    // Parent clip and a user mask, in the parent layer coordinates.
+    mask: Option<Mask>,
+    // The number of saved states right after the matching `save_layer` call.
+    save_count: usize,
+}
+
+/// A stateful drawing context over a `PixmapMut`.
+///
+/// Unlike `PixmapMut` drawing methods, a canvas keeps track of the current transform
+/// and clipping region, which can be saved and restored, just like in Skia or HTML Canvas.
+/// All drawing methods simply forward to `PixmapMut` ones.
+///
+/// Clipping is implemented via a `Mask`, which will be allocated only
+/// on the first clipping operation. Saving doesn't copy it, unless the clip
+/// is changed afterwards.
+///
+/// Offscreen layers are supported via [`Canvas::save_layer`].
+#[derive(Debug)]
+pub struct Canvas<'a> {
+    pixmap: PixmapMut<'a>,
+    state: CanvasState,
+    stack: Vec<CanvasState>,
+    layers: Vec<Layer>,
+}
+
+impl<'a> Canvas<'a> {
+    /// Creates a new canvas.
+    ///
+    /// The initial transform is identity and there is no clipping.
+    pub fn new(pixmap: PixmapMut<'a>) -> Self {
+        Canvas {
+            pixmap,
+            state: CanvasState {
+                transform: Transform::identity(),
+                clip_mask: None,
+            },
+            stack: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    /// Returns the underlying pixmap.
+    ///
+    /// Content of layers that are not restored yet is not included.
+    pub fn pixmap(&self) -> PixmapRef<'_> {
+        self.pixmap.as_ref()
+    }
+
+    /// Returns the underlying mutable pixmap.
+    ///
+    /// Any drawing done directly on a pixmap ignores the canvas transform and clipping.
+    pub fn pixmap_mut(&mut self) -> &mut PixmapMut<'a> {
+        &mut self.pixmap
+    }
+
+    /// Consumes the canvas and returns the underlying pixmap.
+    ///
+    /// All layers that are not restored yet will be restored first.
+    pub fn into_pixmap(mut self) -> PixmapMut<'a> {
+        self.restore_to_count(0);
+        self.pixmap
+    }
+
+    /// Saves the current transform and clip.
+    ///
+    /// They will be restored by the matching [`Canvas::restore`] call.
+    pub fn save(&mut self) {
+        self.stack.push(self.state.clone());
+    }
+
+    /// Saves the current transform and clip and redirects all drawing to an offscreen layer.
+    ///
+    /// The layer will be composited back by the matching [`Canvas::restore`] call,
+    /// using `paint` opacity and blend mode, current clip and an optional `mask`.
+    ///
+    /// `bounds` are affected by the current transform and used only to limit the layer size.
+    /// When not set, the layer will cover the whole canvas.
+    ///
+    /// `mask` must have the same size as the canvas pixmap.
+    pub fn save_layer(&mut self, bounds: Option<Rect>, paint: &PixmapPaint, mask: Option<&Mask>) {
+        let target_rect = self.target_rect();
+        let layer_rect = target_rect.and_then(|target_rect| match bounds {
+            Some(bounds) => bounds
+                .transform(self.state.transform)
+                .and_then(|r| r.round_out())
+                .and_then(|r| r.intersect(&target_rect)),
+            None => Some(target_rect),
+        });
+
+        let mut layer = Layer {
+            pixmap: None,
+            x: 0,
+            y: 0,
+            paint: paint.clone(),
+            mask: None,
+            save_count: self.stack.len() + 1,
+        };
+
+        if let (Some(target_rect), Some(rect)) = (target_rect, layer_rect) {
+            layer.pixmap = Pixmap::new(rect.width(), rect.height());
+            layer.x = rect.x();
+            layer.y = rect.y();
+            layer.mask = self.state.clip_mask.as_deref().cloned();
+
+            if let Some(mask) = mask {
+                if mask.width() != self.pixmap.width() || mask.height() != self.pixmap.height() {
+                    log::warn!("Canvas and Mask are expected to have the same size");
+                } else if let Some(mask) = mask.clone_rect(target_rect) {
+                    // A mask cropped to the parent layer.
+                    match layer.mask {
+                        Some(ref mut layer_mask) => layer_mask.intersect(&mask),
+                        None => layer.mask = Some(mask),
+                    }
+                }
+            }
+        }
+
+        self.save();
+        // The clip will be applied during compositing.
+        self.state.clip_mask = None;
+        self.layers.push(layer);
+    }
+
+    /// Restores the transform and clip to the state at the last [`Canvas::save`] call.
+    ///
+    /// If the last call was [`Canvas::save_layer`], the layer will be composited
+    /// onto the parent layer or the underlying pixmap.
+    ///
+    /// Does nothing when there are no saved states.
+    pub fn restore(&mut self) {
+        match self.stack.pop() {
+            Some(state) => self.state = state,
+            None => {
+                log::warn!("restore was called without a matching save");
+                return;
+            }
+        }
+
+        if self.layers.last().map(|layer| layer.save_count) == Some(self.stack.len() + 1) {
+            if let Some(layer) = self.layers.pop() {
+                self.composite_layer(layer);
+            }
+        }
+    }
+
+    /// Restores the state until the number of saved states is equal to `count`.
+    ///
+    /// Does nothing when `count` is greater or equal to the current number of saved states.
+    pub fn restore_to_count(&mut self, count: usize) {
+        while self.stack.len() > count {
+            self.restore();
+        }
+    }
+
+    /// Returns the number of saved states, including layers.
+    pub fn save_count(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns the current transform.
+    pub fn transform(&self) -> Transform {
+        self.state.transform
+    }
+
+    /// Replaces the current transform.
+    pub fn set_transform(&mut self, ts: Transform) {
+        self.state.transform = ts;
+    }
+
+    /// Resets the current transform to identity.
+    pub fn reset_transform(&mut self) {
+        self.state.transform = Transform::identity();
+    }
+
+    /// Pre-concatenates the current transform with `ts`.
+    ///
+    /// Which means that `ts` will be applied to the drawn content first.
+    pub fn concat(&mut self, ts: Transform) {
+        self.state.transform = self.state.transform.pre_concat(ts);
+    }
+
+    /// Pre-concatenates the current transform with a translate.
+    pub fn translate(&mut self, tx: f32, ty: f32) {
+        self.state.transform = self.state.transform.pre_translate(tx, ty);
+    }
+
+    /// Pre-concatenates the current transform with a scale.
+    pub fn scale(&mut self, sx: f32, sy: f32) {
+        self.state.transform = self.state.transform.pre_scale(sx, sy);
+    }
+
+    /// Pre-concatenates the current transform with a rotation.
+    ///
+    /// `angle` is in degrees.
+    pub fn rotate(&mut self, angle: f32) {
+        self.state.transform = self.state.transform.pre_rotate(angle);
+    }
+
+    /// Returns the current clip mask.
+    ///
+    /// Returns `None` when there is no clipping.
+    pub fn clip_mask(&self) -> Option<&Mask> {
+        self.state.clip_mask.as_deref()
+    }
+
+    /// Intersects the current clip with a rectangle.
+    ///
+    /// The rectangle is affected by the current transform.
+    pub fn clip_rect(&mut self, rect: Rect, anti_alias: bool) {
+        let path = PathBuilder::from_rect(rect);
+        self.clip_path(&path, FillRule::Winding, anti_alias);
+    }
+
+    /// Intersects the current clip with a path.
+    ///
+    /// The path is affected by the current transform.
+    pub fn clip_path(&mut self, path: &Path, fill_rule: FillRule, anti_alias: bool) {
+        let transform = self.layer_transform();
+        match self.state.clip_mask {
+            Some(ref mut mask) => {
+                Arc::make_mut(mask).intersect_path(path, fill_rule, anti_alias, transform)
+            }
+            None => {
+                let Some(rect) = self.target_rect() else {
+                    return; // an empty layer, nothing to clip
+                };
+
+                let Some(mut mask) = Mask::new(rect.width(), rect.height()) else {
+                    return; // technically unreachable
+                };
+
+                mask.fill_path(path, fill_rule, anti_alias, transform);
+                self.state.clip_mask = Some(Arc::new(mask));
+            }
+        }
+    }
+
+    /// Removes clipping.
+    pub fn reset_clip(&mut self) {
+        self.state.clip_mask = None;
+    }
+
+    /// Draws a filled rectangle.
+    ///
+    /// See [`PixmapMut::fill_rect`] for details.
+    pub fn fill_rect(&mut self, rect: Rect, paint: &Paint) {
+        if let Some((mut pixmap, transform, mask)) = self.target() {
+            pixmap.fill_rect(rect, paint, transform, mask);
+        }
+    }
+
+    /// Draws a filled rounded rectangle.
+    ///
+    /// See [`PixmapMut::fill_rrect`] for details.
+    pub fn fill_rrect(&mut self, rrect: RRect, paint: &Paint) {
+        if let Some((mut pixmap, transform, mask)) = self.target() {
+            pixmap.fill_rrect(rrect, paint, transform, mask);
+        }
+    }
+
+    /// Strokes a rounded rectangle.
+    ///
+    /// See [`PixmapMut::stroke_rrect`] for details.
+    pub fn stroke_rrect(&mut self, rrect: RRect, paint: &Paint, stroke: &Stroke) {
+        if let Some((mut pixmap, transform, mask)) = self.target() {
+            pixmap.stroke_rrect(rrect, paint, stroke, transform, mask);
+        }
+    }
+
+    /// Draws a filled path.
+    ///
+    /// See [`PixmapMut::fill_path`] for details.
+    pub fn fill_path(&mut self, path: &Path, paint: &Paint, fill_rule: FillRule) {
+        if let Some((mut pixmap, transform, mask)) = self.target() {
+            pixmap.fill_path(path, paint, fill_rule, transform, mask);
+        }
+    }
+
+    /// Strokes a path.
+    ///
+    /// See [`PixmapMut::stroke_path`] for details.
+    pub fn stroke_path(&mut self, path: &Path, paint: &Paint, stroke: &Stroke) {
+        if let Some((mut pixmap, transform, mask)) = self.target() {
+            pixmap.stroke_path(path, paint, stroke, transform, mask);
+        }
+    }
+
+    /// Draws a `Pixmap` at the specified position.
+    ///
+    /// See [`PixmapMut::draw_pixmap`] for details.
+    pub fn draw_pixmap(&mut self, x: i32, y: i32, pixmap: PixmapRef, paint: &PixmapPaint) {
+        if let Some((mut target, transform, mask)) = self.target() {
+            target.draw_pixmap(x, y, pixmap, paint, transform, mask);
+        }
+    }
+
+    /// Draws a Coons patch.
+    ///
+    /// See [`PixmapMut::draw_patch`] for details.
+    pub fn draw_patch(
+        &mut self,
+        cubics: &[Point; 12],
+        colors: Option<&[Color; 4]>,
+        tex_coords: Option<&[Point; 4]>,
+        blend_mode: BlendMode,
+        paint: &Paint,
+    ) {
+        if let Some((mut pixmap, transform, mask)) = self.target() {
+            pixmap.draw_patch(
+                cubics, colors, tex_coords, blend_mode, paint, transform, mask,
+            );
+        }
+    }
+
+    /// Draws a triangle mesh.
+    ///
+    /// See [`PixmapMut::draw_vertices`] for details.
+    pub fn draw_vertices(
+        &mut self,
+        positions: &[Point],
+        colors: Option<&[Color]>,
+        tex_coords: Option<&[Point]>,
+        indices: Option<&[u16]>,
+        blend_mode: BlendMode,
+        paint: &Paint,
+    ) {
+        if let Some((mut pixmap, transform, mask)) = self.target() {
+            pixmap.draw_vertices(
+                positions, colors, tex_coords, indices, blend_mode, paint, transform, mask,
+            );
+        }
+    }
+
+    /// Returns the current layer origin in the canvas coordinates.
+    fn origin(&self) -> (i32, i32) {
+        match self.layers.last() {
+            Some(layer) => (layer.x, layer.y),
+            None => (0, 0),
+        }
+    }
+
+    /// Returns the current layer rect in the canvas coordinates.
+    ///
+    /// Returns `None` for an empty layer.
+    fn target_rect(&self) -> Option<IntRect> {
+        match self.layers.last() {
+            Some(layer) => {
+                let pixmap = layer.pixmap.as_ref()?;
+                IntRect::from_xywh(layer.x, layer.y, pixmap.width(), pixmap.height())
+            }
+            None => IntRect::from_xywh(0, 0, self.pixmap.width(), self.pixmap.height()),
+        }
+    }
+
+    /// Returns the current transform adjusted to the current layer origin.
+    fn layer_transform(&self) -> Transform {
+        let (x, y) = self.origin();
+        self.state.transform.post_translate(-x as f32, -y as f32)
+    }
+
+    /// Returns the current drawing target with the matching transform and clip.
+    ///
+    /// Returns `None` for an empty layer.
+    fn target(&mut self) -> Option<(PixmapMut<'_>, Transform, Option<&Mask>)> {
+        let transform = self.layer_transform();
+        let mask = self.state.clip_mask.as_deref();
+        let pixmap = match self.layers.last_mut() {
+            Some(layer) => layer.pixmap.as_mut()?.as_mut(),
+            None => self.pixmap.reborrow(),
+        };
+
+        Some((pixmap, transform, mask))
+    }
+
+    fn composite_layer(&mut self, layer: Layer) {
+        let Some(pixmap) = layer.pixmap else {
+            return; // an empty layer, nothing to draw
+        };
+
+        let (parent_x, parent_y) = self.origin();
+        if let Some((mut target, _, _)) = self.target() {
+            target.draw_pixmap(
+                layer.x - parent_x,
+                layer.y - parent_y,
+                pixmap.as_ref(),
+                &layer.paint,
+                Transform::identity(),
+                layer.mask.as_ref(),
+            );
+        }
+    }
+}
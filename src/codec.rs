@@ -0,0 +1,823 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+// A binary `Picture` encoding.
+//
+// All values are little-endian. The layout is:
+//
+// - signature: `TSKP`
+// - version: u16
+// - cull rect: 4 x f32 (left, top, right, bottom)
+// - pixmaps: u32 count, then width: u32, height: u32 and premultiplied RGBA data
+// - masks: u32 count, then width: u32, height: u32 and 8-bit data
+// - commands: u32 count, then a u8 tag and a command-specific payload
+//
+// Pixmaps and masks are referenced by commands using a u32 index.
+// Floats are stored as is. Shaders are stored in their preprocessed form,
+// so a decoded picture is rendered exactly like the original one.
+//
+// A paint is a shader, an optional color filter, a blend mode, a color space
+// and anti-aliasing, high quality pipeline and parallel flags.
+// A gradient is a list of stops, a spread mode, transforms and an interpolation mode.
+//
+// The format wasn't released yet, therefore changes like these are made
+// to the version 1 layout directly, without compatibility branches.
+
+use alloc::vec::Vec;
+
+use tiny_skia_path::{NormalizedF32, PathVerb};
+
+use crate::picture::{Command, RecordedPaint, RecordedShader};
+use crate::{BlendMode, Color, ColorFilter, ColorSpace, FillRule, FilterQuality, IntSize};
+use crate::{GradientInterpolation, LineCap, LineJoin, LinearGradient, Mask, Path};
+use crate::{PathBuilder, PerspectiveTransform, Picture, Pixmap, PixmapPaint, Point, Rect, Shader};
+use crate::{RadialGradient, SpreadMode, Stroke, StrokeDash};
+use crate::{SweepGradient, Transform, BYTES_PER_PIXEL};
+
+const SIGNATURE: &[u8; 4] = b"TSKP";
+const VERSION: u16 = 1;
+
+const VERBS: [PathVerb; 5] = [
+    PathVerb::Move,
+    PathVerb::Line,
+    PathVerb::Quad,
+    PathVerb::Cubic,
+    PathVerb::Close,
+];
+
+const BLEND_MODES: [BlendMode; 29] = [
+    BlendMode::Clear,
+    BlendMode::Source,
+    BlendMode::Destination,
+    BlendMode::SourceOver,
+    BlendMode::DestinationOver,
+    BlendMode::SourceIn,
+    BlendMode::DestinationIn,
+    BlendMode::SourceOut,
+    BlendMode::DestinationOut,
+    BlendMode::SourceAtop,
+    BlendMode::DestinationAtop,
+    BlendMode::Xor,
+    BlendMode::Plus,
+    BlendMode::Modulate,
+    BlendMode::Screen,
+    BlendMode::Overlay,
+    BlendMode::Darken,
+    BlendMode::Lighten,
+    BlendMode::ColorDodge,
+    BlendMode::ColorBurn,
+    BlendMode::HardLight,
+    BlendMode::SoftLight,
+    BlendMode::Difference,
+    BlendMode::Exclusion,
+    BlendMode::Multiply,
+    BlendMode::Hue,
+    BlendMode::Saturation,
+    BlendMode::Color,
+    BlendMode::Luminosity,
+];
+
+/// An error returned by [`Picture::decode`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DecodeError {
+    /// The data doesn't start with a picture signature.
+    InvalidSignature,
+    /// The data was encoded using an unsupported format version.
+    UnsupportedVersion(u16),
+    /// The data ended unexpectedly.
+    UnexpectedEndOfData,
+    /// The data contains an invalid value.
+    ///
+    /// `offset` is the position at which the value was rejected.
+    InvalidData {
+        /// Byte offset.
+        offset: usize,
+    },
+    /// There is unused data after the picture.
+    TrailingData,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            DecodeError::InvalidSignature => write!(f, "not a picture"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported picture version {}", v),
+            DecodeError::UnexpectedEndOfData => write!(f, "unexpected end of data"),
+            DecodeError::InvalidData { offset } => write!(f, "invalid data at offset {}", offset),
+            DecodeError::TrailingData => write!(f, "unexpected data after the picture"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+impl Picture {
+    /// Encodes the picture into a compact binary format.
+    ///
+    /// The format is versioned and can be decoded via [`Picture::decode`]
+    /// by the same or a newer version of `tiny-skia`.
+    /// Pixmaps and masks are stored uncompressed.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.data.extend_from_slice(SIGNATURE);
+        w.data.extend_from_slice(&VERSION.to_le_bytes());
+        w.write_rect(self.cull_rect());
+
+        w.write_len(self.pixmaps.len());
+        for pixmap in &self.pixmaps {
+            w.write_u32(pixmap.width());
+            w.write_u32(pixmap.height());
+            w.data.extend_from_slice(pixmap.data());
+        }
+
+        w.write_len(self.masks.len());
+        for mask in &self.masks {
+            w.write_u32(mask.width());
+            w.write_u32(mask.height());
+            w.data.extend_from_slice(mask.data());
+        }
+
+        w.write_len(self.commands.len());
+        for command in &self.commands {
+            write_command(command, &mut w);
+        }
+
+        w.data
+    }
+
+    /// Decodes a picture encoded via [`Picture::encode`].
+    ///
+    /// The data is fully validated. Malformed data results in an error, never in a panic.
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader { data, pos: 0 };
+        if r.read_bytes(SIGNATURE.len()).ok() != Some(&SIGNATURE[..]) {
+            return Err(DecodeError::InvalidSignature);
+        }
+
+        let version = u16::from_le_bytes([r.read_u8()?, r.read_u8()?]);
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let cull_rect = r.read_rect()?;
+
+        let count = r.read_len(8)?;
+        let mut pixmaps = Vec::with_capacity(count);
+        for _ in 0..count {
+            let size = r.read_size()?;
+            let len = data_len(size, BYTES_PER_PIXEL)?;
+            let data = r.read_bytes(len)?;
+            // Every channel must be premultiplied.
+            if data
+                .chunks(BYTES_PER_PIXEL)
+                .any(|c| c[0] > c[3] || c[1] > c[3] || c[2] > c[3])
+            {
+                return Err(r.invalid());
+            }
+
+            pixmaps.push(Pixmap::from_vec(data.to_vec(), size).ok_or_else(|| r.invalid())?);
+        }
+
+        let count = r.read_len(8)?;
+        let mut masks = Vec::with_capacity(count);
+        for _ in 0..count {
+            let size = r.read_size()?;
+            let len = data_len(size, 1)?;
+            let data = r.read_bytes(len)?;
+            masks.push(Mask::from_vec(data.to_vec(), size).ok_or_else(|| r.invalid())?);
+        }
+
+        let count = r.read_len(1)?;
+        let mut commands = Vec::with_capacity(count);
+        for _ in 0..count {
+            commands.push(read_command(&mut r, pixmaps.len(), masks.len())?);
+        }
+
+        if r.pos != data.len() {
+            return Err(DecodeError::TrailingData);
+        }
+
+        let mut picture = Picture {
+            cull_rect,
+            commands,
+            pixmaps,
+            mipmaps: Vec::new(),
+            masks,
+        };
+        picture.generate_mipmaps();
+        Ok(picture)
+    }
+}
+
+fn data_len(size: IntSize, bytes_per_pixel: usize) -> Result<usize, DecodeError> {
+    (size.width() as usize)
+        .checked_mul(size.height() as usize)
+        .and_then(|n| n.checked_mul(bytes_per_pixel))
+        .ok_or(DecodeError::UnexpectedEndOfData)
+}
+
+const FILL_RECT: u8 = 0;
+const FILL_PATH: u8 = 1;
+const STROKE_PATH: u8 = 2;
+const DRAW_PIXMAP: u8 = 3;
+const APPLY_MASK: u8 = 4;
+
+fn write_command(command: &Command, w: &mut Writer) {
+    match *command {
+        Command::FillRect {
+            rect,
+            ref paint,
+            transform,
+            mask,
+        } => {
+            w.write_u8(FILL_RECT);
+            w.write_rect(rect);
+            write_paint(paint, w);
+            w.write_transform(transform);
+            w.write_index(mask);
+        }
+        Command::FillPath {
+            ref path,
+            ref paint,
+            fill_rule,
+            transform,
+            mask,
+        } => {
+            w.write_u8(FILL_PATH);
+            write_path(path, w);
+            write_paint(paint, w);
+            w.write_u8(fill_rule as u8);
+            w.write_transform(transform);
+            w.write_index(mask);
+        }
+        Command::StrokePath {
+            ref path,
+            ref paint,
+            ref stroke,
+            transform,
+            mask,
+        } => {
+            w.write_u8(STROKE_PATH);
+            write_path(path, w);
+            write_paint(paint, w);
+            write_stroke(stroke, w);
+            w.write_transform(transform);
+            w.write_index(mask);
+        }
+        Command::DrawPixmap {
+            x,
+            y,
+            pixmap,
+            ref paint,
+            transform,
+            mask,
+        } => {
+            w.write_u8(DRAW_PIXMAP);
+            w.write_i32(x);
+            w.write_i32(y);
+            w.write_len(pixmap);
+            w.write_f32(NormalizedF32::new_clamped(paint.opacity).get());
+            w.write_blend_mode(paint.blend_mode);
+            w.write_u8(paint.quality as u8);
+            write_color_filter(paint.color_filter.as_ref(), w);
+            w.write_u8(paint.color_space as u8);
+            w.write_bool(paint.parallel);
+            w.write_transform(transform);
+            w.write_index(mask);
+        }
+        Command::ApplyMask { mask } => {
+            w.write_u8(APPLY_MASK);
+            w.write_len(mask);
+        }
+    }
+}
+
+fn read_command(r: &mut Reader, pixmaps: usize, masks: usize) -> Result<Command, DecodeError> {
+    let command = match r.read_u8()? {
+        FILL_RECT => Command::FillRect {
+            rect: r.read_rect()?,
+            paint: read_paint(r, pixmaps)?,
+            transform: r.read_transform()?,
+            mask: r.read_optional_index(masks)?,
+        },
+        FILL_PATH => Command::FillPath {
+            path: read_path(r)?,
+            paint: read_paint(r, pixmaps)?,
+            fill_rule: match r.read_u8()? {
+                0 => FillRule::Winding,
+                1 => FillRule::EvenOdd,
+                _ => return Err(r.invalid()),
+            },
+            transform: r.read_transform()?,
+            mask: r.read_optional_index(masks)?,
+        },
+        STROKE_PATH => Command::StrokePath {
+            path: read_path(r)?,
+            paint: read_paint(r, pixmaps)?,
+            stroke: read_stroke(r)?,
+            transform: r.read_transform()?,
+            mask: r.read_optional_index(masks)?,
+        },
+        DRAW_PIXMAP => Command::DrawPixmap {
+            x: r.read_i32()?,
+            y: r.read_i32()?,
+            pixmap: r.read_index(pixmaps)?,
+            paint: PixmapPaint {
+                opacity: r.read_normalized_f32()?.get(),
+                blend_mode: r.read_blend_mode()?,
+                quality: r.read_filter_quality()?,
+                color_filter: read_color_filter(r)?,
+                color_space: r.read_color_space()?,
+                parallel: r.read_bool()?,
+            },
+            transform: r.read_transform()?,
+            mask: r.read_optional_index(masks)?,
+        },
+        APPLY_MASK => Command::ApplyMask {
+            mask: r.read_index(masks)?,
+        },
+        _ => return Err(r.invalid()),
+    };
+
+    Ok(command)
+}
+
+fn write_path(path: &Path, w: &mut Writer) {
+    w.write_len(path.verbs().len());
+    for verb in path.verbs() {
+        w.write_u8(*verb as u8);
+    }
+
+    w.write_len(path.points().len());
+    for p in path.points() {
+        w.write_point(*p);
+    }
+}
+
+fn read_path(r: &mut Reader) -> Result<Path, DecodeError> {
+    let verbs_len = r.read_len(1)?;
+    let verbs = r.read_bytes(verbs_len)?;
+    let points_len = r.read_len(8)?;
+    let mut points = Vec::with_capacity(points_len);
+    for _ in 0..points_len {
+        points.push(r.read_point()?);
+    }
+
+    // Rebuilding the path validates verbs and points.
+    let err = r.invalid();
+    let mut pb = PathBuilder::with_capacity(verbs_len, points_len);
+    let mut points = points.into_iter();
+    let mut next = || points.next().ok_or(err);
+    for verb in verbs {
+        match VERBS.get(usize::from(*verb)).ok_or(err)? {
+            PathVerb::Move => {
+                let p = next()?;
+                pb.move_to(p.x, p.y);
+            }
+            PathVerb::Line => {
+                let p = next()?;
+                pb.line_to(p.x, p.y);
+            }
+            PathVerb::Quad => {
+                let (p1, p) = (next()?, next()?);
+                pb.quad_to(p1.x, p1.y, p.x, p.y);
+            }
+            PathVerb::Cubic => {
+                let (p1, p2, p) = (next()?, next()?, next()?);
+                pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p.x, p.y);
+            }
+            PathVerb::Close => pb.close(),
+        }
+    }
+
+    if next().is_ok() {
+        return Err(err);
+    }
+
+    pb.finish().ok_or(err)
+}
+
+const SOLID_COLOR: u8 = 0;
+const LINEAR_GRADIENT: u8 = 1;
+const RADIAL_GRADIENT: u8 = 2;
+const SWEEP_GRADIENT: u8 = 3;
+const PATTERN: u8 = 4;
+
+fn write_paint(paint: &RecordedPaint, w: &mut Writer) {
+    match paint.shader {
+        RecordedShader::Plain(Shader::SolidColor(color)) => {
+            w.write_u8(SOLID_COLOR);
+            w.write_color(color);
+        }
+        RecordedShader::Plain(Shader::LinearGradient(ref g)) => {
+            w.write_u8(LINEAR_GRADIENT);
+            g.encode(w);
+        }
+        RecordedShader::Plain(Shader::RadialGradient(ref g)) => {
+            w.write_u8(RADIAL_GRADIENT);
+            g.encode(w);
+        }
+        RecordedShader::Plain(Shader::SweepGradient(ref g)) => {
+            w.write_u8(SWEEP_GRADIENT);
+            g.encode(w);
+        }
+        RecordedShader::Plain(Shader::Pattern(_)) => {
+            unreachable!("patterns are recorded separately")
+        }
+        RecordedShader::Pattern {
+            pixmap,
+            spread_mode,
+            quality,
+            opacity,
+            transform,
+        } => {
+            w.write_u8(PATTERN);
+            w.write_len(pixmap);
+            w.write_u8(spread_mode as u8);
+            w.write_u8(quality as u8);
+            w.write_f32(opacity.get());
+            w.write_perspective_transform(transform);
+        }
+    }
+
+    write_color_filter(paint.color_filter.as_ref(), w);
+    w.write_blend_mode(paint.blend_mode);
+    w.write_u8(paint.color_space as u8);
+    w.write_bool(paint.anti_alias);
+    w.write_bool(paint.force_hq_pipeline);
+    w.write_bool(paint.parallel);
+}
+
+fn read_paint(r: &mut Reader, pixmaps: usize) -> Result<RecordedPaint, DecodeError> {
+    let shader = match r.read_u8()? {
+        SOLID_COLOR => RecordedShader::Plain(Shader::SolidColor(r.read_color()?)),
+        LINEAR_GRADIENT => {
+            RecordedShader::Plain(Shader::LinearGradient(LinearGradient::decode(r)?))
+        }
+        RADIAL_GRADIENT => {
+            RecordedShader::Plain(Shader::RadialGradient(RadialGradient::decode(r)?))
+        }
+        SWEEP_GRADIENT => RecordedShader::Plain(Shader::SweepGradient(SweepGradient::decode(r)?)),
+        PATTERN => RecordedShader::Pattern {
+            pixmap: r.read_index(pixmaps)?,
+            spread_mode: r.read_spread_mode()?,
+            quality: r.read_filter_quality()?,
+            opacity: r.read_normalized_f32()?,
+            transform: r.read_perspective_transform()?,
+        },
+        _ => return Err(r.invalid()),
+    };
+
+    Ok(RecordedPaint {
+        shader,
+        color_filter: read_color_filter(r)?,
+        blend_mode: r.read_blend_mode()?,
+        color_space: r.read_color_space()?,
+        anti_alias: r.read_bool()?,
+        force_hq_pipeline: r.read_bool()?,
+        parallel: r.read_bool()?,
+    })
+}
+
+fn write_color_filter(filter: Option<&ColorFilter>, w: &mut Writer) {
+    match filter {
+        Some(filter) => {
+            w.write_bool(true);
+            filter.encode(w);
+        }
+        None => w.write_bool(false),
+    }
+}
+
+fn read_color_filter(r: &mut Reader) -> Result<Option<ColorFilter>, DecodeError> {
+    if !r.read_bool()? {
+        return Ok(None);
+    }
+
+    ColorFilter::decode(r).map(Some)
+}
+
+fn write_stroke(stroke: &Stroke, w: &mut Writer) {
+    w.write_f32(stroke.width);
+    w.write_f32(stroke.miter_limit);
+    w.write_u8(stroke.line_cap as u8);
+    w.write_u8(stroke.line_join as u8);
+    match stroke.dash {
+        Some(ref dash) => {
+            w.write_bool(true);
+            w.write_len(dash.array().len());
+            for n in dash.array() {
+                w.write_f32(*n);
+            }
+            w.write_f32(dash.offset());
+        }
+        None => w.write_bool(false),
+    }
+}
+
+fn read_stroke(r: &mut Reader) -> Result<Stroke, DecodeError> {
+    let width = r.read_finite_f32()?;
+    let miter_limit = r.read_finite_f32()?;
+    let line_cap = match r.read_u8()? {
+        0 => LineCap::Butt,
+        1 => LineCap::Round,
+        2 => LineCap::Square,
+        _ => return Err(r.invalid()),
+    };
+    let line_join = match r.read_u8()? {
+        0 => LineJoin::Miter,
+        1 => LineJoin::MiterClip,
+        2 => LineJoin::Round,
+        3 => LineJoin::Bevel,
+        _ => return Err(r.invalid()),
+    };
+
+    let dash = if r.read_bool()? {
+        let len = r.read_len(4)?;
+        let mut array = Vec::with_capacity(len);
+        for _ in 0..len {
+            array.push(r.read_f32()?);
+        }
+        let offset = r.read_f32()?;
+        Some(StrokeDash::new(array, offset).ok_or_else(|| r.invalid())?)
+    } else {
+        None
+    };
+
+    Ok(Stroke {
+        width,
+        miter_limit,
+        line_cap,
+        line_join,
+        dash,
+    })
+}
+
+#[derive(Default)]
+pub(crate) struct Writer {
+    data: Vec<u8>,
+}
+
+impl Writer {
+    pub fn write_u8(&mut self, n: u8) {
+        self.data.push(n);
+    }
+
+    pub fn write_bool(&mut self, b: bool) {
+        self.write_u8(b as u8);
+    }
+
+    pub fn write_u32(&mut self, n: u32) {
+        self.data.extend_from_slice(&n.to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, n: i32) {
+        self.data.extend_from_slice(&n.to_le_bytes());
+    }
+
+    pub fn write_f32(&mut self, n: f32) {
+        self.data.extend_from_slice(&n.to_le_bytes());
+    }
+
+    // Lengths and indices are stored as u32.
+    pub fn write_len(&mut self, n: usize) {
+        self.write_u32(n as u32);
+    }
+
+    fn write_index(&mut self, index: Option<usize>) {
+        match index {
+            Some(index) => {
+                self.write_bool(true);
+                self.write_len(index);
+            }
+            None => self.write_bool(false),
+        }
+    }
+
+    pub fn write_point(&mut self, p: Point) {
+        self.write_f32(p.x);
+        self.write_f32(p.y);
+    }
+
+    fn write_rect(&mut self, r: Rect) {
+        self.write_f32(r.left());
+        self.write_f32(r.top());
+        self.write_f32(r.right());
+        self.write_f32(r.bottom());
+    }
+
+    pub fn write_color(&mut self, c: Color) {
+        self.write_f32(c.red());
+        self.write_f32(c.green());
+        self.write_f32(c.blue());
+        self.write_f32(c.alpha());
+    }
+
+    pub fn write_blend_mode(&mut self, mode: BlendMode) {
+        let index = BLEND_MODES.iter().position(|m| *m == mode).unwrap_or(0);
+        self.write_u8(index as u8);
+    }
+
+    pub fn write_transform(&mut self, ts: Transform) {
+        for n in [ts.sx, ts.ky, ts.kx, ts.sy, ts.tx, ts.ty] {
+            self.write_f32(n);
+        }
+    }
+
+    pub fn write_perspective_transform(&mut self, ts: PerspectiveTransform) {
+        for n in [
+            ts.sx, ts.ky, ts.kx, ts.sy, ts.tx, ts.ty, ts.px, ts.py, ts.pz,
+        ] {
+            self.write_f32(n);
+        }
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    // Returns an error pointing at the current position.
+    pub fn invalid(&self) -> DecodeError {
+        DecodeError::InvalidData { offset: self.pos }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .ok_or(DecodeError::UnexpectedEndOfData)?;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or(DecodeError::UnexpectedEndOfData)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, DecodeError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, DecodeError> {
+        Ok(i32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32, DecodeError> {
+        Ok(f32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_finite_f32(&mut self) -> Result<f32, DecodeError> {
+        let n = self.read_f32()?;
+        if n.is_finite() {
+            Ok(n)
+        } else {
+            Err(self.invalid())
+        }
+    }
+
+    pub fn read_normalized_f32(&mut self) -> Result<NormalizedF32, DecodeError> {
+        let n = self.read_f32()?;
+        NormalizedF32::new(n).ok_or_else(|| self.invalid())
+    }
+
+    // Reads a length of a list which items occupy at least `item_size` bytes.
+    //
+    // Makes sure that we will not allocate more than the remaining data.
+    pub fn read_len(&mut self, item_size: usize) -> Result<usize, DecodeError> {
+        let len = self.read_u32()? as usize;
+        match len.checked_mul(item_size) {
+            Some(n) if n <= self.data.len() - self.pos => Ok(len),
+            _ => Err(DecodeError::UnexpectedEndOfData),
+        }
+    }
+
+    fn read_index(&mut self, len: usize) -> Result<usize, DecodeError> {
+        let index = self.read_u32()? as usize;
+        if index < len {
+            Ok(index)
+        } else {
+            Err(self.invalid())
+        }
+    }
+
+    fn read_optional_index(&mut self, len: usize) -> Result<Option<usize>, DecodeError> {
+        if self.read_bool()? {
+            self.read_index(len).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn read_size(&mut self) -> Result<IntSize, DecodeError> {
+        let width = self.read_u32()?;
+        let height = self.read_u32()?;
+        IntSize::from_wh(width, height).ok_or_else(|| self.invalid())
+    }
+
+    pub fn read_point(&mut self) -> Result<Point, DecodeError> {
+        Ok(Point::from_xy(
+            self.read_finite_f32()?,
+            self.read_finite_f32()?,
+        ))
+    }
+
+    fn read_rect(&mut self) -> Result<Rect, DecodeError> {
+        let (l, t) = (self.read_f32()?, self.read_f32()?);
+        let (r, b) = (self.read_f32()?, self.read_f32()?);
+        Rect::from_ltrb(l, t, r, b).ok_or_else(|| self.invalid())
+    }
+
+    pub fn read_color(&mut self) -> Result<Color, DecodeError> {
+        let (r, g) = (self.read_f32()?, self.read_f32()?);
+        let (b, a) = (self.read_f32()?, self.read_f32()?);
+        Color::from_rgba(r, g, b, a).ok_or_else(|| self.invalid())
+    }
+
+    pub fn read_blend_mode(&mut self) -> Result<BlendMode, DecodeError> {
+        let index = usize::from(self.read_u8()?);
+        BLEND_MODES
+            .get(index)
+            .copied()
+            .ok_or_else(|| self.invalid())
+    }
+
+    pub fn read_spread_mode(&mut self) -> Result<SpreadMode, DecodeError> {
+        match self.read_u8()? {
+            0 => Ok(SpreadMode::Pad),
+            1 => Ok(SpreadMode::Reflect),
+            2 => Ok(SpreadMode::Repeat),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    pub fn read_color_space(&mut self) -> Result<ColorSpace, DecodeError> {
+        match self.read_u8()? {
+            0 => Ok(ColorSpace::Srgb),
+            1 => Ok(ColorSpace::LinearSrgb),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    pub fn read_gradient_interpolation(&mut self) -> Result<GradientInterpolation, DecodeError> {
+        match self.read_u8()? {
+            0 => Ok(GradientInterpolation::Srgb),
+            1 => Ok(GradientInterpolation::LinearSrgb),
+            2 => Ok(GradientInterpolation::PremultipliedLinearSrgb),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    fn read_filter_quality(&mut self) -> Result<FilterQuality, DecodeError> {
+        match self.read_u8()? {
+            0 => Ok(FilterQuality::Nearest),
+            1 => Ok(FilterQuality::Bilinear),
+            2 => Ok(FilterQuality::Bicubic),
+            3 => Ok(FilterQuality::Trilinear),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    pub fn read_transform(&mut self) -> Result<Transform, DecodeError> {
+        let mut n = [0.0; 6];
+        for v in &mut n {
+            *v = self.read_finite_f32()?;
+        }
+
+        Ok(Transform::from_row(n[0], n[1], n[2], n[3], n[4], n[5]))
+    }
+
+    pub fn read_perspective_transform(&mut self) -> Result<PerspectiveTransform, DecodeError> {
+        let mut n = [0.0; 9];
+        for v in &mut n {
+            *v = self.read_finite_f32()?;
+        }
+
+        Ok(PerspectiveTransform::from_row(
+            n[0], n[1], n[2], n[3], n[4], n[5], n[6], n[7], n[8],
+        ))
+    }
+}
@@ -6,6 +6,9 @@
 
 use tiny_skia_path::{NormalizedF32, Scalar};
 
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use tiny_skia_path::NoStdFloat;
+
 /// 8-bit type for an alpha value. 255 is 100% opaque, zero is 100% transparent.
 pub type AlphaU8 = u8;
 
@@ -357,6 +360,18 @@ pub struct PremultipliedColor {
 }
 
 impl PremultipliedColor {
+    /// Creates a new color, clamping components to the valid range.
+    pub(crate) fn from_rgba_clamped(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let a = NormalizedF32::new_clamped(a);
+        let c = |v: f32| NormalizedF32::new_clamped(v.min(a.get()));
+        PremultipliedColor {
+            r: c(r),
+            g: c(g),
+            b: c(b),
+            a,
+        }
+    }
+
     /// Returns color's red component.
     ///
     /// - The value is guarantee to be in a 0..=1 range.
@@ -410,6 +425,46 @@ impl PremultipliedColor {
     }
 }
 
+/// A working color space.
+///
+/// Defines in which space blending, coverage interpolation and filtering results
+/// are computed. Pixmaps are always stored as sRGB-encoded values.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum ColorSpace {
+    /// Operates directly on sRGB-encoded values.
+    ///
+    /// Fast, but produces darker anti-aliased edges and blends.
+    /// This is what most 2D libraries, including Skia by default, do.
+    #[default]
+    Srgb,
+
+    /// Converts source and destination colors into linear sRGB before blending
+    /// and back into sRGB before storing.
+    ///
+    /// Produces gamma-correct results, but forces the high precision pipeline.
+    LinearSrgb,
+}
+
+/// Converts an sRGB-encoded value into a linear one.
+#[inline]
+pub(crate) fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c * (1.0 / 12.92)
+    } else {
+        ((c + 0.055) * (1.0 / 1.055)).powf(2.4)
+    }
+}
+
+/// Converts a linear value into an sRGB-encoded one.
+#[inline]
+pub(crate) fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 /// Return a*b/255, rounding any fractional bits.
 pub fn premultiply_u8(c: u8, a: u8) -> u8 {
     let prod = u32::from(c) * u32::from(a) + 128;
@@ -434,6 +489,18 @@ fn color_f32_to_u8(
 mod tests {
     use super::*;
 
+    #[test]
+    fn srgb_transfer_roundtrip() {
+        for i in 0..=255 {
+            let c = i as f32 / 255.0;
+            assert!((linear_to_srgb(srgb_to_linear(c)) - c).abs() < 1e-5);
+        }
+
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+        assert!((srgb_to_linear(0.5) - 0.21404).abs() < 1e-4);
+    }
+
     #[test]
     fn premultiply_u8() {
         assert_eq!(
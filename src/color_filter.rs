@@ -0,0 +1,184 @@
+// Copyright 2006 The Android Open Source Project
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::boxed::Box;
+
+use crate::{BlendMode, Color};
+
+use crate::codec::{DecodeError, Reader, Writer};
+
+use crate::pipeline::{self, RasterPipelineBuilder};
+
+/// A color filter.
+///
+/// Transforms colors produced by a shader before they are blended onto the destination.
+///
+/// Essentially a `SkColorFilter`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ColorFilter {
+    pub(crate) kind: ColorFilterKind,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum ColorFilterKind {
+    Matrix([f32; 20]),
+    // Tables in RGBA order.
+    Table(Box<[[u8; 256]; 4]>),
+    Blend(Color, BlendMode),
+}
+
+impl ColorFilter {
+    /// Creates a 4x5 color matrix filter.
+    ///
+    /// The matrix is in row-major order and is applied to unpremultiplied colors.
+    /// Each row produces one of the RGBA channels:
+    ///
+    /// `R' = m[0]*R + m[1]*G + m[2]*B + m[3]*A + m[4]`
+    ///
+    /// Just like in SVG's `feColorMatrix`, the last column is in 0..1 range and not in 0..255.
+    /// The result is clamped to the 0..1 range.
+    ///
+    /// Returns `None` when any of the values is not finite.
+    pub fn matrix(matrix: [f32; 20]) -> Option<Self> {
+        if !matrix.iter().all(|n| n.is_finite()) {
+            return None;
+        }
+
+        Some(ColorFilter {
+            kind: ColorFilterKind::Matrix(matrix),
+        })
+    }
+
+    /// Creates a lookup table filter that uses the same table for all channels.
+    ///
+    /// See [`ColorFilter::table_rgba`] for details.
+    pub fn table(table: &[u8; 256]) -> Self {
+        Self::table_rgba(Some(table), Some(table), Some(table), Some(table))
+    }
+
+    /// Creates a per-channel lookup table filter.
+    ///
+    /// Tables are indexed by unpremultiplied channel values in 0..=255 range.
+    /// A missing table leaves the channel unchanged.
+    pub fn table_rgba(
+        r: Option<&[u8; 256]>,
+        g: Option<&[u8; 256]>,
+        b: Option<&[u8; 256]>,
+        a: Option<&[u8; 256]>,
+    ) -> Self {
+        let mut identity = [0; 256];
+        for (i, v) in identity.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+
+        let tables = [
+            *r.unwrap_or(&identity),
+            *g.unwrap_or(&identity),
+            *b.unwrap_or(&identity),
+            *a.unwrap_or(&identity),
+        ];
+
+        ColorFilter {
+            kind: ColorFilterKind::Table(Box::new(tables)),
+        }
+    }
+
+    /// Creates a filter that blends `color` with the filtered color.
+    ///
+    /// `color` acts as a source and the filtered color acts as a destination.
+    /// Therefore, `BlendMode::SourceIn` would tint the filtered color.
+    pub fn blend(color: Color, mode: BlendMode) -> Self {
+        ColorFilter {
+            kind: ColorFilterKind::Blend(color, mode),
+        }
+    }
+
+    /// Checks if the filter keeps opaque colors opaque.
+    pub(crate) fn preserves_opaque(&self) -> bool {
+        match self.kind {
+            ColorFilterKind::Matrix(ref m) => {
+                m[15] == 0.0 && m[16] == 0.0 && m[17] == 0.0 && m[18] + m[19] >= 1.0
+            }
+            ColorFilterKind::Table(ref tables) => tables[3][255] == 255,
+            ColorFilterKind::Blend(..) => false,
+        }
+    }
+
+    pub(crate) fn push_stages(&self, p: &mut RasterPipelineBuilder) {
+        match self.kind {
+            ColorFilterKind::Matrix(ref m) => {
+                p.push(pipeline::Stage::Unpremultiply);
+                p.push(pipeline::Stage::ColorMatrix);
+                p.push(pipeline::Stage::Clamp0);
+                p.push(pipeline::Stage::ClampA);
+                p.push(pipeline::Stage::Premultiply);
+                p.ctx.color_filter.matrix = *m;
+            }
+            ColorFilterKind::Table(ref tables) => {
+                p.push(pipeline::Stage::Unpremultiply);
+                p.push(pipeline::Stage::ColorTable);
+                p.push(pipeline::Stage::Premultiply);
+                p.ctx.color_filter.tables = tables.iter().flatten().copied().collect();
+            }
+            ColorFilterKind::Blend(color, mode) => {
+                // The filtered color becomes the destination.
+                p.push(pipeline::Stage::MoveSourceToDestination);
+                p.push(pipeline::Stage::FilterColor);
+                if let Some(stage) = mode.to_stage() {
+                    p.push(stage);
+                }
+
+                let c = color.premultiply();
+                p.ctx.color_filter.color = [c.red(), c.green(), c.blue(), c.alpha()];
+            }
+        }
+    }
+
+    pub(crate) fn encode(&self, w: &mut Writer) {
+        match self.kind {
+            ColorFilterKind::Matrix(ref m) => {
+                w.write_u8(0);
+                for n in m {
+                    w.write_f32(*n);
+                }
+            }
+            ColorFilterKind::Table(ref tables) => {
+                w.write_u8(1);
+                for n in tables.iter().flatten() {
+                    w.write_u8(*n);
+                }
+            }
+            ColorFilterKind::Blend(color, mode) => {
+                w.write_u8(2);
+                w.write_color(color);
+                w.write_blend_mode(mode);
+            }
+        }
+    }
+
+    pub(crate) fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        let kind = match r.read_u8()? {
+            0 => {
+                let mut m = [0.0; 20];
+                for n in &mut m {
+                    *n = r.read_finite_f32()?;
+                }
+
+                ColorFilterKind::Matrix(m)
+            }
+            1 => ColorFilterKind::Table(Box::new([
+                r.read_array()?,
+                r.read_array()?,
+                r.read_array()?,
+                r.read_array()?,
+            ])),
+            2 => ColorFilterKind::Blend(r.read_color()?, r.read_blend_mode()?),
+            _ => return Err(r.invalid()),
+        };
+
+        Ok(ColorFilter { kind })
+    }
+}
@@ -5,6 +5,7 @@
 It doesn't look like cairo, QPainter (Qt), HTML Canvas or even Skia itself.
 Instead, `tiny-skia` provides a set of low-level drawing APIs
 and a user should manage the world transform, clipping mask and style manually.
+For simple cases, an optional stateful [`Canvas`] wrapper is available as well.
 
 See the `examples/` directory for usage examples.
 */
@@ -35,9 +36,14 @@ extern crate std;
 extern crate alloc;
 
 mod alpha_runs;
+mod bitmap;
 mod blend_mode;
 mod blitter;
+mod blur;
+mod canvas;
+mod codec;
 mod color;
+mod color_filter;
 mod edge;
 mod edge_builder;
 mod edge_clipper;
@@ -46,28 +52,45 @@ mod geom;
 mod line_clipper;
 mod mask;
 mod math;
+mod patch;
 mod path64;
 mod path_geometry;
+mod picture;
 mod pipeline;
 mod pixmap;
+mod resize;
 mod scan;
 mod shaders;
+mod shadow;
+mod vertices;
 mod wide;
 
 mod painter; // Keep it under `pixmap` for a better order in the docs.
 
+pub use bitmap::{Bitmap, BitmapMut, ColorType};
 pub use blend_mode::BlendMode;
-pub use color::{Color, ColorU8, PremultipliedColor, PremultipliedColorU8};
+pub use blur::BlurEdgeMode;
+pub use canvas::Canvas;
+pub use codec::DecodeError;
+pub use color::{Color, ColorSpace, ColorU8, PremultipliedColor, PremultipliedColorU8};
 pub use color::{ALPHA_OPAQUE, ALPHA_TRANSPARENT, ALPHA_U8_OPAQUE, ALPHA_U8_TRANSPARENT};
+pub use color_filter::ColorFilter;
 pub use mask::{Mask, MaskType};
-pub use painter::{FillRule, Paint};
+pub use painter::Paint;
+pub use picture::{Picture, PictureRecorder};
 pub use pixmap::{Pixmap, PixmapMut, PixmapRef, BYTES_PER_PIXEL};
-pub use shaders::{FilterQuality, GradientStop, PixmapPaint, SpreadMode};
-pub use shaders::{LinearGradient, Pattern, RadialGradient, Shader};
+pub use resize::ResizeFilter;
+pub use shaders::{FilterQuality, GradientInterpolation, GradientStop, PixmapPaint, SpreadMode};
+pub use shaders::{LinearGradient, Mipmaps, Pattern, RadialGradient, Shader, SweepGradient};
+pub use shadow::Shadow;
 
-pub use tiny_skia_path::{IntRect, IntSize, NonZeroRect, Point, Rect, Size, Transform};
+pub use tiny_skia_path::{ContourMeasure, PathMeasure};
+pub use tiny_skia_path::{FillRule, IntRect, IntSize, NonZeroRect, Point, RRect, Rect, Size};
 pub use tiny_skia_path::{LineCap, LineJoin, Stroke, StrokeDash};
-pub use tiny_skia_path::{Path, PathBuilder, PathSegment, PathSegmentsIter, PathStroker};
+pub use tiny_skia_path::{Path, PathBuilder, PathOp, PathSegment, PathSegmentsIter, PathStroker};
+pub use tiny_skia_path::{Path1DStyle, PathEffect};
+pub use tiny_skia_path::{PerspectiveTransform, Transform};
+pub use tiny_skia_path::{SvgPathError, SvgPathErrorKind};
 
 /// An integer length that is guarantee to be > 0
 type LengthU32 = core::num::NonZeroU32;
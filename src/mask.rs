@@ -16,7 +16,7 @@ use crate::painter::DrawTiler;
 use crate::pipeline::RasterPipelineBlitter;
 use crate::pixmap::SubPixmapMut;
 use crate::scan;
-use crate::{FillRule, PixmapRef};
+use crate::{ColorType, FillRule, PixmapRef};
 
 /// A mask type.
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -66,12 +66,12 @@ impl Mask {
         // TODO: optimize
         match mask_type {
             MaskType::Alpha => {
-                for (p, a) in pixmap.pixels().iter().zip(mask.data.as_mut_slice()) {
+                for (p, a) in pixmap.rows().flatten().zip(mask.data.as_mut_slice()) {
                     *a = p.alpha();
                 }
             }
             MaskType::Luminance => {
-                for (p, ma) in pixmap.pixels().iter().zip(mask.data.as_mut_slice()) {
+                for (p, ma) in pixmap.rows().flatten().zip(mask.data.as_mut_slice()) {
                     // Normalize.
                     let mut r = f32::from(p.red()) / 255.0;
                     let mut g = f32::from(p.green()) / 255.0;
@@ -139,6 +139,7 @@ impl Mask {
             size: self.size,
             real_width: self.size.width(),
             data: &self.data,
+            row_offset: 0,
         }
     }
 
@@ -151,14 +152,31 @@ impl Mask {
             size: rect.size(),
             real_width: self.size.width(),
             data: &self.data[offset..],
+            row_offset: 0,
         })
     }
 
+    /// Returns a mask region that matches a `PixmapMut::bands` band.
+    pub(crate) fn band(&self, top: u32, size: IntSize) -> SubMaskRef<'_> {
+        let row_bytes = self.width() as usize;
+        let start = top as usize * row_bytes;
+        let end = start + size.height() as usize * row_bytes;
+
+        SubMaskRef {
+            size,
+            real_width: self.size.width(),
+            data: &self.data[start..end],
+            row_offset: top,
+        }
+    }
+
     pub(crate) fn as_subpixmap(&mut self) -> SubPixmapMut<'_> {
         SubPixmapMut {
             size: self.size,
             real_width: self.size.width() as usize,
             data: &mut self.data,
+            row_offset: 0,
+            color_type: ColorType::A8,
         }
     }
 
@@ -171,9 +189,34 @@ impl Mask {
             size: rect.size(),
             real_width: self.size.width() as usize,
             data: &mut self.data[offset..],
+            row_offset: 0,
+            color_type: ColorType::A8,
         })
     }
 
+    /// Returns a copy of the mask that intersects the `rect`.
+    pub(crate) fn clone_rect(&self, rect: IntRect) -> Option<Self> {
+        let rect = self.size.to_int_rect(0, 0).intersect(&rect)?;
+        let mut data = Vec::with_capacity(rect.width() as usize * rect.height() as usize);
+        for y in rect.top()..rect.bottom() {
+            let start = y as usize * self.width() as usize + rect.left() as usize;
+            data.extend_from_slice(&self.data[start..start + rect.width() as usize]);
+        }
+
+        Some(Self {
+            data,
+            size: rect.size(),
+        })
+    }
+
+    /// Multiplies the mask by another one of the same size.
+    pub(crate) fn intersect(&mut self, other: &Self) {
+        debug_assert_eq!(self.size, other.size);
+        for (a, b) in self.data.iter_mut().zip(other.data.iter()) {
+            *a = crate::color::premultiply_u8(*a, *b);
+        }
+    }
+
     /// Loads a PNG file into a `Mask`.
     ///
     /// Only grayscale images are supported.
@@ -346,10 +389,7 @@ impl Mask {
     ) {
         let mut submask = Self::new(self.width(), self.height()).unwrap();
         submask.fill_path(path, fill_rule, anti_alias, transform);
-
-        for (a, b) in self.data.iter_mut().zip(submask.data.iter()) {
-            *a = crate::color::premultiply_u8(*a, *b);
-        }
+        self.intersect(&submask);
     }
 
     /// Inverts the mask.
@@ -380,6 +420,7 @@ pub struct SubMaskRef<'a> {
     pub data: &'a [u8],
     pub size: IntSize,
     pub real_width: u32,
+    pub row_offset: u32, // see `SubPixmapMut::row_offset`
 }
 
 impl<'a> SubMaskRef<'a> {
@@ -387,6 +428,7 @@ impl<'a> SubMaskRef<'a> {
         crate::pipeline::MaskCtx {
             data: self.data,
             real_width: self.real_width,
+            row_offset: self.row_offset,
         }
     }
 }
@@ -6,11 +6,16 @@
 
 use crate::*;
 
+use rayon::prelude::*;
 use tiny_skia_path::{PathStroker, Scalar, SCALAR_MAX};
 
+use crate::alpha_runs::AlphaRun;
+use crate::blitter::{Blitter, Mask as BlitterMask};
+use crate::color::AlphaU8;
 use crate::geom::ScreenIntRect;
 use crate::mask::SubMaskRef;
-use crate::pipeline::{RasterPipelineBlitter, RasterPipelineBuilder};
+use crate::math::LENGTH_U32_ONE;
+use crate::pipeline::{RasterPipelineBlitter, RasterPipelineBuilder, VertexColors};
 use crate::pixmap::SubPixmapMut;
 use crate::scan;
 
@@ -18,16 +23,6 @@ use crate::geom::IntSizeExt;
 #[cfg(all(not(feature = "std"), feature = "no-std-float"))]
 use tiny_skia_path::NoStdFloat;
 
-/// A path filling rule.
-#[derive(Copy, Clone, PartialEq, Debug, Default)]
-pub enum FillRule {
-    /// Specifies that "inside" is computed by a non-zero sum of signed edge crossings.
-    #[default]
-    Winding,
-    /// Specifies that "inside" is computed by an odd number of edge crossings.
-    EvenOdd,
-}
-
 /// Controls how a shape should be painted.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Paint<'a> {
@@ -36,11 +31,24 @@ pub struct Paint<'a> {
     /// Default: black color
     pub shader: Shader<'a>,
 
+    /// A color filter applied to the shader output before blending.
+    ///
+    /// Default: None
+    pub color_filter: Option<ColorFilter>,
+
     /// Paint blending mode.
     ///
     /// Default: SourceOver
     pub blend_mode: BlendMode,
 
+    /// A working color space used for blending and anti-aliasing.
+    ///
+    /// `ColorSpace::LinearSrgb` produces gamma-correct edges and blends,
+    /// but always uses the high quality pipeline.
+    ///
+    /// Default: Srgb
+    pub color_space: ColorSpace,
+
     /// Enables anti-aliased painting.
     ///
     /// Default: true
@@ -63,15 +71,29 @@ pub struct Paint<'a> {
     ///
     /// Default: false
     pub force_hq_pipeline: bool,
+
+    /// Renders using multiple threads.
+    ///
+    /// When set, the target pixmap is split into row bands, which are rasterized
+    /// and blended in parallel. The output is identical to the single-threaded one.
+    ///
+    /// Affects only `fill_rect`, `fill_path`, `stroke_path` and `draw_pixmap`.
+    /// Mainly useful for large pixmaps, since small ones would not be split at all.
+    ///
+    /// Default: false
+    pub parallel: bool,
 }
 
 impl Default for Paint<'_> {
     fn default() -> Self {
         Paint {
             shader: Shader::SolidColor(Color::BLACK),
+            color_filter: None,
             blend_mode: BlendMode::default(),
+            color_space: ColorSpace::default(),
             anti_alias: true,
             force_hq_pipeline: false,
+            parallel: false,
         }
     }
 }
@@ -109,6 +131,34 @@ impl Pixmap {
         self.as_mut().fill_rect(rect, paint, transform, mask);
     }
 
+    /// Draws a filled rounded rectangle onto the pixmap.
+    ///
+    /// See [`PixmapMut::fill_rrect`](struct.PixmapMut.html#method.fill_rrect) for details.
+    pub fn fill_rrect(
+        &mut self,
+        rrect: RRect,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut().fill_rrect(rrect, paint, transform, mask);
+    }
+
+    /// Strokes a rounded rectangle.
+    ///
+    /// See [`PixmapMut::stroke_rrect`](struct.PixmapMut.html#method.stroke_rrect) for details.
+    pub fn stroke_rrect(
+        &mut self,
+        rrect: RRect,
+        paint: &Paint,
+        stroke: &Stroke,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut()
+            .stroke_rrect(rrect, paint, stroke, transform, mask);
+    }
+
     /// Draws a filled path onto the pixmap.
     ///
     /// See [`PixmapMut::fill_path`](struct.PixmapMut.html#method.fill_path) for details.
@@ -155,6 +205,52 @@ impl Pixmap {
             .draw_pixmap(x, y, pixmap, paint, transform, mask);
     }
 
+    /// Draws a filled path onto the pixmap using a perspective transform.
+    ///
+    /// See [`PixmapMut::fill_path_perspective`](struct.PixmapMut.html#method.fill_path_perspective) for details.
+    pub fn fill_path_perspective(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: PerspectiveTransform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut()
+            .fill_path_perspective(path, paint, fill_rule, transform, mask);
+    }
+
+    /// Strokes a path using a perspective transform.
+    ///
+    /// See [`PixmapMut::stroke_path_perspective`](struct.PixmapMut.html#method.stroke_path_perspective) for details.
+    pub fn stroke_path_perspective(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        stroke: &Stroke,
+        transform: PerspectiveTransform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut()
+            .stroke_path_perspective(path, paint, stroke, transform, mask);
+    }
+
+    /// Draws a `Pixmap` on top of the current `Pixmap` using a perspective transform.
+    ///
+    /// See [`PixmapMut::draw_pixmap_perspective`](struct.PixmapMut.html#method.draw_pixmap_perspective) for details.
+    pub fn draw_pixmap_perspective(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: PixmapRef,
+        paint: &PixmapPaint,
+        transform: PerspectiveTransform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut()
+            .draw_pixmap_perspective(x, y, pixmap, paint, transform, mask);
+    }
+
     /// Applies a masks.
     ///
     /// See [`PixmapMut::apply_mask`](struct.PixmapMut.html#method.apply_mask) for details.
@@ -185,22 +281,111 @@ impl PixmapMut<'_> {
         if transform.is_identity() && !DrawTiler::required(self.width(), self.height()) {
             // TODO: ignore rects outside the pixmap
 
-            let clip = self.size().to_screen_int_rect(0, 0);
+            self.blit(paint, mask, &|clip, blitter| {
+                if paint.anti_alias {
+                    scan::fill_rect_aa(&rect, clip, blitter);
+                } else {
+                    scan::fill_rect(&rect, clip, blitter);
+                }
+            });
+        } else {
+            let path = PathBuilder::from_rect(rect);
+            self.fill_path(&path, paint, FillRule::Winding, transform, mask);
+        }
+    }
 
-            let mask = mask.map(|mask| mask.as_submask());
-            let mut subpix = self.as_subpixmap();
-            let Some(mut blitter) = RasterPipelineBlitter::new(paint, mask, &mut subpix) else {
-                return // nothing to do, all good
-            };
+    /// Draws a filled rounded rectangle onto the pixmap.
+    ///
+    /// Unlike filling a path created via `PathBuilder::push_rrect`, coverage is computed
+    /// analytically, without building edges, which is a lot faster.
+    /// This is possible only when `transform` contains just scale and translate.
+    /// Otherwise, the rounded rectangle is filled as a path.
+    pub fn fill_rrect(
+        &mut self,
+        rrect: RRect,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        let device_rrect = if DrawTiler::required(self.width(), self.height()) {
+            None
+        } else {
+            rrect.transform(transform)
+        };
 
-            if paint.anti_alias {
-                scan::fill_rect_aa(&rect, &clip, &mut blitter);
-            } else {
-                scan::fill_rect(&rect, &clip, &mut blitter);
+        match device_rrect {
+            Some(device_rrect) => {
+                self.fill_device_rrect(&device_rrect, None, paint, transform, mask);
+            }
+            None => {
+                let Some(path) = PathBuilder::from_rrect(rrect) else {
+                    log::warn!("empty rounded rectangles cannot be filled");
+                    return;
+                };
+
+                self.fill_path(&path, paint, FillRule::Winding, transform, mask);
             }
+        }
+    }
+
+    /// Strokes a rounded rectangle.
+    ///
+    /// Just like [`fill_rrect`](Self::fill_rrect), uses analytic coverage when possible.
+    /// Which requires `transform` with just scale and translate, a non-dashed stroke
+    /// wider than a hairline and corners which are either circular or square
+    /// with a miter join. Otherwise, the rounded rectangle is stroked as a path.
+    pub fn stroke_rrect(
+        &mut self,
+        rrect: RRect,
+        paint: &Paint,
+        stroke: &Stroke,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        let device_rrects = if DrawTiler::required(self.width(), self.height()) {
+            None
         } else {
-            let path = PathBuilder::from_rect(rect);
-            self.fill_path(&path, paint, FillRule::Winding, transform, mask);
+            stroke_rrects(&rrect, paint, stroke, transform)
+        };
+
+        match device_rrects {
+            Some((outer, inner)) => {
+                self.fill_device_rrect(&outer, inner.as_ref(), paint, transform, mask);
+            }
+            None => {
+                let Some(path) = PathBuilder::from_rrect(rrect) else {
+                    log::warn!("empty rounded rectangles cannot be stroked");
+                    return;
+                };
+
+                self.stroke_path(&path, paint, stroke, transform, mask);
+            }
+        }
+    }
+
+    // Fills a rounded rectangle in device coordinates, excluding an optional inner one.
+    fn fill_device_rrect(
+        &mut self,
+        rrect: &RRect,
+        inner: Option<&RRect>,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        let mut paint = paint.clone();
+        paint.shader.transform(transform);
+
+        let clip = self.size().to_screen_int_rect(0, 0);
+        let mask = mask.map(|mask| mask.as_submask());
+        let mut subpix = self.as_subpixmap();
+        let Some(mut blitter) = RasterPipelineBlitter::new(&paint, mask, &mut subpix) else {
+            return // nothing to do, all good
+        };
+
+        if paint.anti_alias {
+            scan::rrect::fill_rrect_aa(rrect, inner, &clip, &mut blitter);
+        } else {
+            scan::rrect::fill_rrect(rrect, inner, &clip, &mut blitter);
         }
     }
 
@@ -272,18 +457,13 @@ impl PixmapMut<'_> {
                     paint.shader.transform(ts);
                 }
             } else {
-                let clip_rect = self.size().to_screen_int_rect(0, 0);
-                let submask = mask.map(|mask| mask.as_submask());
-                let mut subpix = self.as_subpixmap();
-                let Some(mut blitter) = RasterPipelineBlitter::new(paint, submask, &mut subpix) else {
-                    return // nothing to do, all good
-                };
-
-                if paint.anti_alias {
-                    scan::path_aa::fill_path(path, fill_rule, &clip_rect, &mut blitter);
-                } else {
-                    scan::path::fill_path(path, fill_rule, &clip_rect, &mut blitter);
-                }
+                self.blit(paint, mask, &|clip, blitter| {
+                    if paint.anti_alias {
+                        scan::path_aa::fill_path(path, fill_rule, clip, blitter);
+                    } else {
+                        scan::path::fill_path(path, fill_rule, clip, blitter);
+                    }
+                });
             }
         } else {
             let Some(path) = path.clone().transform(transform) else {
@@ -397,10 +577,9 @@ impl PixmapMut<'_> {
                     paint.shader.transform(ts);
                 }
             } else {
-                let subpix = &mut self.as_subpixmap();
-                let submask = mask.map(|mask| mask.as_submask());
-                if transform.is_identity() {
-                    Self::stroke_hairline(path, &paint, stroke.line_cap, submask, subpix);
+                let transformed_path;
+                let path = if transform.is_identity() {
+                    path
                 } else {
                     paint.shader.transform(transform);
 
@@ -410,8 +589,17 @@ impl PixmapMut<'_> {
                         return;
                     };
 
-                    Self::stroke_hairline(&path, &paint, stroke.line_cap, submask, subpix);
-                }
+                    transformed_path = path;
+                    &transformed_path
+                };
+
+                self.blit(&paint, mask, &|clip, blitter| {
+                    if paint.anti_alias {
+                        scan::hairline_aa::stroke_path(path, stroke.line_cap, clip, blitter);
+                    } else {
+                        scan::hairline::stroke_path(path, stroke.line_cap, clip, blitter);
+                    }
+                });
             }
         } else {
             let Some(path) = path.stroke(stroke, res_scale) else {
@@ -423,6 +611,140 @@ impl PixmapMut<'_> {
         }
     }
 
+    // Creates a blitter for the whole pixmap and passes it to `draw`.
+    //
+    // In the parallel mode, the pixmap is split into row bands instead and `draw` is called
+    // for each band on a separate thread. The clip rect is still the whole pixmap,
+    // so the scan conversion is exactly the same and each band simply ignores
+    // rows outside of it.
+    fn blit(
+        &mut self,
+        paint: &Paint,
+        mask: Option<&Mask>,
+        draw: &(dyn Fn(&ScreenIntRect, &mut dyn Blitter) + Sync),
+    ) {
+        self.blit_with_vertex_colors(paint, None, mask, draw);
+    }
+
+    fn blit_with_vertex_colors(
+        &mut self,
+        paint: &Paint,
+        vertex_colors: Option<VertexColors>,
+        mask: Option<&Mask>,
+        draw: &(dyn Fn(&ScreenIntRect, &mut dyn Blitter) + Sync),
+    ) {
+        let clip = self.size().to_screen_int_rect(0, 0);
+
+        if paint.parallel {
+            if let Some(band_height) = band_height(self.height()) {
+                let bands = self.bands(band_height);
+                bands.into_par_iter().for_each(|mut band| {
+                    let top = band.row_offset as u32;
+                    let bottom = top + band.size.height();
+                    let submask = mask.map(|mask| mask.band(top, band.size));
+                    let Some(blitter) = RasterPipelineBlitter::new_with_vertex_colors(
+                        paint,
+                        vertex_colors,
+                        submask,
+                        &mut band,
+                    ) else {
+                        return; // nothing to do, all good
+                    };
+
+                    let mut blitter = BandBlitter {
+                        blitter,
+                        top,
+                        bottom,
+                    };
+                    draw(&clip, &mut blitter);
+                });
+
+                return;
+            }
+        }
+
+        let submask = mask.map(|mask| mask.as_submask());
+        let mut subpix = self.as_subpixmap();
+        let Some(mut blitter) = RasterPipelineBlitter::new_with_vertex_colors(
+            paint,
+            vertex_colors,
+            submask,
+            &mut subpix,
+        ) else {
+            return; // nothing to do, all good
+        };
+
+        draw(&clip, &mut blitter);
+    }
+
+    /// Fills a triangle in device coordinates, blending `vertex_colors` with the shader.
+    pub(crate) fn fill_triangle(
+        &mut self,
+        triangle: &Path,
+        paint: &Paint,
+        vertex_colors: Option<VertexColors>,
+        mask: Option<&Mask>,
+    ) {
+        if is_too_big_for_math(triangle) {
+            log::warn!("triangle coordinates are too big");
+            return;
+        }
+
+        let fill = |path: &Path, clip: &ScreenIntRect, blitter: &mut dyn Blitter| {
+            if paint.anti_alias {
+                scan::path_aa::fill_path(path, FillRule::Winding, clip, blitter);
+            } else {
+                scan::path::fill_path(path, FillRule::Winding, clip, blitter);
+            }
+        };
+
+        let Some(tiler) = DrawTiler::new(self.width(), self.height()) else {
+            self.blit_with_vertex_colors(paint, vertex_colors, mask, &|clip, blitter| {
+                fill(triangle, clip, blitter);
+            });
+            return;
+        };
+
+        for tile in tiler {
+            let (tx, ty) = (tile.x() as f32, tile.y() as f32);
+            let ts = Transform::from_translate(-tx, -ty);
+            let Some(path) = triangle.clone().transform(ts) else {
+                continue;
+            };
+
+            let mut paint = paint.clone();
+            paint.shader.transform(ts);
+            let vertex_colors = vertex_colors.map(|mut colors| {
+                for c in [
+                    &mut colors.ctx.r,
+                    &mut colors.ctx.g,
+                    &mut colors.ctx.b,
+                    &mut colors.ctx.a,
+                ] {
+                    c[2] += c[0] * tx + c[1] * ty;
+                }
+                colors
+            });
+
+            let clip_rect = tile.size().to_screen_int_rect(0, 0);
+            let Some(mut subpix) = self.subpixmap(tile.to_int_rect()) else {
+                continue; // technically unreachable
+            };
+
+            let submask = mask.and_then(|mask| mask.submask(tile.to_int_rect()));
+            let Some(mut blitter) = RasterPipelineBlitter::new_with_vertex_colors(
+                &paint,
+                vertex_colors,
+                submask,
+                &mut subpix,
+            ) else {
+                continue; // nothing to do, all good
+            };
+
+            fill(&path, &clip_rect, &mut blitter);
+        }
+    }
+
     /// A stroking for paths with subpixel/hairline width.
     fn stroke_hairline(
         path: &Path,
@@ -453,6 +775,20 @@ impl PixmapMut<'_> {
         paint: &PixmapPaint,
         transform: Transform,
         mask: Option<&Mask>,
+    ) {
+        self.draw_pixmap_with_mipmaps(x, y, pixmap, None, paint, transform, mask);
+    }
+
+    // Like `draw_pixmap`, but uses prebuilt mipmaps of the `pixmap`, if any.
+    pub(crate) fn draw_pixmap_with_mipmaps(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: PixmapRef,
+        mipmaps: Option<&Mipmaps>,
+        paint: &PixmapPaint,
+        transform: Transform,
+        mask: Option<&Mask>,
     ) {
         let rect = pixmap.size().to_int_rect(x, y).to_rect();
 
@@ -460,23 +796,132 @@ impl PixmapMut<'_> {
         // TODO: partially clipped
         // TODO: clipped out
 
-        // Translate pattern as well as bounds.
-        let patt_transform = Transform::from_translate(x as f32, y as f32);
-
-        let paint = Paint {
-            shader: Pattern::new(
-                pixmap,
-                SpreadMode::Pad, // Pad, otherwise we will get weird borders overlap.
-                paint.quality,
-                paint.opacity,
-                patt_transform,
-            ),
-            blend_mode: paint.blend_mode,
-            anti_alias: false,        // Skia doesn't use it too.
-            force_hq_pipeline: false, // Pattern will use hq anyway.
+        let paint = pixmap_paint(x, y, pixmap, mipmaps, paint);
+        self.fill_rect(rect, &paint, transform, mask);
+    }
+
+    /// Draws a filled path onto the pixmap using a perspective transform.
+    ///
+    /// The path is projected using [`Path::transform_perspective`] and the paint shader
+    /// is sampled in a perspective-correct way.
+    /// Parts of the path behind the viewer are clipped away.
+    ///
+    /// Affine transforms are handled by [`fill_path`](Self::fill_path).
+    pub fn fill_path_perspective(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: PerspectiveTransform,
+        mask: Option<&Mask>,
+    ) {
+        if let Some(ts) = transform.to_affine() {
+            self.fill_path(path, paint, fill_rule, ts, mask);
+            return;
+        }
+
+        let Some(path) = path.clone().transform_perspective(transform) else {
+            log::warn!("path perspective transformation failed");
+            return;
         };
 
-        self.fill_rect(rect, &paint, transform, mask);
+        let mut paint = paint.clone();
+        paint.shader.transform_perspective(transform);
+
+        self.fill_path(&path, &paint, fill_rule, Transform::identity(), mask);
+    }
+
+    /// Strokes a path using a perspective transform.
+    ///
+    /// Unlike [`stroke_path`](Self::stroke_path), the stroke is built before the projection,
+    /// therefore its width is affected by the perspective as well.
+    /// Hairlines are projected as is.
+    pub fn stroke_path_perspective(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        stroke: &Stroke,
+        transform: PerspectiveTransform,
+        mask: Option<&Mask>,
+    ) {
+        if let Some(ts) = transform.to_affine() {
+            self.stroke_path(path, paint, stroke, ts, mask);
+            return;
+        }
+
+        if stroke.width < 0.0 {
+            log::warn!("negative stroke width isn't allowed");
+            return;
+        }
+
+        if stroke.width == 0.0 {
+            let Some(path) = path.clone().transform_perspective(transform) else {
+                log::warn!("path perspective transformation failed");
+                return;
+            };
+
+            let mut paint = paint.clone();
+            paint.shader.transform_perspective(transform);
+
+            self.stroke_path(&path, &paint, stroke, Transform::identity(), mask);
+            return;
+        }
+
+        let res_scale = perspective_resolution_scale(path.bounds(), &transform);
+
+        let dash_path;
+        let path = if let Some(ref dash) = stroke.dash {
+            dash_path = match path.dash(dash, res_scale) {
+                Some(v) => v,
+                None => {
+                    log::warn!("path dashing failed");
+                    return;
+                }
+            };
+            &dash_path
+        } else {
+            path
+        };
+
+        let Some(path) = PathStroker::new().stroke(path, stroke, res_scale) else {
+            log::warn!("path stroking failed");
+            return;
+        };
+
+        self.fill_path_perspective(&path, paint, FillRule::Winding, transform, mask);
+    }
+
+    /// Draws a `Pixmap` on top of the current `Pixmap` using a perspective transform.
+    ///
+    /// The same as filling a rectangle with a `pixmap` pattern
+    /// using [`fill_path_perspective`](Self::fill_path_perspective).
+    pub fn draw_pixmap_perspective(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: PixmapRef,
+        paint: &PixmapPaint,
+        transform: PerspectiveTransform,
+        mask: Option<&Mask>,
+    ) {
+        if let Some(ts) = transform.to_affine() {
+            self.draw_pixmap(x, y, pixmap, paint, ts, mask);
+            return;
+        }
+
+        let rect = pixmap.size().to_int_rect(x, y).to_rect();
+
+        let mut paint = pixmap_paint(x, y, pixmap, None, paint);
+        // Projected edges are never axis-aligned.
+        paint.anti_alias = true;
+
+        self.fill_path_perspective(
+            &PathBuilder::from_rect(rect),
+            &paint,
+            FillRule::Winding,
+            transform,
+            mask,
+        );
     }
 
     /// Applies a masks.
@@ -499,11 +944,13 @@ impl PixmapMut<'_> {
         // Just a dummy.
         let pixmap_src = PixmapRef::from_bytes(&[0, 0, 0, 0], 1, 1).unwrap();
 
+        let (load_stage, store_stage) = pipeline::load_store_stages(self.color_type());
+
         let mut p = RasterPipelineBuilder::new();
         p.push(pipeline::Stage::LoadMaskU8);
-        p.push(pipeline::Stage::LoadDestination);
+        p.push(load_stage);
         p.push(pipeline::Stage::DestinationIn);
-        p.push(pipeline::Stage::Store);
+        p.push(store_stage);
         let mut p = p.compile();
         let rect = self.size().to_screen_int_rect(0, 0);
         p.run(
@@ -516,6 +963,99 @@ impl PixmapMut<'_> {
     }
 }
 
+fn pixmap_paint<'a>(
+    x: i32,
+    y: i32,
+    pixmap: PixmapRef<'a>,
+    mipmaps: Option<&'a Mipmaps>,
+    paint: &PixmapPaint,
+) -> Paint<'a> {
+    // Translate pattern as well as bounds.
+    let patt_transform = Transform::from_translate(x as f32, y as f32);
+
+    // Pad, otherwise we will get weird borders overlap.
+    let shader = match mipmaps {
+        Some(mipmaps) if paint.quality == FilterQuality::Trilinear => {
+            Pattern::new_with_mipmaps(mipmaps, SpreadMode::Pad, paint.opacity, patt_transform)
+        }
+        _ => Pattern::new(
+            pixmap,
+            SpreadMode::Pad,
+            paint.quality,
+            paint.opacity,
+            patt_transform,
+        ),
+    };
+
+    Paint {
+        shader,
+        color_filter: paint.color_filter.clone(),
+        blend_mode: paint.blend_mode,
+        color_space: paint.color_space,
+        anti_alias: false,        // Skia doesn't use it too.
+        force_hq_pipeline: false, // Pattern will use hq anyway.
+        parallel: paint.parallel,
+    }
+}
+
+// The projection scales the path the most where `w` is the smallest,
+// which is always at one of the bounding box corners.
+fn perspective_resolution_scale(bounds: Rect, ts: &PerspectiveTransform) -> f32 {
+    let affine = Transform::from_row(ts.sx, ts.ky, ts.kx, ts.sy, ts.tx, ts.ty);
+    let min_w = [
+        Point::from_xy(bounds.left(), bounds.top()),
+        Point::from_xy(bounds.right(), bounds.top()),
+        Point::from_xy(bounds.right(), bounds.bottom()),
+        Point::from_xy(bounds.left(), bounds.bottom()),
+    ]
+    .iter()
+    .fold(f32::MAX, |min, p| min.min(ts.map_w(*p)));
+
+    let scale = PathStroker::compute_resolution_scale(&affine) / min_w;
+    if scale.is_finite() && scale > 0.0 {
+        scale
+    } else {
+        1.0
+    }
+}
+
+// Returns the outer and inner stroke edges in device coordinates,
+// when the stroke can be represented by them.
+fn stroke_rrects(
+    rrect: &RRect,
+    paint: &Paint,
+    stroke: &Stroke,
+    transform: Transform,
+) -> Option<(RRect, Option<RRect>)> {
+    if !(stroke.width > 0.0 && stroke.width.is_finite()) || stroke.dash.is_some() {
+        return None;
+    }
+
+    if treat_as_hairline(paint, stroke, transform).is_some() {
+        return None;
+    }
+
+    // An offset of a circular corner is a circular corner as well,
+    // while elliptical ones would require a different curve.
+    // And a square corner stays square only with a miter join.
+    let is_miter = matches!(stroke.line_join, LineJoin::Miter | LineJoin::MiterClip)
+        && stroke.miter_limit >= core::f32::consts::SQRT_2;
+    for r in rrect.radii() {
+        if r.x != r.y || (r.x == 0.0 && !is_miter) {
+            return None;
+        }
+    }
+
+    let half_width = stroke.width.half();
+    let outer = rrect.outset(half_width, half_width)?.transform(transform)?;
+    let inner = match rrect.inset(half_width, half_width) {
+        Some(inner) => Some(inner.transform(transform)?),
+        None => None, // the stroke covers the whole rect
+    };
+
+    Some((outer, inner))
+}
+
 fn treat_as_hairline(paint: &Paint, stroke: &Stroke, mut ts: Transform) -> Option<f32> {
     fn fast_len(p: Point) -> f32 {
         let mut x = p.x.abs();
@@ -576,6 +1116,94 @@ pub(crate) fn is_too_big_for_math(path: &Path) -> bool {
     !(b.left() >= -MAX && b.top() >= -MAX && b.right() <= MAX && b.bottom() <= MAX)
 }
 
+// Bands smaller than this are not worth a separate thread.
+const MIN_BAND_HEIGHT: u32 = 64;
+
+// Returns a band height that splits the pixmap between the available threads.
+//
+// Returns `None` when the pixmap is too small to be split.
+fn band_height(height: u32) -> Option<LengthU32> {
+    let threads = rayon::current_num_threads() as u32;
+    let band_height = height.div_ceil(threads.max(1)).max(MIN_BAND_HEIGHT);
+    if band_height < height {
+        LengthU32::new(band_height)
+    } else {
+        None
+    }
+}
+
+// A blitter that draws only rows inside a `PixmapMut::bands` band.
+struct BandBlitter<'a, 'b: 'a> {
+    blitter: RasterPipelineBlitter<'a, 'b>,
+    top: u32,
+    bottom: u32,
+}
+
+impl BandBlitter<'_, '_> {
+    fn contains(&self, y: u32) -> bool {
+        y >= self.top && y < self.bottom
+    }
+
+    // Clips a range of rows to the band.
+    fn clip_rows(&self, y: u32, height: u32) -> Option<(u32, LengthU32)> {
+        let top = y.max(self.top);
+        let bottom = y.saturating_add(height).min(self.bottom);
+        Some((top, LengthU32::new(bottom.checked_sub(top)?)?))
+    }
+
+    fn clip_rect(&self, rect: &ScreenIntRect) -> Option<ScreenIntRect> {
+        let (y, height) = self.clip_rows(rect.y(), rect.height())?;
+        ScreenIntRect::from_xywh(rect.x(), y, rect.width(), height.get())
+    }
+}
+
+impl Blitter for BandBlitter<'_, '_> {
+    fn blit_h(&mut self, x: u32, y: u32, width: LengthU32) {
+        if self.contains(y) {
+            self.blitter.blit_h(x, y, width);
+        }
+    }
+
+    fn blit_anti_h(&mut self, x: u32, y: u32, aa: &mut [AlphaU8], runs: &mut [AlphaRun]) {
+        if self.contains(y) {
+            self.blitter.blit_anti_h(x, y, aa, runs);
+        }
+    }
+
+    fn blit_v(&mut self, x: u32, y: u32, height: LengthU32, alpha: AlphaU8) {
+        if let Some((y, height)) = self.clip_rows(y, height.get()) {
+            self.blitter.blit_v(x, y, height, alpha);
+        }
+    }
+
+    fn blit_anti_h2(&mut self, x: u32, y: u32, alpha0: AlphaU8, alpha1: AlphaU8) {
+        if self.contains(y) {
+            self.blitter.blit_anti_h2(x, y, alpha0, alpha1);
+        }
+    }
+
+    fn blit_anti_v2(&mut self, x: u32, y: u32, alpha0: AlphaU8, alpha1: AlphaU8) {
+        match (self.contains(y), self.contains(y + 1)) {
+            (true, true) => self.blitter.blit_anti_v2(x, y, alpha0, alpha1),
+            (true, false) => self.blitter.blit_v(x, y, LENGTH_U32_ONE, alpha0),
+            (false, true) => self.blitter.blit_v(x, y + 1, LENGTH_U32_ONE, alpha1),
+            (false, false) => {}
+        }
+    }
+
+    fn blit_rect(&mut self, rect: &ScreenIntRect) {
+        if let Some(rect) = self.clip_rect(rect) {
+            self.blitter.blit_rect(&rect);
+        }
+    }
+
+    fn blit_mask(&mut self, mask: &BlitterMask, clip: &ScreenIntRect) {
+        if let Some(clip) = self.clip_rect(clip) {
+            self.blitter.blit_mask(mask, &clip);
+        }
+    }
+}
+
 /// Splits the target pixmap into a list of tiles.
 ///
 /// Skia/tiny-skia uses a lot of fixed-point math during path rendering.
@@ -0,0 +1,213 @@
+// Copyright 2014 Google Inc.
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use tiny_skia_path::{Point, Transform};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use tiny_skia_path::NoStdFloat;
+
+use crate::{BlendMode, Color, Mask, Paint, Pixmap, PixmapMut};
+
+// The approximate size of a single patch cell in pixels.
+const PARTITION_SIZE: f32 = 10.0;
+// Limits the number of vertices to 129 * 129, which still fits into `u16` indices.
+const MAX_LOD: u32 = 128;
+
+impl Pixmap {
+    /// Draws a Coons patch.
+    ///
+    /// See [`PixmapMut::draw_patch`] for details.
+    pub fn draw_patch(
+        &mut self,
+        cubics: &[Point; 12],
+        colors: Option<&[Color; 4]>,
+        tex_coords: Option<&[Point; 4]>,
+        blend_mode: BlendMode,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut().draw_patch(
+            cubics, colors, tex_coords, blend_mode, paint, transform, mask,
+        );
+    }
+}
+
+impl PixmapMut<'_> {
+    /// Draws a Coons patch.
+    ///
+    /// Just like `SkCanvas::drawPatch`, the patch is defined by four cubic curves
+    /// stored in `cubics`: the top one going left to right, followed by the right,
+    /// bottom and left ones going clockwise. Each curve starts at the last point
+    /// of the previous one and the left one ends at `cubics[0]`.
+    /// Therefore, corners are `cubics[0]`, `cubics[3]`, `cubics[6]` and `cubics[9]`.
+    ///
+    /// `colors` and `tex_coords` are specified for the same corners and are
+    /// interpolated across the patch. Texture coordinates are in the `paint` shader
+    /// coordinates. Without them, the shader is mapped using the patch points instead.
+    ///
+    /// `blend_mode` combines `colors` (destination) with the `paint` shader (source).
+    /// Use `BlendMode::Destination` to ignore the shader and `BlendMode::Modulate`
+    /// to tint it. Ignored without `colors`.
+    ///
+    /// The patch is tessellated into triangles, which are never anti-aliased,
+    /// because anti-aliasing would produce visible seams between them.
+    pub fn draw_patch(
+        &mut self,
+        cubics: &[Point; 12],
+        colors: Option<&[Color; 4]>,
+        tex_coords: Option<&[Point; 4]>,
+        blend_mode: BlendMode,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        let Some(mesh) = PatchMesh::new(cubics, colors, tex_coords, transform) else {
+            log::warn!("patch tessellation failed");
+            return;
+        };
+
+        let paint = Paint {
+            anti_alias: false,
+            ..paint.clone()
+        };
+
+        self.draw_vertices(
+            &mesh.positions,
+            colors.map(|_| mesh.colors.as_slice()),
+            tex_coords.map(|_| mesh.tex_coords.as_slice()),
+            Some(&mesh.indices),
+            blend_mode,
+            &paint,
+            transform,
+            mask,
+        );
+    }
+}
+
+// A patch tessellated into a grid of triangles. Similar to `SkPatchUtils::MakeVertices`.
+struct PatchMesh {
+    positions: Vec<Point>,
+    colors: Vec<Color>,
+    tex_coords: Vec<Point>,
+    indices: Vec<u16>,
+}
+
+impl PatchMesh {
+    fn new(
+        cubics: &[Point; 12],
+        colors: Option<&[Color; 4]>,
+        tex_coords: Option<&[Point; 4]>,
+        transform: Transform,
+    ) -> Option<Self> {
+        let c = cubics;
+        let top = [c[0], c[1], c[2], c[3]];
+        let right = [c[3], c[4], c[5], c[6]];
+        let bottom = [c[9], c[8], c[7], c[6]];
+        let left = [c[0], c[11], c[10], c[9]];
+
+        // The number of cells depends on the size of the patch on screen.
+        let lod = |a: &[Point; 4], b: &[Point; 4]| -> Option<u32> {
+            let len = approx_length(a, transform).max(approx_length(b, transform));
+            if !len.is_finite() {
+                return None;
+            }
+
+            Some(((len / PARTITION_SIZE).ceil() as u32).clamp(1, MAX_LOD))
+        };
+        let lod_x = lod(&top, &bottom)?;
+        let lod_y = lod(&left, &right)?;
+
+        let vertices_count = ((lod_x + 1) * (lod_y + 1)) as usize;
+        let mut mesh = PatchMesh {
+            positions: Vec::with_capacity(vertices_count),
+            colors: Vec::with_capacity(if colors.is_some() { vertices_count } else { 0 }),
+            tex_coords: Vec::with_capacity(if tex_coords.is_some() {
+                vertices_count
+            } else {
+                0
+            }),
+            indices: Vec::with_capacity((lod_x * lod_y * 6) as usize),
+        };
+
+        for y in 0..=lod_y {
+            let v = y as f32 / lod_y as f32;
+            let left = eval_cubic(&left, v);
+            let right = eval_cubic(&right, v);
+
+            for x in 0..=lod_x {
+                let u = x as f32 / lod_x as f32;
+                let top = eval_cubic(&top, u);
+                let bottom = eval_cubic(&bottom, u);
+
+                // Corner weights in the top-left, top-right, bottom-right, bottom-left order.
+                let w = [(1.0 - u) * (1.0 - v), u * (1.0 - v), u * v, (1.0 - u) * v];
+                let corners = [c[0], c[3], c[6], c[9]];
+
+                // A sum of two ruled surfaces minus a bilinear one.
+                let point = |f: fn(Point) -> f32| {
+                    (1.0 - v) * f(top) + v * f(bottom) + (1.0 - u) * f(left) + u * f(right)
+                        - weighted_sum(&w, corners.map(f))
+                };
+                mesh.positions
+                    .push(Point::from_xy(point(|p| p.x), point(|p| p.y)));
+
+                if let Some(colors) = colors {
+                    let channel = |f: fn(&Color) -> f32| {
+                        weighted_sum(&w, colors.map(|c| f(&c))).clamp(0.0, 1.0)
+                    };
+                    let color = Color::from_rgba(
+                        channel(Color::red),
+                        channel(Color::green),
+                        channel(Color::blue),
+                        channel(Color::alpha),
+                    )?;
+                    mesh.colors.push(color);
+                }
+
+                if let Some(tex_coords) = tex_coords {
+                    let x = weighted_sum(&w, tex_coords.map(|p| p.x));
+                    let y = weighted_sum(&w, tex_coords.map(|p| p.y));
+                    mesh.tex_coords.push(Point::from_xy(x, y));
+                }
+            }
+        }
+
+        let stride = lod_x as u16 + 1;
+        for y in 0..lod_y as u16 {
+            for x in 0..lod_x as u16 {
+                let i = y * stride + x;
+                mesh.indices.extend_from_slice(&[i, i + 1, i + stride]);
+                mesh.indices
+                    .extend_from_slice(&[i + 1, i + stride + 1, i + stride]);
+            }
+        }
+
+        Some(mesh)
+    }
+}
+
+fn weighted_sum(weights: &[f32; 4], values: [f32; 4]) -> f32 {
+    weights.iter().zip(values).map(|(w, v)| w * v).sum()
+}
+
+fn eval_cubic(points: &[Point; 4], t: f32) -> Point {
+    let mt = 1.0 - t;
+    let w = [mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t];
+    Point::from_xy(
+        weighted_sum(&w, points.map(|p| p.x)),
+        weighted_sum(&w, points.map(|p| p.y)),
+    )
+}
+
+// The length of the control polygon, which is never shorter than the curve itself.
+fn approx_length(points: &[Point; 4], transform: Transform) -> f32 {
+    let mut points = *points;
+    transform.map_points(&mut points);
+    points.windows(2).map(|p| p[0].distance(p[1])).sum()
+}
@@ -0,0 +1,603 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+use tiny_skia_path::{IntSize, NormalizedF32, Path, Rect, Stroke, Transform};
+
+use crate::{BlendMode, ColorFilter, FillRule, FilterQuality, LineCap, LineJoin, Mask, Paint};
+use crate::{ColorSpace, MaskType, Shader, SpreadMode};
+use crate::{Mipmaps, Pattern, PerspectiveTransform, Pixmap, PixmapMut, PixmapPaint, PixmapRef};
+
+/// A recorded sequence of drawing commands.
+///
+/// Created by [`PictureRecorder`] and drawn via [`PixmapMut::draw_picture`].
+///
+/// `Picture` owns all of its data, including copies of the used pixmaps and masks,
+/// therefore it can be cached and shared between threads.
+#[derive(Clone, Debug)]
+pub struct Picture {
+    pub(crate) cull_rect: Rect,
+    pub(crate) commands: Vec<Command>,
+    pub(crate) pixmaps: Vec<Pixmap>,
+    // Mipmaps of pixmaps sampled with `FilterQuality::Trilinear`, indexed like `pixmaps`.
+    pub(crate) mipmaps: Vec<Option<Mipmaps>>,
+    pub(crate) masks: Vec<Mask>,
+}
+
+impl Picture {
+    /// Returns picture's cull rectangle.
+    ///
+    /// This is the rectangle that was passed to [`PictureRecorder::new`].
+    pub fn cull_rect(&self) -> Rect {
+        self.cull_rect
+    }
+
+    /// Returns the number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Checks if the picture has no commands.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    // Generates missing mipmaps, so they are not regenerated on each playback.
+    pub(crate) fn generate_mipmaps(&mut self) {
+        self.mipmaps.resize(self.pixmaps.len(), None);
+        for command in &self.commands {
+            let pixmap = match *command {
+                Command::FillRect { ref paint, .. }
+                | Command::FillPath { ref paint, .. }
+                | Command::StrokePath { ref paint, .. } => match paint.shader {
+                    RecordedShader::Pattern {
+                        pixmap,
+                        quality: FilterQuality::Trilinear,
+                        ..
+                    } => pixmap,
+                    _ => continue,
+                },
+                Command::DrawPixmap {
+                    pixmap, ref paint, ..
+                } if paint.quality == FilterQuality::Trilinear => pixmap,
+                _ => continue,
+            };
+
+            if self.mipmaps[pixmap].is_none() {
+                self.mipmaps[pixmap] = Mipmaps::new(self.pixmaps[pixmap].as_ref());
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Command {
+    FillRect {
+        rect: Rect,
+        paint: RecordedPaint,
+        transform: Transform,
+        mask: Option<usize>,
+    },
+    FillPath {
+        path: Path,
+        paint: RecordedPaint,
+        fill_rule: FillRule,
+        transform: Transform,
+        mask: Option<usize>,
+    },
+    StrokePath {
+        path: Path,
+        paint: RecordedPaint,
+        stroke: Stroke,
+        transform: Transform,
+        mask: Option<usize>,
+    },
+    DrawPixmap {
+        x: i32,
+        y: i32,
+        pixmap: usize,
+        paint: PixmapPaint,
+        transform: Transform,
+        mask: Option<usize>,
+    },
+    ApplyMask {
+        mask: usize,
+    },
+}
+
+// A `Paint` with the pattern pixmap replaced by an index.
+#[derive(Clone, Debug)]
+pub(crate) struct RecordedPaint {
+    pub shader: RecordedShader,
+    pub color_filter: Option<ColorFilter>,
+    pub blend_mode: BlendMode,
+    pub color_space: ColorSpace,
+    pub anti_alias: bool,
+    pub force_hq_pipeline: bool,
+    pub parallel: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum RecordedShader {
+    // Never a `Shader::Pattern`.
+    Plain(Shader<'static>),
+    Pattern {
+        pixmap: usize,
+        spread_mode: SpreadMode,
+        quality: FilterQuality,
+        opacity: NormalizedF32,
+        transform: PerspectiveTransform,
+    },
+}
+
+impl RecordedPaint {
+    pub(crate) fn to_paint<'a>(&self, picture: &'a Picture) -> Paint<'a> {
+        let shader = match self.shader {
+            RecordedShader::Plain(ref shader) => shader.clone(),
+            RecordedShader::Pattern {
+                pixmap,
+                spread_mode,
+                quality,
+                opacity,
+                transform,
+            } => match picture.mipmaps[pixmap] {
+                Some(ref mipmaps) if quality == FilterQuality::Trilinear => Shader::Pattern(
+                    Pattern::with_mipmaps(mipmaps, spread_mode, opacity, transform),
+                ),
+                _ => Shader::Pattern(Pattern::new_perspective(
+                    picture.pixmaps[pixmap].as_ref(),
+                    spread_mode,
+                    quality,
+                    opacity,
+                    transform,
+                )),
+            },
+        };
+
+        Paint {
+            shader,
+            color_filter: self.color_filter.clone(),
+            blend_mode: self.blend_mode,
+            color_space: self.color_space,
+            anti_alias: self.anti_alias,
+            force_hq_pipeline: self.force_hq_pipeline,
+            parallel: self.parallel,
+        }
+    }
+}
+
+/// Records drawing commands into a [`Picture`].
+///
+/// Has the same drawing methods as [`PixmapMut`], but nothing is rendered.
+/// Commands that are outside the cull rectangle are skipped.
+///
+/// # Example
+///
+/// ```
+/// use tiny_skia::*;
+///
+/// let mut recorder = PictureRecorder::new(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+/// let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+/// recorder.fill_path(&path, &Paint::default(), FillRule::Winding, Transform::identity(), None);
+/// let picture = recorder.finish();
+///
+/// // Render at 2x.
+/// let mut pixmap = Pixmap::new(200, 200).unwrap();
+/// pixmap.draw_picture(&picture, Transform::from_scale(2.0, 2.0));
+/// ```
+#[derive(Debug)]
+pub struct PictureRecorder {
+    picture: Picture,
+    // Content hashes of the recorded pixmaps and masks, mapped to their indices.
+    pixmap_hashes: BTreeMap<u64, usize>,
+    mask_hashes: BTreeMap<u64, usize>,
+}
+
+impl PictureRecorder {
+    /// Creates a new recorder.
+    ///
+    /// `cull_rect` is the area of interest in picture coordinates.
+    /// Drawing commands that are completely outside of it are not recorded,
+    /// but the ones that are partially inside are not clipped.
+    pub fn new(cull_rect: Rect) -> Self {
+        PictureRecorder {
+            picture: Picture {
+                cull_rect,
+                commands: Vec::new(),
+                pixmaps: Vec::new(),
+                mipmaps: Vec::new(),
+                masks: Vec::new(),
+            },
+            pixmap_hashes: BTreeMap::new(),
+            mask_hashes: BTreeMap::new(),
+        }
+    }
+
+    /// Records a filled rectangle.
+    ///
+    /// See [`PixmapMut::fill_rect`] for details.
+    pub fn fill_rect(
+        &mut self,
+        rect: Rect,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        if !self.is_visible(rect, transform) {
+            return;
+        }
+
+        let paint = self.record_paint(paint);
+        let mask = mask.map(|mask| self.record_mask(mask));
+        self.picture.commands.push(Command::FillRect {
+            rect,
+            paint,
+            transform,
+            mask,
+        });
+    }
+
+    /// Records a filled path.
+    ///
+    /// See [`PixmapMut::fill_path`] for details.
+    pub fn fill_path(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        if !self.is_visible(path.bounds(), transform) {
+            return;
+        }
+
+        let paint = self.record_paint(paint);
+        let mask = mask.map(|mask| self.record_mask(mask));
+        self.picture.commands.push(Command::FillPath {
+            path: path.clone(),
+            paint,
+            fill_rule,
+            transform,
+            mask,
+        });
+    }
+
+    /// Records a stroked path.
+    ///
+    /// See [`PixmapMut::stroke_path`] for details.
+    pub fn stroke_path(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        stroke: &Stroke,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        let radius = stroke_inflation_radius(stroke);
+        let Some(bounds) = path.bounds().outset(radius, radius) else {
+            return;
+        };
+
+        if !self.is_visible(bounds, transform) {
+            return;
+        }
+
+        let paint = self.record_paint(paint);
+        let mask = mask.map(|mask| self.record_mask(mask));
+        self.picture.commands.push(Command::StrokePath {
+            path: path.clone(),
+            paint,
+            stroke: stroke.clone(),
+            transform,
+            mask,
+        });
+    }
+
+    /// Records a pixmap drawing.
+    ///
+    /// The pixmap is copied into the picture.
+    ///
+    /// See [`PixmapMut::draw_pixmap`] for details.
+    pub fn draw_pixmap(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: PixmapRef,
+        paint: &PixmapPaint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        let rect = pixmap.size().to_int_rect(x, y).to_rect();
+        if !self.is_visible(rect, transform) {
+            return;
+        }
+
+        let pixmap = self.record_pixmap(pixmap);
+        let mask = mask.map(|mask| self.record_mask(mask));
+        self.picture.commands.push(Command::DrawPixmap {
+            x,
+            y,
+            pixmap,
+            paint: paint.clone(),
+            transform,
+            mask,
+        });
+    }
+
+    /// Records a mask application.
+    ///
+    /// Like all recorded masks, it's mapped using the playback transform.
+    ///
+    /// See [`PixmapMut::apply_mask`] for details.
+    pub fn apply_mask(&mut self, mask: &Mask) {
+        let mask = self.record_mask(mask);
+        self.picture.commands.push(Command::ApplyMask { mask });
+    }
+
+    /// Finishes recording and returns the resulting picture.
+    ///
+    /// Mipmaps are generated for pixmaps that are drawn with [`FilterQuality::Trilinear`],
+    /// unless they were already provided via [`Pattern::new_with_mipmaps`].
+    pub fn finish(mut self) -> Picture {
+        self.picture.generate_mipmaps();
+        self.picture
+    }
+
+    fn is_visible(&self, rect: Rect, transform: Transform) -> bool {
+        match rect.transform(transform) {
+            Some(bounds) => intersects(&bounds, &self.picture.cull_rect),
+            None => false,
+        }
+    }
+
+    fn record_paint(&mut self, paint: &Paint) -> RecordedPaint {
+        let shader = match paint.shader {
+            Shader::SolidColor(color) => RecordedShader::Plain(Shader::SolidColor(color)),
+            Shader::LinearGradient(ref g) => {
+                RecordedShader::Plain(Shader::LinearGradient(g.clone()))
+            }
+            Shader::RadialGradient(ref g) => {
+                RecordedShader::Plain(Shader::RadialGradient(g.clone()))
+            }
+            Shader::SweepGradient(ref g) => RecordedShader::Plain(Shader::SweepGradient(g.clone())),
+            Shader::Pattern(ref p) => {
+                let pixmap = self.record_pixmap(p.pixmap);
+                if let Some(ref mipmaps) = p.mipmaps {
+                    let recorded = &mut self.picture.mipmaps;
+                    recorded.resize(self.picture.pixmaps.len(), None);
+                    recorded[pixmap].get_or_insert_with(|| mipmaps.clone());
+                }
+
+                RecordedShader::Pattern {
+                    pixmap,
+                    spread_mode: p.spread_mode,
+                    quality: p.quality,
+                    opacity: p.opacity,
+                    transform: p.transform,
+                }
+            }
+        };
+
+        RecordedPaint {
+            shader,
+            color_filter: paint.color_filter.clone(),
+            blend_mode: paint.blend_mode,
+            color_space: paint.color_space,
+            anti_alias: paint.anti_alias,
+            force_hq_pipeline: paint.force_hq_pipeline,
+            parallel: paint.parallel,
+        }
+    }
+
+    // Pixmaps and masks are deduplicated by content, since the same image
+    // is usually drawn multiple times. A content hash is used to find a candidate,
+    // which is then compared in full.
+    fn record_pixmap(&mut self, pixmap: PixmapRef) -> usize {
+        let rows = || pixmap.rows().map(bytemuck::cast_slice::<_, u8>);
+        let hash = hash_rows(pixmap.size(), rows());
+
+        let pixmaps = &mut self.picture.pixmaps;
+        if let Some(&idx) = self.pixmap_hashes.get(&hash) {
+            if pixmaps[idx].as_ref().rows().eq(pixmap.rows()) {
+                return idx;
+            }
+        }
+
+        pixmaps.push(pixmap.to_owned());
+        *self.pixmap_hashes.entry(hash).or_insert(pixmaps.len() - 1)
+    }
+
+    fn record_mask(&mut self, mask: &Mask) -> usize {
+        let hash = hash_rows(mask.size(), mask.data().chunks(mask.width() as usize));
+
+        let masks = &mut self.picture.masks;
+        if let Some(&idx) = self.mask_hashes.get(&hash) {
+            if masks[idx] == *mask {
+                return idx;
+            }
+        }
+
+        masks.push(mask.clone());
+        *self.mask_hashes.entry(hash).or_insert(masks.len() - 1)
+    }
+}
+
+impl PixmapMut<'_> {
+    /// Draws a picture.
+    ///
+    /// `transform` is applied on top of the transforms of each recorded command.
+    /// Nothing will be drawn when the transformed picture's cull rectangle
+    /// is outside the pixmap. Commands outside the pixmap are skipped as well.
+    ///
+    /// Recorded masks are mapped using `transform` as well.
+    /// Areas outside of a mapped mask are masked out.
+    pub fn draw_picture(&mut self, picture: &Picture, transform: Transform) {
+        let Some(pixmap_rect) = self.size().to_int_rect(0, 0).to_rect().outset(1.0, 1.0) else {
+            return;
+        };
+
+        let is_visible = |rect: Rect, ts: Transform| match rect.transform(ts) {
+            Some(bounds) => intersects(&bounds, &pixmap_rect),
+            None => false,
+        };
+
+        if !is_visible(picture.cull_rect, transform) {
+            return;
+        }
+
+        let masks: Vec<_> = picture
+            .masks
+            .iter()
+            .map(|mask| transform_mask(mask, transform, self.size()))
+            .collect();
+        for command in &picture.commands {
+            match *command {
+                Command::FillRect {
+                    rect,
+                    ref paint,
+                    transform: ts,
+                    mask,
+                } => {
+                    let ts = ts.post_concat(transform);
+                    if is_visible(rect, ts) {
+                        let paint = paint.to_paint(picture);
+                        self.fill_rect(rect, &paint, ts, mask.map(|i| &*masks[i]));
+                    }
+                }
+                Command::FillPath {
+                    ref path,
+                    ref paint,
+                    fill_rule,
+                    transform: ts,
+                    mask,
+                } => {
+                    let ts = ts.post_concat(transform);
+                    if is_visible(path.bounds(), ts) {
+                        let paint = paint.to_paint(picture);
+                        self.fill_path(path, &paint, fill_rule, ts, mask.map(|i| &*masks[i]));
+                    }
+                }
+                Command::StrokePath {
+                    ref path,
+                    ref paint,
+                    ref stroke,
+                    transform: ts,
+                    mask,
+                } => {
+                    let ts = ts.post_concat(transform);
+                    let radius = stroke_inflation_radius(stroke);
+                    let bounds = path.bounds().outset(radius, radius);
+                    if matches!(bounds, Some(bounds) if is_visible(bounds, ts)) {
+                        let paint = paint.to_paint(picture);
+                        self.stroke_path(path, &paint, stroke, ts, mask.map(|i| &*masks[i]));
+                    }
+                }
+                Command::DrawPixmap {
+                    x,
+                    y,
+                    pixmap,
+                    ref paint,
+                    transform: ts,
+                    mask,
+                } => {
+                    let ts = ts.post_concat(transform);
+                    let mipmaps = picture.mipmaps[pixmap].as_ref();
+                    let pixmap = picture.pixmaps[pixmap].as_ref();
+                    if is_visible(pixmap.size().to_int_rect(x, y).to_rect(), ts) {
+                        let mask = mask.map(|i| &*masks[i]);
+                        self.draw_pixmap_with_mipmaps(x, y, pixmap, mipmaps, paint, ts, mask);
+                    }
+                }
+                Command::ApplyMask { mask } => {
+                    self.apply_mask(&masks[mask]);
+                }
+            }
+        }
+    }
+}
+
+impl Pixmap {
+    /// Draws a picture.
+    ///
+    /// See [`PixmapMut::draw_picture`] for details.
+    pub fn draw_picture(&mut self, picture: &Picture, transform: Transform) {
+        self.as_mut().draw_picture(picture, transform);
+    }
+}
+
+// Maps a recorded mask onto a pixmap of the specified size.
+//
+// The mask is resampled just like a pixmap would be.
+fn transform_mask(mask: &Mask, transform: Transform, size: IntSize) -> Cow<'_, Mask> {
+    if transform.is_identity() && mask.size() == size {
+        return Cow::Borrowed(mask);
+    }
+
+    let mut data = Vec::with_capacity(mask.data().len() * 4);
+    for a in mask.data() {
+        data.extend_from_slice(&[0, 0, 0, *a]);
+    }
+
+    let Some(mut target) = Pixmap::new(size.width(), size.height()) else {
+        return Cow::Borrowed(mask);
+    };
+
+    if let Some(pixmap) = Pixmap::from_vec(data, mask.size()) {
+        let paint = PixmapPaint {
+            quality: FilterQuality::Bilinear,
+            ..PixmapPaint::default()
+        };
+        target.draw_pixmap(0, 0, pixmap.as_ref(), &paint, transform, None);
+    }
+
+    Cow::Owned(Mask::from_pixmap(target.as_ref(), MaskType::Alpha))
+}
+
+// An FxHash-like hash of an image content.
+fn hash_rows<'a>(size: IntSize, rows: impl Iterator<Item = &'a [u8]>) -> u64 {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+    let add = |hash: u64, value: u64| (hash.rotate_left(5) ^ value).wrapping_mul(SEED);
+
+    let dimensions = (u64::from(size.width()) << 32) | u64::from(size.height());
+    let mut hash = add(0, dimensions);
+    for row in rows {
+        let mut chunks = row.chunks_exact(8);
+        for chunk in &mut chunks {
+            hash = add(hash, u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+
+        for byte in chunks.remainder() {
+            hash = add(hash, u64::from(*byte));
+        }
+    }
+
+    hash
+}
+
+// Returns how much a stroke can extend beyond the path bounds.
+//
+// Hairlines are handled by the 1px outset of the pixmap rect during playback.
+fn stroke_inflation_radius(stroke: &Stroke) -> f32 {
+    let mut multiplier = 1.0;
+    if matches!(stroke.line_join, LineJoin::Miter | LineJoin::MiterClip) {
+        multiplier = stroke.miter_limit.max(1.0);
+    }
+
+    if stroke.line_cap == LineCap::Square {
+        multiplier = multiplier.max(core::f32::consts::SQRT_2);
+    }
+
+    stroke.width * 0.5 * multiplier
+}
+
+// Unlike `Rect::intersect`, treats touching rectangles as intersecting.
+fn intersects(a: &Rect, b: &Rect) -> bool {
+    a.left() <= b.right() && b.left() <= a.right() && a.top() <= b.bottom() && b.top() <= a.bottom()
+}
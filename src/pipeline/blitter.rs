@@ -4,8 +4,9 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
-use crate::{BlendMode, Color, LengthU32, Paint, PixmapRef, PremultipliedColorU8, Shader};
-use crate::{ALPHA_U8_OPAQUE, ALPHA_U8_TRANSPARENT};
+use crate::PremultipliedColorU8;
+use crate::{BlendMode, Color, ColorSpace, ColorType, LengthU32, Paint, PixmapRef};
+use crate::{Shader, ALPHA_U8_OPAQUE, ALPHA_U8_TRANSPARENT};
 
 use crate::alpha_runs::AlphaRun;
 use crate::blitter::{Blitter, Mask};
@@ -16,6 +17,14 @@ use crate::math::LENGTH_U32_ONE;
 use crate::pipeline::{self, RasterPipeline, RasterPipelineBuilder};
 use crate::pixmap::SubPixmapMut;
 
+/// Colors interpolated across a triangle.
+#[derive(Copy, Clone, Debug)]
+pub struct VertexColors {
+    pub ctx: pipeline::VertexColorCtx,
+    /// Combines vertex colors (destination) with the shader (source).
+    pub blend_mode: BlendMode,
+}
+
 pub struct RasterPipelineBlitter<'a, 'b: 'a> {
     mask: Option<SubMaskRef<'a>>,
     pixmap_src: PixmapRef<'a>,
@@ -29,7 +38,16 @@ pub struct RasterPipelineBlitter<'a, 'b: 'a> {
 
 impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
     pub fn new(
-        paint: &Paint<'a>,
+        paint: &'a Paint<'a>,
+        mask: Option<SubMaskRef<'a>>,
+        pixmap: &'a mut SubPixmapMut<'b>,
+    ) -> Option<Self> {
+        Self::new_with_vertex_colors(paint, None, mask, pixmap)
+    }
+
+    pub fn new_with_vertex_colors(
+        paint: &'a Paint<'a>,
+        vertex_colors: Option<VertexColors>,
         mask: Option<SubMaskRef<'a>>,
         pixmap: &'a mut SubPixmapMut<'b>,
     ) -> Option<Self> {
@@ -43,26 +61,41 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
             }
         }
 
+        let is_solid_color = paint.is_solid_color() && vertex_colors.is_none();
+        let is_opaque = paint.shader.is_opaque()
+            && vertex_colors.is_none()
+            && match paint.color_filter {
+                Some(ref filter) => filter.preserves_opaque(),
+                None => true,
+            };
+
         // Fast-reject.
         // This is basically SkInterpretXfermode().
         match paint.blend_mode {
             // `Destination` keep the pixmap unchanged. Nothing to do here.
             BlendMode::Destination => return None,
-            BlendMode::DestinationIn if paint.shader.is_opaque() && paint.is_solid_color() => {
-                return None
-            }
+            BlendMode::DestinationIn if is_opaque && is_solid_color => return None,
             _ => {}
         }
 
         // We can strength-reduce SourceOver into Source when opaque.
         let mut blend_mode = paint.blend_mode;
-        if paint.shader.is_opaque() && blend_mode == BlendMode::SourceOver && mask.is_none() {
+        if is_opaque && blend_mode == BlendMode::SourceOver && mask.is_none() {
             blend_mode = BlendMode::Source;
         }
 
+        // Only RGBA8 pixels can be written directly. Other color types require conversion.
+        let is_rgba8 = pixmap.color_type == ColorType::Rgba8;
+
         // When we're drawing a constant color in Source mode, we can sometimes just memset.
+        // Filtered colors are produced by the pipeline only.
         let mut memset2d_color = None;
-        if paint.is_solid_color() && blend_mode == BlendMode::Source && mask.is_none() {
+        if is_rgba8
+            && is_solid_color
+            && paint.color_filter.is_none()
+            && blend_mode == BlendMode::Source
+            && mask.is_none()
+        {
             // Unlike Skia, our shader cannot be constant.
             // Therefore there is no need to run a raster pipeline to get shader's color.
             if let Shader::SolidColor(ref color) = paint.shader {
@@ -71,30 +104,79 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
         };
 
         // Clear is just a transparent color memset.
-        if blend_mode == BlendMode::Clear && !paint.anti_alias && mask.is_none() {
+        if blend_mode == BlendMode::Clear && !paint.anti_alias && mask.is_none() && is_rgba8 {
             blend_mode = BlendMode::Source;
             memset2d_color = Some(PremultipliedColorU8::TRANSPARENT);
         }
 
+        let is_linear = paint.color_space == ColorSpace::LinearSrgb;
+        let (load_stage, store_stage) = pipeline::load_store_stages(pixmap.color_type);
+        // Destination colors must be converted into the working color space as well.
+        let push_load_destination = |p: &mut RasterPipelineBuilder| {
+            p.push(load_stage);
+            if is_linear {
+                p.push(pipeline::Stage::SrgbToLinearDestination);
+            }
+        };
+        let push_store = |p: &mut RasterPipelineBuilder| {
+            if is_linear {
+                p.push(pipeline::Stage::LinearToSrgb);
+            }
+            p.push(store_stage);
+        };
+
+        let push_shader = |p: &mut RasterPipelineBuilder| -> bool {
+            let Some(colors) = vertex_colors else {
+                return paint.shader.push_stages(p);
+            };
+
+            p.ctx.vertex_color = colors.ctx;
+            if colors.blend_mode == BlendMode::Destination {
+                // The shader is not needed at all.
+                p.push(pipeline::Stage::VertexColor);
+                p.push(pipeline::Stage::MoveDestinationToSource);
+                return true;
+            }
+
+            if !paint.shader.push_stages(p) {
+                return false;
+            }
+
+            p.push(pipeline::Stage::VertexColor);
+            if let Some(blend_stage) = colors.blend_mode.to_stage() {
+                p.push(blend_stage);
+            }
+
+            true
+        };
+
         let blit_anti_h_rp = {
             let mut p = RasterPipelineBuilder::new();
             p.set_force_hq_pipeline(paint.force_hq_pipeline);
-            if !paint.shader.push_stages(&mut p) {
+            if !push_shader(&mut p) {
                 return None;
             }
 
+            if let Some(ref filter) = paint.color_filter {
+                filter.push_stages(&mut p);
+            }
+
+            if is_linear {
+                p.push(pipeline::Stage::SrgbToLinear);
+            }
+
             if mask.is_some() {
                 p.push(pipeline::Stage::MaskU8);
             }
 
             if blend_mode.should_pre_scale_coverage() {
                 p.push(pipeline::Stage::Scale1Float);
-                p.push(pipeline::Stage::LoadDestination);
+                push_load_destination(&mut p);
                 if let Some(blend_stage) = blend_mode.to_stage() {
                     p.push(blend_stage);
                 }
             } else {
-                p.push(pipeline::Stage::LoadDestination);
+                push_load_destination(&mut p);
                 if let Some(blend_stage) = blend_mode.to_stage() {
                     p.push(blend_stage);
                 }
@@ -102,7 +184,7 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
                 p.push(pipeline::Stage::Lerp1Float);
             }
 
-            p.push(pipeline::Stage::Store);
+            push_store(&mut p);
 
             p.compile()
         };
@@ -110,26 +192,34 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
         let blit_rect_rp = {
             let mut p = RasterPipelineBuilder::new();
             p.set_force_hq_pipeline(paint.force_hq_pipeline);
-            if !paint.shader.push_stages(&mut p) {
+            if !push_shader(&mut p) {
                 return None;
             }
 
+            if let Some(ref filter) = paint.color_filter {
+                filter.push_stages(&mut p);
+            }
+
+            if is_linear {
+                p.push(pipeline::Stage::SrgbToLinear);
+            }
+
             if mask.is_some() {
                 p.push(pipeline::Stage::MaskU8);
             }
 
-            if blend_mode == BlendMode::SourceOver && mask.is_none() {
+            if blend_mode == BlendMode::SourceOver && mask.is_none() && !is_linear && is_rgba8 {
                 // TODO: ignore when dither_rate is non-zero
                 p.push(pipeline::Stage::SourceOverRgba);
             } else {
                 if blend_mode != BlendMode::Source {
-                    p.push(pipeline::Stage::LoadDestination);
+                    push_load_destination(&mut p);
                     if let Some(blend_stage) = blend_mode.to_stage() {
                         p.push(blend_stage);
                     }
                 }
 
-                p.push(pipeline::Stage::Store);
+                push_store(&mut p);
             }
 
             p.compile()
@@ -138,22 +228,30 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
         let blit_mask_rp = {
             let mut p = RasterPipelineBuilder::new();
             p.set_force_hq_pipeline(paint.force_hq_pipeline);
-            if !paint.shader.push_stages(&mut p) {
+            if !push_shader(&mut p) {
                 return None;
             }
 
+            if let Some(ref filter) = paint.color_filter {
+                filter.push_stages(&mut p);
+            }
+
+            if is_linear {
+                p.push(pipeline::Stage::SrgbToLinear);
+            }
+
             if mask.is_some() {
                 p.push(pipeline::Stage::MaskU8);
             }
 
             if blend_mode.should_pre_scale_coverage() {
                 p.push(pipeline::Stage::ScaleU8);
-                p.push(pipeline::Stage::LoadDestination);
+                push_load_destination(&mut p);
                 if let Some(blend_stage) = blend_mode.to_stage() {
                     p.push(blend_stage);
                 }
             } else {
-                p.push(pipeline::Stage::LoadDestination);
+                push_load_destination(&mut p);
                 if let Some(blend_stage) = blend_mode.to_stage() {
                     p.push(blend_stage);
                 }
@@ -161,13 +259,13 @@ impl<'a, 'b: 'a> RasterPipelineBlitter<'a, 'b> {
                 p.push(pipeline::Stage::LerpU8);
             }
 
-            p.push(pipeline::Stage::Store);
+            push_store(&mut p);
 
             p.compile()
         };
 
         let pixmap_src = match paint.shader {
-            Shader::Pattern(ref patt) => patt.pixmap,
+            Shader::Pattern(ref patt) => patt.source(),
             // Just a dummy one.
             _ => PixmapRef::from_bytes(&[0, 0, 0, 0], 1, 1).unwrap(),
         };
@@ -15,8 +15,9 @@ For some reason, we are almost 2x slower. Maybe because Skia uses clang's vector
 and we're using a manual implementation.
 */
 
-use crate::{PremultipliedColorU8, SpreadMode, PixmapRef};
+use crate::{ColorType, PremultipliedColorU8, SpreadMode, PixmapRef};
 
+use crate::color;
 use crate::geom::ScreenIntRect;
 use crate::pixmap::SubPixmapMut;
 use crate::wide::{f32x8, i32x8, u32x8};
@@ -104,21 +105,50 @@ pub const STAGES: &[StageFn; super::STAGES_COUNT] = &[
     luminosity,
     source_over_rgba,
     transform,
+    perspective,
     reflect,
     repeat,
     bilinear,
     bicubic,
+    trilinear,
+    vertex_color,
     pad_x1,
     reflect_x1,
     repeat_x1,
     gradient,
     evenly_spaced_2_stop_gradient,
     xy_to_radius,
+    xy_to_unit_angle,
+    xy_to_2pt_conical_strip,
     xy_to_2pt_conical_focal_on_circle,
     xy_to_2pt_conical_well_behaved,
     xy_to_2pt_conical_greater,
+    xy_to_2pt_conical_smaller,
+    alter_2pt_conical_compensate_focal,
+    alter_2pt_conical_unswap,
+    negate_x,
+    mask_2pt_conical_nan,
     mask_2pt_conical_degenerates,
     apply_vector_mask,
+    unpremultiply,
+    color_matrix,
+    color_table,
+    filter_color,
+    srgb_to_linear,
+    srgb_to_linear_destination,
+    linear_to_srgb,
+    load_dst_bgra8,
+    store_bgra8,
+    load_dst_rgb565,
+    store_rgb565,
+    load_dst_gray8,
+    store_gray8,
+    load_dst_rgba16,
+    store_rgba16,
+    load_dst_rgba_f16,
+    store_rgba_f16,
+    load_dst_rgba_f32,
+    store_rgba_f32,
 ];
 
 pub fn fn_ptr(f: StageFn) -> *const () {
@@ -272,21 +302,109 @@ pub fn store_tail(p: &mut Pipeline) {
     p.next_stage();
 }
 
-// Currently, all mask/A8 pixmaps are handled by lowp.
-pub fn load_dst_u8(_: &mut Pipeline) {
-    // unreachable
+fn load_dst_bgra8(p: &mut Pipeline) {
+    load_dst_pixels(p, ColorType::Bgra8);
 }
 
-pub fn load_dst_u8_tail(_: &mut Pipeline) {
-    // unreachable
+fn store_bgra8(p: &mut Pipeline) {
+    store_pixels(p, ColorType::Bgra8);
 }
 
-pub fn store_u8(_: &mut Pipeline) {
-    // unreachable
+fn load_dst_rgb565(p: &mut Pipeline) {
+    load_dst_pixels(p, ColorType::Rgb565);
 }
 
-pub fn store_u8_tail(_: &mut Pipeline) {
-    // unreachable
+fn store_rgb565(p: &mut Pipeline) {
+    store_pixels(p, ColorType::Rgb565);
+}
+
+fn load_dst_gray8(p: &mut Pipeline) {
+    load_dst_pixels(p, ColorType::Gray8);
+}
+
+fn store_gray8(p: &mut Pipeline) {
+    store_pixels(p, ColorType::Gray8);
+}
+
+fn load_dst_rgba16(p: &mut Pipeline) {
+    load_dst_pixels(p, ColorType::Rgba16);
+}
+
+fn store_rgba16(p: &mut Pipeline) {
+    store_pixels(p, ColorType::Rgba16);
+}
+
+fn load_dst_rgba_f16(p: &mut Pipeline) {
+    load_dst_pixels(p, ColorType::RgbaF16);
+}
+
+fn store_rgba_f16(p: &mut Pipeline) {
+    store_pixels(p, ColorType::RgbaF16);
+}
+
+fn load_dst_rgba_f32(p: &mut Pipeline) {
+    load_dst_pixels(p, ColorType::RgbaF32);
+}
+
+fn store_rgba_f32(p: &mut Pipeline) {
+    store_pixels(p, ColorType::RgbaF32);
+}
+
+// Unlike RGBA8 load/store, handles tail as well,
+// since other formats are converted one pixel at a time anyway.
+#[inline(always)]
+fn load_dst_pixels(p: &mut Pipeline, color_type: ColorType) {
+    let bpp = color_type.bytes_per_pixel();
+    let data = p.pixmap_dst.bytes_at_xy(p.dx, p.dy);
+    let mut c = [[0.0; STAGE_WIDTH]; 4];
+    for (i, pixel) in data.chunks_exact(bpp).take(p.tail).enumerate() {
+        let [r, g, b, a] = color_type.read(pixel);
+        c[0][i] = r;
+        c[1][i] = g;
+        c[2][i] = b;
+        c[3][i] = a;
+    }
+
+    p.dr = f32x8::from(c[0]);
+    p.dg = f32x8::from(c[1]);
+    p.db = f32x8::from(c[2]);
+    p.da = f32x8::from(c[3]);
+
+    p.next_stage();
+}
+
+#[inline(always)]
+fn store_pixels(p: &mut Pipeline, color_type: ColorType) {
+    let r: [f32; STAGE_WIDTH] = p.r.into();
+    let g: [f32; STAGE_WIDTH] = p.g.into();
+    let b: [f32; STAGE_WIDTH] = p.b.into();
+    let a: [f32; STAGE_WIDTH] = p.a.into();
+
+    let bpp = color_type.bytes_per_pixel();
+    let tail = p.tail;
+    let data = p.pixmap_dst.bytes_at_xy(p.dx, p.dy);
+    for (i, pixel) in data.chunks_exact_mut(bpp).take(tail).enumerate() {
+        color_type.write([r[i], g[i], b[i], a[i]], pixel);
+    }
+
+    p.next_stage();
+}
+
+// Masks are always handled by lowp, but A8 bitmaps can require highp.
+pub fn load_dst_u8(p: &mut Pipeline) {
+    load_dst_pixels(p, ColorType::A8);
+}
+
+pub fn load_dst_u8_tail(p: &mut Pipeline) {
+    load_dst_pixels(p, ColorType::A8);
+}
+
+pub fn store_u8(p: &mut Pipeline) {
+    store_pixels(p, ColorType::A8);
+}
+
+pub fn store_u8_tail(p: &mut Pipeline) {
+    store_pixels(p, ColorType::A8);
 }
 
 pub fn gather(p: &mut Pipeline) {
@@ -304,7 +422,7 @@ fn gather_ix(pixmap: PixmapRef, mut x: f32x8, mut y: f32x8) -> u32x8 {
     x = x.max(f32x8::default()).min(f32x8::splat(w));
     y = y.max(f32x8::default()).min(f32x8::splat(h));
 
-    (y.trunc_int() * i32x8::splat(pixmap.width() as i32) + x.trunc_int()).to_u32x8_bitcast()
+    (y.trunc_int() * i32x8::splat(pixmap.stride_pixels() as i32) + x.trunc_int()).to_u32x8_bitcast()
 }
 
 #[inline(always)]
@@ -313,8 +431,19 @@ fn ulp_sub(v: f32) -> f32 {
     bytemuck::cast::<u32, f32>(bytemuck::cast::<f32, u32>(v) - 1)
 }
 
-fn load_mask_u8(_: &mut Pipeline) {
-    // unreachable
+fn load_mask_u8(p: &mut Pipeline) {
+    let offset = p.mask_ctx.offset(p.dx, p.dy);
+    let mut c = [0.0; 8];
+    for i in 0..p.tail {
+        c[i] = p.mask_ctx.data[offset + i] as f32;
+    }
+
+    p.r = f32x8::default();
+    p.g = f32x8::default();
+    p.b = f32x8::default();
+    p.a = f32x8::from(c) / f32x8::splat(255.0);
+
+    p.next_stage();
 }
 
 fn mask_u8(p: &mut Pipeline) {
@@ -684,6 +813,18 @@ fn transform(p: &mut Pipeline) {
     p.next_stage();
 }
 
+fn perspective(p: &mut Pipeline) {
+    let ts = &p.ctx.perspective;
+
+    let tr = mad(p.r, f32x8::splat(ts.sx), mad(p.g, f32x8::splat(ts.kx), f32x8::splat(ts.tx)));
+    let tg = mad(p.r, f32x8::splat(ts.ky), mad(p.g, f32x8::splat(ts.sy), f32x8::splat(ts.ty)));
+    let tw = mad(p.r, f32x8::splat(ts.px), mad(p.g, f32x8::splat(ts.py), f32x8::splat(ts.pz)));
+    p.r = tr / tw;
+    p.g = tg / tw;
+
+    p.next_stage();
+}
+
 // Tile x or y to [0,limit) == [0,limit - 1 ulp] (think, sampling from images).
 // The gather stages will hard clamp the output of these stages to [0,limit)...
 // we just need to do the basic repeat or mirroring.
@@ -749,6 +890,58 @@ fn bicubic(p: &mut Pipeline) {
     p.next_stage();
 }
 
+fn trilinear(p: &mut Pipeline) {
+    let ctx = p.ctx.mipmap;
+    let levels = if ctx.t == 0.0 { 1 } else { 2 };
+    let one = f32x8::splat(1.0);
+    let mut colors = [[f32x8::default(); 4]; 2];
+    for (level, [r, g, b, a]) in ctx.levels.iter().zip(&mut colors).take(levels) {
+        let Some(pixmap) = level.rect.and_then(|rect| p.pixmap_src.region(rect)) else {
+            continue;
+        };
+
+        let x = p.r * f32x8::splat(level.scale_x);
+        let fx = (x + f32x8::splat(0.5)).fract();
+        let y = p.g * f32x8::splat(level.scale_y);
+        let fy = (y + f32x8::splat(0.5)).fract();
+        let wx = [one - fx, fx];
+        let wy = [one - fy, fy];
+
+        sampler_2x2(pixmap, &level.sampler, x, y, &wx, &wy, r, g, b, a);
+    }
+
+    let [c0, c1] = colors;
+    if levels == 1 {
+        [p.r, p.g, p.b, p.a] = c0;
+    } else {
+        let t = f32x8::splat(ctx.t);
+        p.r = lerp(c0[0], c1[0], t);
+        p.g = lerp(c0[1], c1[1], t);
+        p.b = lerp(c0[2], c1[2], t);
+        p.a = lerp(c0[3], c1[3], t);
+    }
+
+    p.next_stage();
+}
+
+// Like `load_dst`, but loads colors interpolated across a triangle.
+fn vertex_color(p: &mut Pipeline) {
+    let ctx = &p.ctx.vertex_color;
+    let iota = f32x8::from([0.5, 1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5]);
+    let x = f32x8::splat(p.dx as f32) + iota;
+    let y = f32x8::splat(p.dy as f32 + 0.5);
+    let channel = |c: &[f32; 3]| mad(x, f32x8::splat(c[0]), mad(y, f32x8::splat(c[1]), f32x8::splat(c[2])));
+
+    // Pixels partially covered by a triangle can be outside of it,
+    // where colors are extrapolated and have to be clamped.
+    p.da = channel(&ctx.a).max(f32x8::default()).min(f32x8::splat(1.0));
+    p.dr = channel(&ctx.r).max(f32x8::default()).min(p.da);
+    p.dg = channel(&ctx.g).max(f32x8::default()).min(p.da);
+    p.db = channel(&ctx.b).max(f32x8::default()).min(p.da);
+
+    p.next_stage();
+}
+
 // In bicubic interpolation, the 16 pixels and +/- 0.5 and +/- 1.5 offsets from the sample
 // pixel center are combined with a non-uniform cubic filter, with higher values near the center.
 //
@@ -980,6 +1173,49 @@ fn xy_to_radius(p: &mut Pipeline) {
     p.next_stage();
 }
 
+fn xy_to_unit_angle(p: &mut Pipeline) {
+    let ctx = &p.ctx.sweep_gradient;
+
+    let x = p.r;
+    let y = p.g;
+    let x_abs = x.abs();
+    let y_abs = y.abs();
+
+    let slope = x_abs.min(y_abs) / x_abs.max(y_abs);
+    let s = slope * slope;
+
+    // Use a 7th degree polynomial to approximate atan.
+    // This was generated using sollya.gforge.inria.fr.
+    // A float optimized polynomial was generated using the following command.
+    // P1 = fpminimax((1/(2*Pi))*atan(x),[|1,3,5,7|],[|24...|],[2^(-40),1],relative);
+    let phi = slope * mad(s, mad(s, mad(s,
+        f32x8::splat(-7.0547382347285747528076171875e-3),
+        f32x8::splat(2.476101927459239959716796875e-2)),
+        f32x8::splat(-5.185396969318389892578125e-2)),
+        f32x8::splat(0.15912117063999176025390625));
+
+    let phi = x_abs.cmp_lt(y_abs).blend(f32x8::splat(1.0 / 4.0) - phi, phi);
+    let phi = x.cmp_lt(f32x8::default()).blend(f32x8::splat(1.0 / 2.0) - phi, phi);
+    let phi = y.cmp_lt(f32x8::default()).blend(f32x8::splat(1.0) - phi, phi);
+    let phi = phi.cmp_ne(phi).blend(f32x8::default(), phi); // Check for NaN.
+
+    // Unlike Skia, we apply the start/end angles scale and bias here,
+    // since we have only one transform per pipeline.
+    p.r = (phi + f32x8::splat(ctx.t_bias)) * f32x8::splat(ctx.t_scale);
+
+    p.next_stage();
+}
+
+fn xy_to_2pt_conical_strip(p: &mut Pipeline) {
+    let ctx = &p.ctx.two_point_conical_gradient;
+
+    let x = p.r;
+    let y = p.g;
+    p.r = x + (f32x8::splat(ctx.p0) - y * y).sqrt();
+
+    p.next_stage();
+}
+
 fn xy_to_2pt_conical_focal_on_circle(p: &mut Pipeline) {
     let x = p.r;
     let y = p.g;
@@ -1008,6 +1244,59 @@ fn xy_to_2pt_conical_greater(p: &mut Pipeline) {
     p.next_stage();
 }
 
+fn xy_to_2pt_conical_smaller(p: &mut Pipeline) {
+    let ctx = &p.ctx.two_point_conical_gradient;
+
+    let x = p.r;
+    let y = p.g;
+    p.r = -(x * x - y * y).sqrt() - x * f32x8::splat(ctx.p0);
+
+    p.next_stage();
+}
+
+fn alter_2pt_conical_compensate_focal(p: &mut Pipeline) {
+    let ctx = &p.ctx.two_point_conical_gradient;
+
+    p.r += f32x8::splat(ctx.p1);
+
+    p.next_stage();
+}
+
+fn alter_2pt_conical_unswap(p: &mut Pipeline) {
+    p.r = f32x8::splat(1.0) - p.r;
+
+    p.next_stage();
+}
+
+fn negate_x(p: &mut Pipeline) {
+    p.r = -p.r;
+
+    p.next_stage();
+}
+
+fn mask_2pt_conical_nan(p: &mut Pipeline) {
+    let ctx = &mut p.ctx.two_point_conical_gradient;
+
+    let t = p.r;
+    let is_degenerate = t.cmp_ne(t);
+    p.r = is_degenerate.blend(f32x8::default(), t);
+
+    let is_not_degenerate = !is_degenerate.to_u32x8_bitcast();
+    let is_not_degenerate: [u32; 8] = bytemuck::cast(is_not_degenerate);
+    ctx.mask = bytemuck::cast([
+        if is_not_degenerate[0] != 0 { !0 } else { 0 },
+        if is_not_degenerate[1] != 0 { !0 } else { 0 },
+        if is_not_degenerate[2] != 0 { !0 } else { 0 },
+        if is_not_degenerate[3] != 0 { !0 } else { 0 },
+        if is_not_degenerate[4] != 0 { !0 } else { 0 },
+        if is_not_degenerate[5] != 0 { !0 } else { 0 },
+        if is_not_degenerate[6] != 0 { !0 } else { 0 },
+        if is_not_degenerate[7] != 0 { !0 } else { 0 },
+    ]);
+
+    p.next_stage();
+}
+
 fn mask_2pt_conical_degenerates(p: &mut Pipeline) {
     let ctx = &mut p.ctx.two_point_conical_gradient;
 
@@ -1042,6 +1331,109 @@ fn apply_vector_mask(p: &mut Pipeline) {
     p.next_stage();
 }
 
+fn unpremultiply(p: &mut Pipeline) {
+    let scale = p.a.cmp_eq(f32x8::default()).blend(f32x8::default(), f32x8::splat(1.0) / p.a);
+    p.r *= scale;
+    p.g *= scale;
+    p.b *= scale;
+
+    p.next_stage();
+}
+
+fn color_matrix(p: &mut Pipeline) {
+    let m = &p.ctx.color_filter.matrix;
+    let (r, g, b, a) = (p.r, p.g, p.b, p.a);
+
+    macro_rules! row {
+        ($i:expr) => {
+            mad(r, f32x8::splat(m[$i]), mad(g, f32x8::splat(m[$i + 1]),
+                mad(b, f32x8::splat(m[$i + 2]), mad(a, f32x8::splat(m[$i + 3]), f32x8::splat(m[$i + 4])))))
+        };
+    }
+
+    p.r = row!(0);
+    p.g = row!(5);
+    p.b = row!(10);
+    p.a = row!(15);
+
+    p.next_stage();
+}
+
+fn color_table(p: &mut Pipeline) {
+    let tables = p.ctx.color_filter.tables.as_slice();
+
+    #[inline(always)]
+    fn lookup(table: &[u8], c: f32x8) -> f32x8 {
+        let idx: [i32; 8] = bytemuck::cast((c * f32x8::splat(255.0)).round_int());
+        // Negative indices turn into huge ones, so we have to clamp them as well.
+        let v = |i: i32| table[(i.max(0) as usize).min(255)] as f32 * (1.0 / 255.0);
+        f32x8::from([
+            v(idx[0]), v(idx[1]), v(idx[2]), v(idx[3]),
+            v(idx[4]), v(idx[5]), v(idx[6]), v(idx[7]),
+        ])
+    }
+
+    p.r = lookup(&tables[0..256], p.r);
+    p.g = lookup(&tables[256..512], p.g);
+    p.b = lookup(&tables[512..768], p.b);
+    p.a = lookup(&tables[768..1024], p.a);
+
+    p.next_stage();
+}
+
+fn filter_color(p: &mut Pipeline) {
+    let c = &p.ctx.color_filter.color;
+    p.r = f32x8::splat(c[0]);
+    p.g = f32x8::splat(c[1]);
+    p.b = f32x8::splat(c[2]);
+    p.a = f32x8::splat(c[3]);
+
+    p.next_stage();
+}
+
+fn srgb_to_linear(p: &mut Pipeline) {
+    let a = p.a;
+    p.r = transfer_premultiplied(p.r, a, color::srgb_to_linear);
+    p.g = transfer_premultiplied(p.g, a, color::srgb_to_linear);
+    p.b = transfer_premultiplied(p.b, a, color::srgb_to_linear);
+
+    p.next_stage();
+}
+
+fn srgb_to_linear_destination(p: &mut Pipeline) {
+    let a = p.da;
+    p.dr = transfer_premultiplied(p.dr, a, color::srgb_to_linear);
+    p.dg = transfer_premultiplied(p.dg, a, color::srgb_to_linear);
+    p.db = transfer_premultiplied(p.db, a, color::srgb_to_linear);
+
+    p.next_stage();
+}
+
+fn linear_to_srgb(p: &mut Pipeline) {
+    let a = p.a;
+    p.r = transfer_premultiplied(p.r, a, color::linear_to_srgb);
+    p.g = transfer_premultiplied(p.g, a, color::linear_to_srgb);
+    p.b = transfer_premultiplied(p.b, a, color::linear_to_srgb);
+
+    p.next_stage();
+}
+
+// Transfer functions are defined for unpremultiplied values,
+// so we have to unpremultiply, apply and premultiply back.
+#[inline(always)]
+fn transfer_premultiplied(c: f32x8, a: f32x8, f: fn(f32) -> f32) -> f32x8 {
+    let c: [f32; 8] = c.into();
+    let a: [f32; 8] = a.into();
+    let mut out = [0.0; 8];
+    for i in 0..STAGE_WIDTH {
+        if a[i] > 0.0 {
+            out[i] = f(c[i] / a[i]) * a[i];
+        }
+    }
+
+    f32x8::from(out)
+}
+
 pub fn just_return(_: &mut Pipeline) {
     // Ends the loop.
 }
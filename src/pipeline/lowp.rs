@@ -117,21 +117,50 @@ pub const STAGES: &[StageFn; super::STAGES_COUNT] = &[
     null_fn, // Luminosity
     source_over_rgba,
     transform,
+    null_fn, // Perspective
     null_fn, // Reflect
     null_fn, // Repeat
     null_fn, // Bilinear
     null_fn, // Bicubic
+    null_fn, // Trilinear
+    null_fn, // VertexColor
     pad_x1,
     reflect_x1,
     repeat_x1,
     gradient,
     evenly_spaced_2_stop_gradient,
     xy_to_radius,
+    xy_to_unit_angle,
+    null_fn, // XYTo2PtConicalStrip
     null_fn, // XYTo2PtConicalFocalOnCircle
     null_fn, // XYTo2PtConicalWellBehaved
     null_fn, // XYTo2PtConicalGreater
+    null_fn, // XYTo2PtConicalSmaller
+    alter_2pt_conical_compensate_focal,
+    alter_2pt_conical_unswap,
+    null_fn, // NegateX
+    null_fn, // Mask2PtConicalNan
     null_fn, // Mask2PtConicalDegenerates
     null_fn, // ApplyVectorMask
+    null_fn, // Unpremultiply
+    null_fn, // ColorMatrix
+    null_fn, // ColorTable
+    null_fn, // FilterColor
+    null_fn, // SrgbToLinear
+    null_fn, // SrgbToLinearDestination
+    null_fn, // LinearToSrgb
+    load_dst_bgra8,
+    store_bgra8,
+    load_dst_rgb565,
+    store_rgb565,
+    load_dst_gray8,
+    store_gray8,
+    null_fn, // LoadDestinationRgba16
+    null_fn, // StoreRgba16
+    null_fn, // LoadDestinationRgbaF16
+    null_fn, // StoreRgbaF16
+    null_fn, // LoadDestinationRgbaF32
+    null_fn, // StoreRgbaF32
 ];
 
 pub fn fn_ptr(f: StageFn) -> *const () {
@@ -637,6 +666,55 @@ fn xy_to_radius(p: &mut Pipeline) {
     p.next_stage();
 }
 
+fn xy_to_unit_angle(p: &mut Pipeline) {
+    let ctx = &p.ctx.sweep_gradient;
+
+    let x = join(&p.r, &p.g);
+    let y = join(&p.b, &p.a);
+    let x_abs = x.abs();
+    let y_abs = y.abs();
+
+    let slope = x_abs.min(&y_abs) / x_abs.max(&y_abs);
+    let s = slope * slope;
+
+    // Use a 7th degree polynomial to approximate atan.
+    // See highp for details.
+    let phi = slope * mad(s, mad(s, mad(s,
+        f32x16::splat(-7.0547382347285747528076171875e-3),
+        f32x16::splat(2.476101927459239959716796875e-2)),
+        f32x16::splat(-5.185396969318389892578125e-2)),
+        f32x16::splat(0.15912117063999176025390625));
+
+    let phi = x_abs.cmp_lt(&y_abs).blend(f32x16::splat(1.0 / 4.0) - phi, phi);
+    let phi = x.cmp_lt(&f32x16::default()).blend(f32x16::splat(1.0 / 2.0) - phi, phi);
+    let phi = y.cmp_lt(&f32x16::default()).blend(f32x16::splat(1.0) - phi, phi);
+    let phi = phi.cmp_ne(&phi).blend(f32x16::default(), phi); // Check for NaN.
+
+    let phi = (phi + f32x16::splat(ctx.t_bias)) * f32x16::splat(ctx.t_scale);
+    split(&phi, &mut p.r, &mut p.g);
+    split(&y, &mut p.b, &mut p.a);
+
+    p.next_stage();
+}
+
+fn alter_2pt_conical_compensate_focal(p: &mut Pipeline) {
+    let ctx = &p.ctx.two_point_conical_gradient;
+
+    let t = join(&p.r, &p.g);
+    let t = t + f32x16::splat(ctx.p1);
+    split(&t, &mut p.r, &mut p.g);
+
+    p.next_stage();
+}
+
+fn alter_2pt_conical_unswap(p: &mut Pipeline) {
+    let t = join(&p.r, &p.g);
+    let t = f32x16::splat(1.0) - t;
+    split(&t, &mut p.r, &mut p.g);
+
+    p.next_stage();
+}
+
 // We are using u16 for index, not u32 as Skia, to simplify the code a bit.
 // The gradient creation code will not allow that many stops anyway.
 fn gradient_lookup(
@@ -714,6 +792,89 @@ pub fn just_return(_: &mut Pipeline) {
     // Ends the loop.
 }
 
+fn load_dst_bgra8(p: &mut Pipeline) {
+    load_dst_pixels(p, |d| [d[2].into(), d[1].into(), d[0].into(), d[3].into()]);
+}
+
+fn store_bgra8(p: &mut Pipeline) {
+    store_pixels(p, |c, d| {
+        d[0] = c[2] as u8;
+        d[1] = c[1] as u8;
+        d[2] = c[0] as u8;
+        d[3] = c[3] as u8;
+    });
+}
+
+fn load_dst_rgb565(p: &mut Pipeline) {
+    load_dst_pixels(p, |d| {
+        let n = u16::from_ne_bytes([d[0], d[1]]);
+        let r = n >> 11;
+        let g = (n >> 5) & 63;
+        let b = n & 31;
+        [(r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2), 255]
+    });
+}
+
+fn store_rgb565(p: &mut Pipeline) {
+    store_pixels(p, |c, d| {
+        let r = (c[0] * 31 + 127) / 255;
+        let g = (c[1] * 63 + 127) / 255;
+        let b = (c[2] * 31 + 127) / 255;
+        d[..2].copy_from_slice(&(r << 11 | g << 5 | b).to_ne_bytes());
+    });
+}
+
+fn load_dst_gray8(p: &mut Pipeline) {
+    load_dst_pixels(p, |d| [d[0].into(), d[0].into(), d[0].into(), 255]);
+}
+
+fn store_gray8(p: &mut Pipeline) {
+    store_pixels(p, |c, d| {
+        // Rec. 709 luma coefficients in 8-bit fixed point.
+        d[0] = ((c[0] * 54 + c[1] * 183 + c[2] * 19 + 128) >> 8) as u8;
+    });
+}
+
+// Unlike RGBA8 and A8 load/store, handles tail as well,
+// since other formats are converted one pixel at a time anyway.
+#[inline(always)]
+fn load_dst_pixels(p: &mut Pipeline, read: impl Fn(&[u8]) -> [u16; 4]) {
+    let bpp = p.pixmap.color_type.bytes_per_pixel();
+    let tail = p.tail;
+    let data = p.pixmap.bytes_at_xy(p.dx, p.dy);
+    let mut c = [[0; STAGE_WIDTH]; 4];
+    for (i, pixel) in data.chunks_exact(bpp).take(tail).enumerate() {
+        let [r, g, b, a] = read(pixel);
+        c[0][i] = r;
+        c[1][i] = g;
+        c[2][i] = b;
+        c[3][i] = a;
+    }
+
+    p.dr = u16x16(c[0]);
+    p.dg = u16x16(c[1]);
+    p.db = u16x16(c[2]);
+    p.da = u16x16(c[3]);
+
+    p.next_stage();
+}
+
+#[inline(always)]
+fn store_pixels(p: &mut Pipeline, write: impl Fn([u16; 4], &mut [u8])) {
+    let r = p.r.as_slice();
+    let g = p.g.as_slice();
+    let b = p.b.as_slice();
+    let a = p.a.as_slice();
+
+    let bpp = p.pixmap.color_type.bytes_per_pixel();
+    let data = p.pixmap.bytes_at_xy(p.dx, p.dy);
+    for (i, pixel) in data.chunks_exact_mut(bpp).take(p.tail).enumerate() {
+        write([r[i], g[i], b[i], a[i]], pixel);
+    }
+
+    p.next_stage();
+}
+
 pub fn null_fn(_: &mut Pipeline) {
     // Just for unsupported functions in STAGES.
 }
@@ -50,10 +50,10 @@ use arrayvec::ArrayVec;
 
 use tiny_skia_path::NormalizedF32;
 
-use crate::{Color, PremultipliedColor, PremultipliedColorU8, SpreadMode};
-use crate::{PixmapRef, Transform};
+use crate::{Color, ColorType, PremultipliedColor, PremultipliedColorU8, SpreadMode};
+use crate::{IntRect, PerspectiveTransform, PixmapRef, Transform};
 
-pub use blitter::RasterPipelineBlitter;
+pub use blitter::{RasterPipelineBlitter, VertexColors};
 
 use crate::geom::ScreenIntRect;
 use crate::pixmap::SubPixmapMut;
@@ -115,24 +115,67 @@ pub enum Stage {
     Luminosity,
     SourceOverRgba,
     Transform,
+    Perspective,
     Reflect,
     Repeat,
     Bilinear,
     Bicubic,
+    Trilinear,
+    VertexColor,
     PadX1,
     ReflectX1,
     RepeatX1,
     Gradient,
     EvenlySpaced2StopGradient,
     XYToRadius,
+    XYToUnitAngle,
+    XYTo2PtConicalStrip,
     XYTo2PtConicalFocalOnCircle,
     XYTo2PtConicalWellBehaved,
     XYTo2PtConicalGreater,
+    XYTo2PtConicalSmaller,
+    Alter2PtConicalCompensateFocal,
+    Alter2PtConicalUnswap,
+    NegateX,
+    Mask2PtConicalNan,
     Mask2PtConicalDegenerates,
     ApplyVectorMask,
+    Unpremultiply,
+    ColorMatrix,
+    ColorTable,
+    FilterColor,
+    SrgbToLinear,
+    SrgbToLinearDestination,
+    LinearToSrgb,
+    LoadDestinationBgra8,
+    StoreBgra8,
+    LoadDestinationRgb565,
+    StoreRgb565,
+    LoadDestinationGray8,
+    StoreGray8,
+    LoadDestinationRgba16,
+    StoreRgba16,
+    LoadDestinationRgbaF16,
+    StoreRgbaF16,
+    LoadDestinationRgbaF32,
+    StoreRgbaF32,
 }
 
-pub const STAGES_COUNT: usize = Stage::ApplyVectorMask as usize + 1;
+pub const STAGES_COUNT: usize = Stage::StoreRgbaF32 as usize + 1;
+
+/// Returns stages that load and store destination pixels of the specified color type.
+pub fn load_store_stages(color_type: ColorType) -> (Stage, Stage) {
+    match color_type {
+        ColorType::Rgba8 => (Stage::LoadDestination, Stage::Store),
+        ColorType::Bgra8 => (Stage::LoadDestinationBgra8, Stage::StoreBgra8),
+        ColorType::Rgb565 => (Stage::LoadDestinationRgb565, Stage::StoreRgb565),
+        ColorType::Gray8 => (Stage::LoadDestinationGray8, Stage::StoreGray8),
+        ColorType::A8 => (Stage::LoadDestinationU8, Stage::StoreU8),
+        ColorType::Rgba16 => (Stage::LoadDestinationRgba16, Stage::StoreRgba16),
+        ColorType::RgbaF16 => (Stage::LoadDestinationRgbaF16, Stage::StoreRgbaF16),
+        ColorType::RgbaF32 => (Stage::LoadDestinationRgbaF32, Stage::StoreRgbaF32),
+    }
+}
 
 impl<'a> PixmapRef<'a> {
     #[inline(always)]
@@ -155,7 +198,7 @@ impl<'a> PixmapRef<'a> {
 impl<'a> SubPixmapMut<'a> {
     #[inline(always)]
     pub(crate) const fn offset(&self, dx: usize, dy: usize) -> usize {
-        self.real_width * dy + dx
+        self.real_width * (dy - self.row_offset) + dx
     }
 
     #[inline(always)]
@@ -164,6 +207,12 @@ impl<'a> SubPixmapMut<'a> {
         &mut self.pixels_mut()[offset..]
     }
 
+    #[inline(always)]
+    pub(crate) fn bytes_at_xy(&mut self, dx: usize, dy: usize) -> &mut [u8] {
+        let offset = self.offset(dx, dy) * self.color_type.bytes_per_pixel();
+        &mut self.data[offset..]
+    }
+
     #[inline(always)]
     pub(crate) fn slice_mask_at_xy(&mut self, dx: usize, dy: usize) -> &mut [u8] {
         let offset = self.offset(dx, dy);
@@ -223,12 +272,13 @@ impl AAMaskCtx {
 pub struct MaskCtx<'a> {
     pub data: &'a [u8],
     pub real_width: u32,
+    pub row_offset: u32,
 }
 
 impl MaskCtx<'_> {
     #[inline(always)]
     const fn offset(&self, dx: usize, dy: usize) -> usize {
-        self.real_width as usize * dy + dx
+        self.real_width as usize * (dy - self.row_offset as usize) + dx
     }
 }
 
@@ -236,13 +286,18 @@ impl MaskCtx<'_> {
 pub struct Context {
     pub current_coverage: f32,
     pub sampler: SamplerCtx,
+    pub mipmap: MipmapCtx,
+    pub vertex_color: VertexColorCtx,
     pub uniform_color: UniformColorCtx,
     pub evenly_spaced_2_stop_gradient: EvenlySpaced2StopGradientCtx,
     pub gradient: GradientCtx,
     pub two_point_conical_gradient: TwoPointConicalGradientCtx,
+    pub sweep_gradient: SweepGradientCtx,
     pub limit_x: TileCtx,
     pub limit_y: TileCtx,
     pub transform: Transform,
+    pub perspective: PerspectiveTransform,
+    pub color_filter: ColorFilterCtx,
 }
 
 #[derive(Copy, Clone, Default, Debug)]
@@ -252,6 +307,29 @@ pub struct SamplerCtx {
     pub inv_height: f32,
 }
 
+#[derive(Copy, Clone, Default, Debug)]
+pub struct MipmapCtx {
+    pub levels: [MipmapLevelCtx; 2],
+    pub t: f32, // the weight of the second level
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct MipmapLevelCtx {
+    pub rect: Option<IntRect>, // the level location inside the source pixmap
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub sampler: SamplerCtx,
+}
+
+// Each premultiplied channel is `x * c[0] + y * c[1] + c[2]` in device coordinates.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct VertexColorCtx {
+    pub r: [f32; 3],
+    pub g: [f32; 3],
+    pub b: [f32; 3],
+    pub a: [f32; 3],
+}
+
 #[derive(Copy, Clone, Default, Debug)]
 pub struct UniformColorCtx {
     pub r: f32,
@@ -315,9 +393,25 @@ impl GradientCtx {
 
 #[derive(Copy, Clone, Default, Debug)]
 pub struct TwoPointConicalGradientCtx {
-    // This context is used only in highp, where we use Tx4.
+    // The mask is used only in highp, where we use Tx4.
     pub mask: u32x8,
     pub p0: f32,
+    pub p1: f32,
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SweepGradientCtx {
+    pub t_bias: f32,
+    pub t_scale: f32,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct ColorFilterCtx {
+    pub matrix: [f32; 20],
+    /// RGBA lookup tables, 256 values each.
+    pub tables: Vec<u8>,
+    /// A premultiplied RGBA color.
+    pub color: [f32; 4],
 }
 
 #[derive(Copy, Clone, Default, Debug)]
@@ -356,6 +450,15 @@ impl RasterPipelineBuilder {
         }
     }
 
+    pub fn push_perspective(&mut self, ts: PerspectiveTransform) {
+        if let Some(ts) = ts.to_affine() {
+            self.push_transform(ts);
+        } else if ts.is_finite() {
+            self.stages.push(Stage::Perspective);
+            self.ctx.perspective = ts;
+        }
+    }
+
     pub fn push_uniform_color(&mut self, c: PremultipliedColor) {
         let r = c.red();
         let g = c.green();
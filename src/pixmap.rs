@@ -14,7 +14,7 @@ use rayon_macro::parallel;
 
 use tiny_skia_path::IntSize;
 
-use crate::{Color, IntRect};
+use crate::{Color, ColorType, IntRect, LengthU32};
 
 use crate::color::PremultipliedColorU8;
 use crate::geom::{IntSizeExt, ScreenIntRect};
@@ -186,6 +186,7 @@ impl Pixmap {
         PixmapRef {
             data: &self.data,
             size: self.size,
+            stride: self.size.width() as usize * BYTES_PER_PIXEL,
         }
     }
 
@@ -194,6 +195,8 @@ impl Pixmap {
         PixmapMut {
             data: &mut self.data,
             size: self.size,
+            stride: self.size.width() as usize * BYTES_PER_PIXEL,
+            color_type: ColorType::Rgba8,
         }
     }
 
@@ -284,11 +287,13 @@ impl core::fmt::Debug for Pixmap {
 ///
 /// Can be created from `Pixmap` or from a user provided data.
 ///
-/// The data is not aligned, therefore width == stride.
+/// Unless created using [`PixmapRef::from_bytes_with_stride`], width == stride.
 #[derive(Clone, Copy, PartialEq)]
 pub struct PixmapRef<'a> {
     data: &'a [u8],
     size: IntSize,
+    // In bytes.
+    stride: usize,
 }
 
 impl<'a> PixmapRef<'a> {
@@ -305,15 +310,52 @@ impl<'a> PixmapRef<'a> {
             return None;
         }
 
-        Some(PixmapRef { data, size })
+        Some(PixmapRef {
+            data,
+            size,
+            stride: size.width() as usize * BYTES_PER_PIXEL,
+        })
+    }
+
+    /// Creates a new `PixmapRef` from bytes with an explicit row stride.
+    ///
+    /// `stride` is the distance between rows in bytes. It must be a multiple of
+    /// `BYTES_PER_PIXEL` and at least `width * BYTES_PER_PIXEL`.
+    /// This allows referencing a subregion of a larger image or padded rows.
+    ///
+    /// The size must be at least `(height - 1) * stride + width * BYTES_PER_PIXEL`.
+    /// Zero size in an error. Stride is limited by `i32::MAX`.
+    pub fn from_bytes_with_stride(
+        data: &'a [u8],
+        width: u32,
+        height: u32,
+        stride: usize,
+    ) -> Option<Self> {
+        let size = IntSize::from_wh(width, height)?;
+        let data_len = data_len_for_stride(size, stride)?;
+        if data.len() < data_len {
+            return None;
+        }
+
+        Some(PixmapRef {
+            data: &data[..data_len],
+            size,
+            stride,
+        })
     }
 
     /// Creates a new `Pixmap` from the current data.
     ///
-    /// Clones the underlying data.
+    /// Clones the underlying data. Row padding is not copied.
     pub fn to_owned(&self) -> Pixmap {
+        let row_bytes = self.width() as usize * BYTES_PER_PIXEL;
+        let mut data = Vec::with_capacity(row_bytes * self.height() as usize);
+        for row in self.data.chunks(self.stride).take(self.height() as usize) {
+            data.extend_from_slice(&row[..row_bytes]);
+        }
+
         Pixmap {
-            data: self.data.to_vec(),
+            data,
             size: self.size,
         }
     }
@@ -330,11 +372,23 @@ impl<'a> PixmapRef<'a> {
         self.size.height()
     }
 
+    /// Returns the distance between rows in bytes.
+    #[inline]
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
     /// Returns pixmap's size.
     pub(crate) const fn size(&self) -> IntSize {
         self.size
     }
 
+    /// Returns the distance between rows in pixels.
+    #[inline]
+    pub(crate) const fn stride_pixels(&self) -> usize {
+        self.stride / BYTES_PER_PIXEL
+    }
+
     /// Returns pixmap's rect.
     pub(crate) fn rect(&self) -> ScreenIntRect {
         self.size.to_screen_int_rect(0, 0)
@@ -342,6 +396,8 @@ impl<'a> PixmapRef<'a> {
 
     /// Returns the internal data.
     ///
+    /// Includes row padding, if any.
+    ///
     /// Byteorder: RGBA
     pub const fn data(&self) -> &'a [u8] {
         self.data
@@ -351,16 +407,29 @@ impl<'a> PixmapRef<'a> {
     ///
     /// Returns `None` when position is out of bounds.
     pub fn pixel(&self, x: u32, y: u32) -> Option<PremultipliedColorU8> {
-        let idx = self.width().checked_mul(y)?.checked_add(x)?;
-        self.pixels().get(idx as usize).copied()
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        let idx = y as usize * self.stride_pixels() + x as usize;
+        self.pixels().get(idx).copied()
     }
 
     /// Returns a slice of pixels.
+    ///
+    /// Includes row padding, if any. Use [`PixmapRef::rows`] to skip it.
     pub fn pixels(&self) -> &'a [PremultipliedColorU8] {
         bytemuck::cast_slice(self.data())
     }
 
-    // TODO: add rows() iterator
+    /// Returns an iterator over pixel rows, without padding.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [PremultipliedColorU8]> {
+        let width = self.width() as usize;
+        self.pixels()
+            .chunks(self.stride_pixels())
+            .take(self.height() as usize)
+            .map(move |row| &row[..width])
+    }
 
     /// Returns a copy of the pixmap that intersects the `rect`.
     ///
@@ -378,9 +447,10 @@ impl<'a> PixmapRef<'a> {
             // TODO: optimize
             for y in 0..rect.height() {
                 for x in 0..rect.width() {
-                    let old_idx = (y + rect.y() as u32) * self.width() + (x + rect.x() as u32);
+                    let old_idx = (y + rect.y() as u32) as usize * self.stride_pixels()
+                        + (x + rect.x() as u32) as usize;
                     let new_idx = y * rect.width() + x;
-                    new_pixels[new_idx as usize] = old_pixels[old_idx as usize];
+                    new_pixels[new_idx as usize] = old_pixels[old_idx];
                 }
             }
         }
@@ -388,6 +458,24 @@ impl<'a> PixmapRef<'a> {
         Some(new)
     }
 
+    /// Returns a view into a region of the pixmap.
+    ///
+    /// Unlike `clone_rect`, doesn't copy anything and
+    /// returns `None` when `rect` is not fully inside the pixmap.
+    pub(crate) fn region(&self, rect: IntRect) -> Option<PixmapRef<'a>> {
+        if rect.intersect(&self.rect().to_int_rect())? != rect {
+            return None;
+        }
+
+        let offset = rect.y() as usize * self.stride + rect.x() as usize * BYTES_PER_PIXEL;
+        PixmapRef::from_bytes_with_stride(
+            &self.data[offset..],
+            rect.width(),
+            rect.height(),
+            self.stride,
+        )
+    }
+
     /// Encodes pixmap into a PNG data.
     #[cfg(feature = "png-format")]
     pub fn encode_png(&self) -> Result<Vec<u8>, png::EncodingError> {
@@ -443,11 +531,15 @@ impl core::fmt::Debug for PixmapRef<'_> {
 ///
 /// Can be created from `Pixmap` or from a user provided data.
 ///
-/// The data is not aligned, therefore width == stride.
+/// Unless created using [`PixmapMut::from_bytes_with_stride`], width == stride.
 #[derive(PartialEq)]
 pub struct PixmapMut<'a> {
     data: &'a mut [u8],
     size: IntSize,
+    // In bytes.
+    stride: usize,
+    // Always `Rgba8`, unless created by a `BitmapMut`.
+    color_type: ColorType,
 }
 
 impl<'a> PixmapMut<'a> {
@@ -464,17 +556,66 @@ impl<'a> PixmapMut<'a> {
             return None;
         }
 
-        Some(PixmapMut { data, size })
+        Some(PixmapMut {
+            data,
+            size,
+            stride: size.width() as usize * BYTES_PER_PIXEL,
+            color_type: ColorType::Rgba8,
+        })
+    }
+
+    /// Creates a new `PixmapMut` from bytes with an explicit row stride.
+    ///
+    /// `stride` is the distance between rows in bytes. It must be a multiple of
+    /// `BYTES_PER_PIXEL` and at least `width * BYTES_PER_PIXEL`.
+    /// This allows rendering into a subregion of a larger framebuffer or into padded rows.
+    /// Padding is never modified.
+    ///
+    /// The size must be at least `(height - 1) * stride + width * BYTES_PER_PIXEL`.
+    /// Zero size in an error. Stride is limited by `i32::MAX`.
+    pub fn from_bytes_with_stride(
+        data: &'a mut [u8],
+        width: u32,
+        height: u32,
+        stride: usize,
+    ) -> Option<Self> {
+        let size = IntSize::from_wh(width, height)?;
+        let data_len = data_len_for_stride(size, stride)?;
+        if data.len() < data_len {
+            return None;
+        }
+
+        Some(PixmapMut {
+            data: &mut data[..data_len],
+            size,
+            stride,
+            color_type: ColorType::Rgba8,
+        })
+    }
+
+    /// Creates a render target for a `BitmapMut`.
+    ///
+    /// Such `PixmapMut` must never be exposed to the caller,
+    /// since pixel accessors assume RGBA8 data.
+    pub(crate) fn from_bitmap_data(
+        data: &'a mut [u8],
+        size: IntSize,
+        stride: usize,
+        color_type: ColorType,
+    ) -> Self {
+        PixmapMut {
+            data,
+            size,
+            stride,
+            color_type,
+        }
     }
 
     /// Creates a new `Pixmap` from the current data.
     ///
-    /// Clones the underlying data.
+    /// Clones the underlying data. Row padding is not copied.
     pub fn to_owned(&self) -> Pixmap {
-        Pixmap {
-            data: self.data.to_vec(),
-            size: self.size,
-        }
+        self.as_ref().to_owned()
     }
 
     /// Returns a container that references Pixmap's data.
@@ -482,6 +623,17 @@ impl<'a> PixmapMut<'a> {
         PixmapRef {
             data: self.data,
             size: self.size,
+            stride: self.stride,
+        }
+    }
+
+    /// Reborrows the current `PixmapMut`.
+    pub(crate) fn reborrow(&mut self) -> PixmapMut<'_> {
+        PixmapMut {
+            data: self.data,
+            size: self.size,
+            stride: self.stride,
+            color_type: self.color_type,
         }
     }
 
@@ -497,37 +649,75 @@ impl<'a> PixmapMut<'a> {
         self.size.height()
     }
 
+    /// Returns the distance between rows in bytes.
+    #[inline]
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
     /// Returns pixmap's size.
     pub(crate) const fn size(&self) -> IntSize {
         self.size
     }
 
+    pub(crate) const fn color_type(&self) -> ColorType {
+        self.color_type
+    }
+
+    /// Returns the distance between rows in pixels.
+    const fn stride_pixels(&self) -> usize {
+        self.stride / self.color_type.bytes_per_pixel()
+    }
+
     /// Fills the entire pixmap with a specified color.
     pub fn fill(&mut self, color: Color) {
         let c = color.premultiply().to_color_u8();
-        parallel!(for p in self.pixels_mut() {
-            *p = c;
-        });
+        if self.stride == self.width() as usize * BYTES_PER_PIXEL {
+            parallel!(for p in self.pixels_mut() {
+                *p = c;
+            });
+        } else {
+            for row in self.rows_mut() {
+                row.fill(c);
+            }
+        }
     }
 
     /// Returns the mutable internal data.
     ///
+    /// Includes row padding, if any.
+    ///
     /// Byteorder: RGBA
     pub fn data_mut(&mut self) -> &mut [u8] {
         self.data
     }
 
     /// Returns a mutable slice of pixels.
+    ///
+    /// Includes row padding, if any. Use [`PixmapMut::rows_mut`] to skip it.
     pub fn pixels_mut(&mut self) -> &mut [PremultipliedColorU8] {
         bytemuck::cast_slice_mut(self.data_mut())
     }
 
+    /// Returns an iterator over mutable pixel rows, without padding.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [PremultipliedColorU8]> {
+        let width = self.width() as usize;
+        let height = self.height() as usize;
+        let stride = self.stride_pixels();
+        self.pixels_mut()
+            .chunks_mut(stride)
+            .take(height)
+            .map(move |row| &mut row[..width])
+    }
+
     /// Creates `SubPixmapMut` that contains the whole `PixmapMut`.
     pub(crate) fn as_subpixmap(&mut self) -> SubPixmapMut {
         SubPixmapMut {
             size: self.size(),
-            real_width: self.width() as usize,
+            real_width: self.stride_pixels(),
             data: self.data,
+            row_offset: 0,
+            color_type: self.color_type,
         }
     }
 
@@ -536,15 +726,41 @@ impl<'a> PixmapMut<'a> {
     /// Returns `None` when `Pixmap`'s rect doesn't contain `rect`.
     pub(crate) fn subpixmap(&mut self, rect: IntRect) -> Option<SubPixmapMut> {
         let rect = self.size.to_int_rect(0, 0).intersect(&rect)?;
-        let row_bytes = self.width() as usize * BYTES_PER_PIXEL;
-        let offset = rect.top() as usize * row_bytes + rect.left() as usize * BYTES_PER_PIXEL;
+        let bpp = self.color_type.bytes_per_pixel();
+        let offset = rect.top() as usize * self.stride + rect.left() as usize * bpp;
 
         Some(SubPixmapMut {
             size: rect.size(),
-            real_width: self.width() as usize,
+            real_width: self.stride_pixels(),
             data: &mut self.data[offset..],
+            row_offset: 0,
+            color_type: self.color_type,
         })
     }
+
+    /// Splits `PixmapMut` into row bands with the specified height.
+    ///
+    /// Unlike `subpixmap`, bands are addressed using the `PixmapMut` coordinates.
+    pub(crate) fn bands(&mut self, height: LengthU32) -> Vec<SubPixmapMut<'_>> {
+        let width = self.width();
+        let color_type = self.color_type;
+        let real_width = self.stride_pixels();
+        let stride = self.stride;
+        let row_bytes = width as usize * color_type.bytes_per_pixel();
+        self.data
+            .chunks_mut(height.get() as usize * stride)
+            .enumerate()
+            .map(|(i, data)| SubPixmapMut {
+                // The last row has no padding.
+                size: IntSize::from_wh(width, ((data.len() - row_bytes) / stride + 1) as u32)
+                    .unwrap(),
+                real_width,
+                data,
+                row_offset: i * height.get() as usize,
+                color_type,
+            })
+            .collect()
+    }
 }
 
 impl core::fmt::Debug for PixmapMut<'_> {
@@ -571,6 +787,11 @@ pub struct SubPixmapMut<'a> {
     pub data: &'a mut [u8],
     pub size: IntSize,
     pub real_width: usize,
+    /// A row that `data` starts at.
+    ///
+    /// Non-zero only for row bands, which are addressed using the parent coordinates.
+    pub row_offset: usize,
+    pub color_type: ColorType,
 }
 
 impl<'a> SubPixmapMut<'a> {
@@ -603,3 +824,13 @@ fn data_len_for_size(size: IntSize) -> Option<usize> {
     let row_bytes = min_row_bytes(size)?;
     compute_data_len(size, row_bytes.get())
 }
+
+fn data_len_for_stride(size: IntSize, stride: usize) -> Option<usize> {
+    let row_bytes = min_row_bytes(size)?;
+    if stride < row_bytes.get() || stride & (BYTES_PER_PIXEL - 1) != 0 || stride > i32::MAX as usize
+    {
+        return None;
+    }
+
+    compute_data_len(size, stride)
+}
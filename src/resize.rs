@@ -0,0 +1,181 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use tiny_skia_path::IntSize;
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use tiny_skia_path::NoStdFloat;
+
+use crate::wide::f32x4;
+use crate::{Pixmap, PixmapRef};
+
+/// A filter used for resizing images.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ResizeFilter {
+    /// A box filter.
+    ///
+    /// Averages all source pixels covered by the destination one.
+    /// Fast and well suited for downscaling by integer factors,
+    /// but behaves like nearest-neighbor when upscaling.
+    Box,
+    /// A Mitchell-Netravali cubic filter with B = C = 1/3.
+    ///
+    /// A good balance between sharpness and ringing.
+    Mitchell,
+    /// A Lanczos filter with three lobes.
+    ///
+    /// The sharpest one, but can produce ringing near hard edges.
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    fn support(self) -> f32 {
+        match self {
+            ResizeFilter::Box => 0.5,
+            ResizeFilter::Mitchell => 2.0,
+            ResizeFilter::Lanczos3 => 3.0,
+        }
+    }
+
+    fn weight(self, x: f32) -> f32 {
+        match self {
+            ResizeFilter::Box => {
+                if (-0.5..0.5).contains(&x) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            ResizeFilter::Mitchell => {
+                let x = x.abs();
+                if x < 1.0 {
+                    (7.0 * x * x * x - 12.0 * x * x + 16.0 / 3.0) / 6.0
+                } else if x < 2.0 {
+                    (-7.0 / 3.0 * x * x * x + 12.0 * x * x - 20.0 * x + 32.0 / 3.0) / 6.0
+                } else {
+                    0.0
+                }
+            }
+            ResizeFilter::Lanczos3 => {
+                if x.abs() < 3.0 {
+                    sinc(x) * sinc(x / 3.0)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = x * core::f32::consts::PI;
+        x.sin() / x
+    }
+}
+
+impl Pixmap {
+    /// Returns a resized copy of the pixmap.
+    ///
+    /// See [`PixmapRef::resize`] for details.
+    pub fn resize(&self, width: u32, height: u32, filter: ResizeFilter) -> Option<Pixmap> {
+        self.as_ref().resize(width, height, filter)
+    }
+}
+
+impl PixmapRef<'_> {
+    /// Returns a resized copy of the pixmap.
+    ///
+    /// Unlike drawing a scaled pixmap, which samples only a few source pixels
+    /// per destination one, this function takes all covered source pixels into account
+    /// and doesn't alias even when downscaling by large factors.
+    ///
+    /// Pixels are filtered in premultiplied space and edges are clamped.
+    ///
+    /// Returns `None` when the new size is zero.
+    pub fn resize(&self, width: u32, height: u32, filter: ResizeFilter) -> Option<Pixmap> {
+        let size = IntSize::from_wh(width, height)?;
+        let (src_width, src_height) = (self.width() as usize, self.height() as usize);
+        let (dst_width, dst_height) = (size.width() as usize, size.height() as usize);
+
+        let src: Vec<f32x4> = self
+            .rows()
+            .flatten()
+            .map(|c| f32x4::from([c.red(), c.green(), c.blue(), c.alpha()].map(f32::from)))
+            .collect();
+
+        // Resize rows first and then columns.
+        let weights_x = contributions(src_width, dst_width, filter);
+        let mut tmp = Vec::with_capacity(dst_width * src_height);
+        for row in src.chunks(src_width) {
+            for c in &weights_x {
+                tmp.push(convolve(c, |i| row[i]));
+            }
+        }
+
+        let weights_y = contributions(src_height, dst_height, filter);
+        let mut pixmap = Pixmap::new(size.width(), size.height())?;
+        let mut pixels = pixmap.data_mut().chunks_exact_mut(4);
+        for c in &weights_y {
+            for x in 0..dst_width {
+                let v: [f32; 4] = convolve(c, |i| tmp[i * dst_width + x]).round().into();
+                // Lanczos and Mitchell filters can overshoot,
+                // so we have to make sure that the result is still premultiplied.
+                let a = v[3].clamp(0.0, 255.0);
+                let pixel = pixels.next()?;
+                for (p, c) in pixel.iter_mut().zip(&v[..3]) {
+                    *p = c.clamp(0.0, a) as u8;
+                }
+                pixel[3] = a as u8;
+            }
+        }
+
+        Some(pixmap)
+    }
+}
+
+// Source pixels contributing to a single destination one.
+struct Contribution {
+    start: usize,
+    weights: Vec<f32>,
+}
+
+fn contributions(src_len: usize, dst_len: usize, filter: ResizeFilter) -> Vec<Contribution> {
+    let scale = src_len as f32 / dst_len as f32;
+    // When downscaling, the filter is stretched to cover all source pixels.
+    let filter_scale = scale.max(1.0);
+    let support = filter.support() * filter_scale;
+
+    (0..dst_len)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * scale;
+            let start = (center - support).floor().max(0.0) as usize;
+            let end = ((center + support).ceil() as usize).min(src_len);
+            let mut weights: Vec<f32> = (start..end)
+                .map(|j| filter.weight((j as f32 + 0.5 - center) / filter_scale))
+                .collect();
+
+            // Pixels outside the image are skipped, so weights have to be renormalized.
+            let sum: f32 = weights.iter().sum();
+            if sum != 0.0 {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            }
+
+            Contribution { start, weights }
+        })
+        .collect()
+}
+
+fn convolve(c: &Contribution, sample: impl Fn(usize) -> f32x4) -> f32x4 {
+    let mut sum = f32x4::default();
+    for (i, w) in c.weights.iter().enumerate() {
+        sum += f32x4::splat(*w) * sample(c.start + i);
+    }
+    sum
+}
@@ -801,7 +801,7 @@ impl Blitter for RectClipBlitter<'_> {
         mut runs: &mut [AlphaRun],
     ) {
         const fn y_in_rect(y: u32, rect: ScreenIntRect) -> bool {
-            (y - rect.top()) < rect.height()
+            y.wrapping_sub(rect.top()) < rect.height()
         }
 
         if !y_in_rect(y, self.clip) || x >= self.clip.right() {
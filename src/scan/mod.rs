@@ -8,6 +8,7 @@ pub mod hairline;
 pub mod hairline_aa;
 pub mod path;
 pub mod path_aa;
+pub mod rrect;
 
 use crate::{IntRect, Rect};
 
@@ -0,0 +1,299 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+// Analytic rounded rectangle rasterization.
+//
+// Unlike the generic path rasterization, there is no need to build edges here.
+// Each row is described by the left and right rounded rectangle edges
+// and only pixels on those edges require coverage computation.
+
+use alloc::vec::Vec;
+use core::num::NonZeroU16;
+
+use tiny_skia_path::Scalar;
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use tiny_skia_path::NoStdFloat;
+
+use crate::{LengthU32, Point, RRect};
+
+use crate::alpha_runs::AlphaRun;
+use crate::blitter::Blitter;
+use crate::geom::ScreenIntRect;
+
+/// Fills a rounded rectangle, excluding an optional inner one.
+///
+/// `inner` must be inside `rrect`.
+pub fn fill_rrect(
+    rrect: &RRect,
+    inner: Option<&RRect>,
+    clip: &ScreenIntRect,
+    blitter: &mut dyn Blitter,
+) {
+    let Some((top, bottom)) = rows(rrect, clip, false) else {
+        return;
+    };
+
+    for y in top..bottom {
+        // Pixels with a center inside the shape are filled.
+        let py = y as f32 + 0.5;
+        let Some((left, right)) = span(rrect, py, clip) else {
+            continue;
+        };
+
+        match inner.and_then(|inner| span(inner, py, clip)) {
+            Some((inner_left, inner_right)) => {
+                blit_h(left, y, inner_left, blitter);
+                blit_h(inner_right, y, right, blitter);
+            }
+            None => blit_h(left, y, right, blitter),
+        }
+    }
+}
+
+/// Fills an anti-aliased rounded rectangle, excluding an optional inner one.
+///
+/// `inner` must be inside `rrect`.
+pub fn fill_rrect_aa(
+    rrect: &RRect,
+    inner: Option<&RRect>,
+    clip: &ScreenIntRect,
+    blitter: &mut dyn Blitter,
+) {
+    let Some((top, bottom)) = rows(rrect, clip, true) else {
+        return;
+    };
+
+    let mut coverage = Vec::new();
+    let mut alpha = Vec::new();
+    let mut runs: Vec<AlphaRun> = Vec::new();
+    for y in top..bottom {
+        let Some(outer) = RowSpan::new(rrect, y, clip) else {
+            continue;
+        };
+
+        coverage.clear();
+        coverage.resize((outer.right - outer.left) as usize, 0.0);
+        outer.accumulate(rrect, outer.left, &mut coverage, 1.0);
+
+        if let Some(inner) = inner {
+            if let Some(span) = RowSpan::new(inner, y, clip) {
+                span.accumulate(inner, outer.left, &mut coverage, -1.0);
+            }
+        }
+
+        alpha.clear();
+        alpha.extend(
+            coverage
+                .iter()
+                .map(|c| (c.bound(0.0, 1.0) * 255.0 + 0.5) as u8),
+        );
+
+        // Convert alpha values into runs.
+        runs.clear();
+        runs.resize(alpha.len() + 1, None);
+        let mut i = 0;
+        while i < alpha.len() {
+            let mut n = 1;
+            while i + n < alpha.len() && alpha[i + n] == alpha[i] && n < usize::from(u16::MAX) {
+                n += 1;
+            }
+
+            runs[i] = NonZeroU16::new(n as u16);
+            i += n;
+        }
+
+        blitter.blit_anti_h(outer.left, y, &mut alpha, &mut runs);
+    }
+}
+
+// Returns a range of rows affected by the rounded rectangle.
+fn rows(rrect: &RRect, clip: &ScreenIntRect, anti_alias: bool) -> Option<(u32, u32)> {
+    let rect = rrect.rect();
+    let (top, bottom) = if anti_alias {
+        (rect.top().floor(), rect.bottom().ceil())
+    } else {
+        ((rect.top() + 0.5).floor(), (rect.bottom() + 0.5).floor())
+    };
+
+    let top = top.max(clip.top() as f32);
+    let bottom = bottom.min(clip.bottom() as f32);
+    if bottom > top {
+        Some((top as u32, bottom as u32))
+    } else {
+        None
+    }
+}
+
+// Returns a range of pixels with a center inside the shape at the specified row.
+fn span(rrect: &RRect, py: f32, clip: &ScreenIntRect) -> Option<(u32, u32)> {
+    let rect = rrect.rect();
+    if !(py >= rect.top() && py < rect.bottom()) {
+        return None;
+    }
+
+    let left = (left_edge(rrect, py) + 0.5).floor().max(clip.left() as f32);
+    let right = (right_edge(rrect, py) + 0.5)
+        .floor()
+        .min(clip.right() as f32);
+    if right > left {
+        Some((left as u32, right as u32))
+    } else {
+        None
+    }
+}
+
+fn blit_h(left: u32, y: u32, right: u32, blitter: &mut dyn Blitter) {
+    if let Some(width) = right.checked_sub(left).and_then(LengthU32::new) {
+        blitter.blit_h(left, y, width);
+    }
+}
+
+// Pixels affected by the rounded rectangle in a single row.
+struct RowSpan {
+    y: u32,
+    // The covered part of the row.
+    y0: f32,
+    y1: f32,
+    left: u32,
+    right: u32,
+    // Fully covered pixels.
+    inner_left: u32,
+    inner_right: u32,
+}
+
+impl RowSpan {
+    fn new(rrect: &RRect, y: u32, clip: &ScreenIntRect) -> Option<Self> {
+        let rect = rrect.rect();
+        let [ul, ur, _, _] = rrect.radii();
+
+        let y0 = (y as f32).max(rect.top());
+        let y1 = (y as f32 + 1.0).min(rect.bottom());
+        if y1 <= y0 {
+            return None;
+        }
+
+        // Edges are the farthest from the center at corners ends
+        // and the closest at the row ends.
+        let left_min = left_edge(rrect, (rect.top() + ul.y).bound(y0, y1));
+        let left_max = left_edge(rrect, y0).max(left_edge(rrect, y1));
+        let right_max = right_edge(rrect, (rect.top() + ur.y).bound(y0, y1));
+        let right_min = right_edge(rrect, y0).min(right_edge(rrect, y1));
+
+        let left = left_min.floor().max(clip.left() as f32);
+        let right = right_max.ceil().min(clip.right() as f32);
+        if right <= left {
+            return None;
+        }
+
+        let left = left as u32;
+        let right = right as u32;
+
+        let (inner_left, inner_right) = if y1 - y0 == 1.0 {
+            let inner_left = left_max.ceil().bound(left as f32, right as f32) as u32;
+            let inner_right = right_min.floor().bound(inner_left as f32, right as f32) as u32;
+            (inner_left, inner_right)
+        } else {
+            (right, right)
+        };
+
+        Some(RowSpan {
+            y,
+            y0,
+            y1,
+            left,
+            right,
+            inner_left,
+            inner_right,
+        })
+    }
+
+    // Adds pixels coverage multiplied by `sign` to `coverage`, which starts at `x0`.
+    fn accumulate(&self, rrect: &RRect, x0: u32, coverage: &mut [f32], sign: f32) {
+        for x in self.left..self.right {
+            let c = if x >= self.inner_left && x < self.inner_right {
+                1.0
+            } else {
+                self.pixel_coverage(rrect, x)
+            };
+
+            coverage[(x - x0) as usize] += c * sign;
+        }
+    }
+
+    fn pixel_coverage(&self, rrect: &RRect, x: u32) -> f32 {
+        let rect = rrect.rect();
+        let x0 = (x as f32).max(rect.left());
+        let x1 = (x as f32 + 1.0).min(rect.right());
+        let rect_coverage = (x1 - x0).max(0.0) * (self.y1 - self.y0);
+
+        let p = Point::from_xy(x as f32 + 0.5, self.y as f32 + 0.5);
+        match corner_center(rrect, p) {
+            Some((center, radii)) => {
+                // An approximate signed distance to the ellipse.
+                let dx = p.x - center.x;
+                let dy = p.y - center.y;
+                let f = (dx * dx) / (radii.x * radii.x) + (dy * dy) / (radii.y * radii.y) - 1.0;
+                let gx = 2.0 * dx / (radii.x * radii.x);
+                let gy = 2.0 * dy / (radii.y * radii.y);
+                let grad = (gx * gx + gy * gy).sqrt();
+                let ellipse_coverage = if grad.is_nearly_zero() {
+                    1.0
+                } else {
+                    (0.5 - f / grad).bound(0.0, 1.0)
+                };
+
+                ellipse_coverage.min(rect_coverage)
+            }
+            None => rect_coverage,
+        }
+    }
+}
+
+// Returns the center and radii of the corner ellipse, when the point is in a corner.
+fn corner_center(rrect: &RRect, p: Point) -> Option<(Point, Point)> {
+    let r = rrect.rect();
+    let [ul, ur, lr, ll] = rrect.radii();
+
+    if p.x < r.left() + ul.x && p.y < r.top() + ul.y {
+        Some((Point::from_xy(r.left() + ul.x, r.top() + ul.y), ul))
+    } else if p.x > r.right() - ur.x && p.y < r.top() + ur.y {
+        Some((Point::from_xy(r.right() - ur.x, r.top() + ur.y), ur))
+    } else if p.x > r.right() - lr.x && p.y > r.bottom() - lr.y {
+        Some((Point::from_xy(r.right() - lr.x, r.bottom() - lr.y), lr))
+    } else if p.x < r.left() + ll.x && p.y > r.bottom() - ll.y {
+        Some((Point::from_xy(r.left() + ll.x, r.bottom() - ll.y), ll))
+    } else {
+        None
+    }
+}
+
+// Returns the left edge X at the specified Y inside the rounded rectangle.
+fn left_edge(rrect: &RRect, y: f32) -> f32 {
+    let rect = rrect.rect();
+    let [ul, _, _, ll] = rrect.radii();
+    rect.left() + corner_offset(rect.top(), rect.bottom(), ul, ll, y)
+}
+
+// Returns the right edge X at the specified Y inside the rounded rectangle.
+fn right_edge(rrect: &RRect, y: f32) -> f32 {
+    let rect = rrect.rect();
+    let [_, ur, lr, _] = rrect.radii();
+    rect.right() - corner_offset(rect.top(), rect.bottom(), ur, lr, y)
+}
+
+// Returns the horizontal distance between the rectangle and the rounded rectangle edges.
+fn corner_offset(top: f32, bottom: f32, top_radii: Point, bottom_radii: Point, y: f32) -> f32 {
+    let (radii, dy) = if y < top + top_radii.y {
+        (top_radii, top + top_radii.y - y)
+    } else if y > bottom - bottom_radii.y {
+        (bottom_radii, y - (bottom - bottom_radii.y))
+    } else {
+        return 0.0;
+    };
+
+    let t = dy / radii.y;
+    radii.x * (1.0 - (1.0 - t * t).max(0.0).sqrt())
+}
@@ -8,8 +8,10 @@ use alloc::vec::Vec;
 
 use tiny_skia_path::{NormalizedF32, Scalar};
 
-use crate::{Color, SpreadMode, Transform};
+use crate::{Color, PerspectiveTransform, SpreadMode, Transform};
 
+use crate::codec::{DecodeError, Reader, Writer};
+use crate::color::srgb_to_linear;
 use crate::pipeline::RasterPipelineBuilder;
 use crate::pipeline::{self, EvenlySpaced2StopGradientCtx, GradientColor, GradientCtx};
 
@@ -37,14 +39,37 @@ impl GradientStop {
     }
 }
 
+/// A color space in which gradient colors are interpolated.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum GradientInterpolation {
+    /// Interpolates unpremultiplied sRGB-encoded colors.
+    ///
+    /// Matches SVG and most 2D libraries.
+    #[default]
+    Srgb,
+
+    /// Interpolates unpremultiplied linear sRGB colors.
+    ///
+    /// Produces perceptually brighter transitions without dark bands between
+    /// saturated colors.
+    LinearSrgb,
+
+    /// Interpolates premultiplied linear sRGB colors.
+    ///
+    /// Unlike `LinearSrgb`, transitions into transparent colors do not pick up
+    /// the color of the transparent stop.
+    PremultipliedLinearSrgb,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Gradient {
     stops: Vec<GradientStop>,
     tile_mode: SpreadMode,
-    pub(crate) transform: Transform,
+    pub(crate) transform: PerspectiveTransform,
     points_to_unit: Transform,
     pub(crate) colors_are_opaque: bool,
     has_uniform_stops: bool,
+    pub(crate) interpolation: GradientInterpolation,
 }
 
 impl Gradient {
@@ -96,10 +121,11 @@ impl Gradient {
         Self {
             stops,
             tile_mode,
-            transform,
+            transform: transform.into(),
             points_to_unit,
             colors_are_opaque,
             has_uniform_stops,
+            interpolation: GradientInterpolation::default(),
         }
     }
 
@@ -115,8 +141,8 @@ impl Gradient {
             log::warn!("failed to invert a gradient transform. Nothing will be rendered");
             return false;
         };
-        ts = ts.post_concat(self.points_to_unit);
-        p.push_transform(ts);
+        ts = ts.post_concat(self.points_to_unit.into());
+        p.push_perspective(ts);
 
         push_stages_pre(p);
 
@@ -142,17 +168,12 @@ impl Gradient {
         if self.stops.len() == 2 {
             debug_assert!(self.has_uniform_stops);
 
-            let c0 = self.stops[0].color;
-            let c1 = self.stops[1].color;
+            let c0 = self.stop_color(self.stops[0].color);
+            let c1 = self.stop_color(self.stops[1].color);
 
             p.ctx.evenly_spaced_2_stop_gradient = EvenlySpaced2StopGradientCtx {
-                factor: GradientColor::new(
-                    c1.red() - c0.red(),
-                    c1.green() - c0.green(),
-                    c1.blue() - c0.blue(),
-                    c1.alpha() - c0.alpha(),
-                ),
-                bias: GradientColor::from(c0),
+                factor: GradientColor::new(c1.r - c0.r, c1.g - c0.g, c1.b - c0.b, c1.a - c0.a),
+                bias: c0,
             };
 
             p.push(pipeline::Stage::EvenlySpaced2StopGradient);
@@ -189,13 +210,13 @@ impl Gradient {
             };
 
             let mut t_l = self.stops[first_stop].position.get();
-            let mut c_l = GradientColor::from(self.stops[first_stop].color);
+            let mut c_l = self.stop_color(self.stops[first_stop].color);
             ctx.push_const_color(c_l);
             ctx.t_values.push(NormalizedF32::ZERO);
             // N.B. lastStop is the index of the last stop, not one after.
             for i in first_stop..last_stop {
                 let t_r = self.stops[i + 1].position.get();
-                let c_r = GradientColor::from(self.stops[i + 1].color);
+                let c_r = self.stop_color(self.stops[i + 1].color);
                 debug_assert!(t_l <= t_r);
                 if t_l < t_r {
                     // For each stop we calculate a bias B and a scale factor F, such that
@@ -241,15 +262,41 @@ impl Gradient {
             p.ctx.gradient = ctx;
         }
 
-        if !self.colors_are_opaque {
+        let is_premultiplied = self.interpolation == GradientInterpolation::PremultipliedLinearSrgb;
+        if !self.colors_are_opaque && !is_premultiplied {
             p.push(pipeline::Stage::Premultiply);
         }
 
+        // Shaders always produce sRGB-encoded colors.
+        if self.interpolation != GradientInterpolation::Srgb {
+            p.push(pipeline::Stage::LinearToSrgb);
+        }
+
         push_stages_post(p);
 
         true
     }
 
+    // Converts a stop color into the interpolation color space.
+    fn stop_color(&self, c: Color) -> GradientColor {
+        let a = c.alpha();
+        match self.interpolation {
+            GradientInterpolation::Srgb => GradientColor::from(c),
+            GradientInterpolation::LinearSrgb => GradientColor::new(
+                srgb_to_linear(c.red()),
+                srgb_to_linear(c.green()),
+                srgb_to_linear(c.blue()),
+                a,
+            ),
+            GradientInterpolation::PremultipliedLinearSrgb => GradientColor::new(
+                srgb_to_linear(c.red()) * a,
+                srgb_to_linear(c.green()) * a,
+                srgb_to_linear(c.blue()) * a,
+                a,
+            ),
+        }
+    }
+
     pub fn apply_opacity(&mut self, opacity: f32) {
         for stop in &mut self.stops {
             stop.color.apply_opacity(opacity);
@@ -257,4 +304,116 @@ impl Gradient {
 
         self.colors_are_opaque = self.stops.iter().all(|p| p.color.is_opaque());
     }
+
+    pub(crate) fn encode(&self, w: &mut Writer) {
+        w.write_len(self.stops.len());
+        for stop in &self.stops {
+            w.write_f32(stop.position.get());
+            w.write_color(stop.color);
+        }
+
+        w.write_u8(self.tile_mode as u8);
+        w.write_perspective_transform(self.transform);
+        w.write_transform(self.points_to_unit);
+        w.write_bool(self.has_uniform_stops);
+        w.write_u8(self.interpolation as u8);
+    }
+
+    // Stops are expected to be already normalized by `Gradient::new`.
+    pub(crate) fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        let len = r.read_len(20)?;
+        if len < 2 {
+            return Err(r.invalid());
+        }
+
+        let mut stops = Vec::with_capacity(len);
+        let mut prev = 0.0;
+        for _ in 0..len {
+            let position = r.read_normalized_f32()?;
+            let color = r.read_color()?;
+            if position.get() < prev {
+                return Err(r.invalid());
+            }
+
+            prev = position.get();
+            stops.push(GradientStop { position, color });
+        }
+
+        if stops[0].position.get() != 0.0 || stops[len - 1].position.get() != 1.0 {
+            return Err(r.invalid());
+        }
+
+        let tile_mode = r.read_spread_mode()?;
+        let transform = r.read_perspective_transform()?;
+        let points_to_unit = r.read_transform()?;
+        let has_uniform_stops = r.read_bool()?;
+        // The two stops case is always uniform.
+        if len == 2 && !has_uniform_stops {
+            return Err(r.invalid());
+        }
+
+        let interpolation = r.read_gradient_interpolation()?;
+
+        Ok(Self {
+            colors_are_opaque: stops.iter().all(|p| p.color.is_opaque()),
+            stops,
+            tile_mode,
+            transform,
+            points_to_unit,
+            has_uniform_stops,
+            interpolation,
+        })
+    }
+}
+
+pub fn average_gradient_color(points: &[GradientStop]) -> Color {
+    use crate::wide::f32x4;
+
+    fn load_color(c: Color) -> f32x4 {
+        f32x4::from([c.red(), c.green(), c.blue(), c.alpha()])
+    }
+
+    fn store_color(c: f32x4) -> Color {
+        let c: [f32; 4] = c.into();
+        Color::from_rgba(c[0], c[1], c[2], c[3]).unwrap()
+    }
+
+    assert!(!points.is_empty());
+
+    // The gradient is a piecewise linear interpolation between colors. For a given interval,
+    // the integral between the two endpoints is 0.5 * (ci + cj) * (pj - pi), which provides that
+    // intervals average color. The overall average color is thus the sum of each piece. The thing
+    // to keep in mind is that the provided gradient definition may implicitly use p=0 and p=1.
+    let mut blend = f32x4::splat(0.0);
+
+    // Bake 1/(colorCount - 1) uniform stop difference into this scale factor
+    let w_scale = f32x4::splat(0.5);
+
+    for i in 0..points.len() - 1 {
+        // Calculate the average color for the interval between pos(i) and pos(i+1)
+        let c0 = load_color(points[i].color);
+        let c1 = load_color(points[i + 1].color);
+        // when pos == null, there are colorCount uniformly distributed stops, going from 0 to 1,
+        // so pos[i + 1] - pos[i] = 1/(colorCount-1)
+        let w = points[i + 1].position.get() - points[i].position.get();
+        blend += w_scale * f32x4::splat(w) * (c1 + c0);
+    }
+
+    // Now account for any implicit intervals at the start or end of the stop definitions
+    if points[0].position.get() > 0.0 {
+        // The first color is fixed between p = 0 to pos[0], so 0.5 * (ci + cj) * (pj - pi)
+        // becomes 0.5 * (c + c) * (pj - 0) = c * pj
+        let c = load_color(points[0].color);
+        blend += f32x4::splat(points[0].position.get()) * c;
+    }
+
+    let last_idx = points.len() - 1;
+    if points[last_idx].position.get() < 1.0 {
+        // The last color is fixed between pos[n-1] to p = 1, so 0.5 * (ci + cj) * (pj - pi)
+        // becomes 0.5 * (c + c) * (1 - pi) = c * (1 - pi)
+        let c = load_color(points[last_idx].color);
+        blend += (f32x4::splat(1.0) - f32x4::splat(points[last_idx].position.get())) * c;
+    }
+
+    store_color(blend)
 }
@@ -8,9 +8,10 @@ use alloc::vec::Vec;
 
 use tiny_skia_path::Scalar;
 
-use crate::{Color, GradientStop, Point, Shader, SpreadMode, Transform};
+use crate::{GradientStop, Point, Shader, SpreadMode, Transform};
 
-use super::gradient::{Gradient, DEGENERATE_THRESHOLD};
+use super::gradient::{average_gradient_color, Gradient, DEGENERATE_THRESHOLD};
+use crate::codec::{DecodeError, Reader, Writer};
 use crate::pipeline::RasterPipelineBuilder;
 
 /// A linear gradient shader.
@@ -94,6 +95,16 @@ impl LinearGradient {
     pub(crate) fn push_stages(&self, p: &mut RasterPipelineBuilder) -> bool {
         self.base.push_stages(p, &|_| {}, &|_| {})
     }
+
+    pub(crate) fn encode(&self, w: &mut Writer) {
+        self.base.encode(w);
+    }
+
+    pub(crate) fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            base: Gradient::decode(r)?,
+        })
+    }
 }
 
 fn points_to_unit_ts(start: Point, end: Point) -> Option<Transform> {
@@ -109,58 +120,6 @@ fn points_to_unit_ts(start: Point, end: Point) -> Option<Transform> {
     Some(ts)
 }
 
-fn average_gradient_color(points: &[GradientStop]) -> Color {
-    use crate::wide::f32x4;
-
-    fn load_color(c: Color) -> f32x4 {
-        f32x4::from([c.red(), c.green(), c.blue(), c.alpha()])
-    }
-
-    fn store_color(c: f32x4) -> Color {
-        let c: [f32; 4] = c.into();
-        Color::from_rgba(c[0], c[1], c[2], c[3]).unwrap()
-    }
-
-    assert!(!points.is_empty());
-
-    // The gradient is a piecewise linear interpolation between colors. For a given interval,
-    // the integral between the two endpoints is 0.5 * (ci + cj) * (pj - pi), which provides that
-    // intervals average color. The overall average color is thus the sum of each piece. The thing
-    // to keep in mind is that the provided gradient definition may implicitly use p=0 and p=1.
-    let mut blend = f32x4::splat(0.0);
-
-    // Bake 1/(colorCount - 1) uniform stop difference into this scale factor
-    let w_scale = f32x4::splat(0.5);
-
-    for i in 0..points.len() - 1 {
-        // Calculate the average color for the interval between pos(i) and pos(i+1)
-        let c0 = load_color(points[i].color);
-        let c1 = load_color(points[i + 1].color);
-        // when pos == null, there are colorCount uniformly distributed stops, going from 0 to 1,
-        // so pos[i + 1] - pos[i] = 1/(colorCount-1)
-        let w = points[i + 1].position.get() - points[i].position.get();
-        blend += w_scale * f32x4::splat(w) * (c1 + c0);
-    }
-
-    // Now account for any implicit intervals at the start or end of the stop definitions
-    if points[0].position.get() > 0.0 {
-        // The first color is fixed between p = 0 to pos[0], so 0.5 * (ci + cj) * (pj - pi)
-        // becomes 0.5 * (c + c) * (pj - 0) = c * pj
-        let c = load_color(points[0].color);
-        blend += f32x4::splat(points[0].position.get()) * c;
-    }
-
-    let last_idx = points.len() - 1;
-    if points[last_idx].position.get() < 1.0 {
-        // The last color is fixed between pos[n-1] to p = 1, so 0.5 * (ci + cj) * (pj - pi)
-        // becomes 0.5 * (c + c) * (1 - pi) = c * (1 - pi)
-        let c = load_color(points[last_idx].color);
-        blend += (f32x4::splat(1.0) - f32x4::splat(points[last_idx].position.get())) * c;
-    }
-
-    store_color(blend)
-}
-
 fn ts_from_sin_cos_at(sin: f32, cos: f32, px: f32, py: f32) -> Transform {
     let cos_inv = 1.0 - cos;
     Transform::from_row(
@@ -8,15 +8,17 @@ mod gradient;
 mod linear_gradient;
 mod pattern;
 mod radial_gradient;
+mod sweep_gradient;
 
 use tiny_skia_path::{NormalizedF32, Scalar};
 
-pub use gradient::GradientStop;
+pub use gradient::{GradientInterpolation, GradientStop};
 pub use linear_gradient::LinearGradient;
-pub use pattern::{FilterQuality, Pattern, PixmapPaint};
+pub use pattern::{FilterQuality, Mipmaps, Pattern, PixmapPaint};
 pub use radial_gradient::RadialGradient;
+pub use sweep_gradient::SweepGradient;
 
-use crate::{Color, Transform};
+use crate::{Color, PerspectiveTransform, Transform};
 
 use crate::pipeline::RasterPipelineBuilder;
 
@@ -52,6 +54,8 @@ pub enum Shader<'a> {
     LinearGradient(LinearGradient),
     /// A radial gradient shader.
     RadialGradient(RadialGradient),
+    /// A sweep gradient shader.
+    SweepGradient(SweepGradient),
     /// A pattern shader.
     Pattern(Pattern<'a>),
 }
@@ -63,6 +67,7 @@ impl<'a> Shader<'a> {
             Shader::SolidColor(ref c) => c.is_opaque(),
             Shader::LinearGradient(ref g) => g.is_opaque(),
             Shader::RadialGradient(_) => false,
+            Shader::SweepGradient(ref g) => g.is_opaque(),
             Shader::Pattern(_) => false,
         }
     }
@@ -79,12 +84,32 @@ impl<'a> Shader<'a> {
             }
             Shader::LinearGradient(ref g) => g.push_stages(p),
             Shader::RadialGradient(ref g) => g.push_stages(p),
+            Shader::SweepGradient(ref g) => g.push_stages(p),
             Shader::Pattern(ref patt) => patt.push_stages(p),
         }
     }
 
+    /// Sets the color space in which gradient colors are interpolated.
+    ///
+    /// Has no effect on non-gradient shaders.
+    pub fn set_gradient_interpolation(&mut self, interpolation: GradientInterpolation) {
+        match self {
+            Shader::LinearGradient(g) => g.base.interpolation = interpolation,
+            Shader::RadialGradient(g) => g.base.interpolation = interpolation,
+            Shader::SweepGradient(g) => g.base.interpolation = interpolation,
+            Shader::SolidColor(_) | Shader::Pattern(_) => {}
+        }
+    }
+
     /// Transforms the shader.
     pub fn transform(&mut self, ts: Transform) {
+        self.transform_perspective(ts.into());
+    }
+
+    /// Transforms the shader using a perspective transform.
+    ///
+    /// Gradients and patterns will be sampled in a perspective-correct way.
+    pub fn transform_perspective(&mut self, ts: PerspectiveTransform) {
         match self {
             Shader::SolidColor(_) => {}
             Shader::LinearGradient(g) => {
@@ -93,6 +118,9 @@ impl<'a> Shader<'a> {
             Shader::RadialGradient(g) => {
                 g.base.transform = g.base.transform.post_concat(ts);
             }
+            Shader::SweepGradient(g) => {
+                g.base.transform = g.base.transform.post_concat(ts);
+            }
             Shader::Pattern(p) => {
                 p.transform = p.transform.post_concat(ts);
             }
@@ -123,6 +151,9 @@ impl<'a> Shader<'a> {
             Shader::RadialGradient(g) => {
                 g.base.apply_opacity(opacity);
             }
+            Shader::SweepGradient(g) => {
+                g.base.apply_opacity(opacity);
+            }
             Shader::Pattern(ref mut p) => {
                 p.opacity = NormalizedF32::new(p.opacity.get() * opacity.bound(0.0, 1.0)).unwrap();
             }
@@ -4,9 +4,14 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
-use tiny_skia_path::NormalizedF32;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
 
-use crate::{BlendMode, PixmapRef, Shader, SpreadMode, Transform};
+use tiny_skia_path::{IntRect, NormalizedF32};
+
+use crate::{BlendMode, ColorFilter, PerspectiveTransform, Pixmap, PixmapRef, Shader};
+use crate::{ColorSpace, ResizeFilter, SpreadMode, Transform};
 
 use crate::pipeline;
 use crate::pipeline::RasterPipelineBuilder;
@@ -23,12 +28,22 @@ pub enum FilterQuality {
     Bilinear,
     /// Bicubic. High quality, but slow.
     Bicubic,
+    /// Bilinear with mipmaps.
+    ///
+    /// When downscaling, samples the two closest mipmap levels
+    /// and interpolates between them, which prevents aliasing.
+    /// Otherwise, identical to `Bilinear`.
+    ///
+    /// Mipmaps are generated when a `Pattern` is created,
+    /// which makes it noticeably more expensive.
+    /// Use [`Mipmaps`] with [`Pattern::new_with_mipmaps`] to generate them only once.
+    Trilinear,
 }
 
 /// Controls how a pixmap should be blended.
 ///
 /// Like `Paint`, but for `Pixmap`.
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct PixmapPaint {
     /// Pixmap opacity.
     ///
@@ -46,6 +61,25 @@ pub struct PixmapPaint {
     ///
     /// Default: Nearest
     pub quality: FilterQuality,
+
+    /// A color filter applied to the pixmap colors before blending.
+    ///
+    /// Default: None
+    pub color_filter: Option<ColorFilter>,
+
+    /// A working color space used for blending.
+    ///
+    /// See [`Paint::color_space`](crate::Paint::color_space) for details.
+    ///
+    /// Default: Srgb
+    pub color_space: ColorSpace,
+
+    /// Renders using multiple threads.
+    ///
+    /// See [`Paint::parallel`](crate::Paint::parallel) for details.
+    ///
+    /// Default: false
+    pub parallel: bool,
 }
 
 impl Default for PixmapPaint {
@@ -54,6 +88,9 @@ impl Default for PixmapPaint {
             opacity: 1.0,
             blend_mode: BlendMode::default(),
             quality: FilterQuality::Nearest,
+            color_filter: None,
+            color_space: ColorSpace::Srgb,
+            parallel: false,
         }
     }
 }
@@ -61,16 +98,14 @@ impl Default for PixmapPaint {
 /// A pattern shader.
 ///
 /// Essentially a `SkImageShader`.
-///
-/// Unlike Skia, we do not support `FilterQuality::Medium`, because it involves
-/// mipmap generation, which adds too much complexity.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Pattern<'a> {
     pub(crate) pixmap: PixmapRef<'a>,
-    quality: FilterQuality,
-    spread_mode: SpreadMode,
+    pub(crate) mipmaps: Option<Mipmaps>,
+    pub(crate) quality: FilterQuality,
+    pub(crate) spread_mode: SpreadMode,
     pub(crate) opacity: NormalizedF32,
-    pub(crate) transform: Transform,
+    pub(crate) transform: PerspectiveTransform,
 }
 
 impl<'a> Pattern<'a> {
@@ -85,13 +120,78 @@ impl<'a> Pattern<'a> {
         opacity: f32,
         transform: Transform,
     ) -> Shader {
-        Shader::Pattern(Pattern {
+        Shader::Pattern(Pattern::new_perspective(
             pixmap,
             spread_mode,
             quality,
-            opacity: NormalizedF32::new_clamped(opacity),
+            NormalizedF32::new_clamped(opacity),
+            transform.into(),
+        ))
+    }
+
+    /// Creates a new pattern shader with [`FilterQuality::Trilinear`] quality
+    /// using prebuilt mipmaps.
+    ///
+    /// `opacity` will be clamped to the 0..=1 range.
+    pub fn new_with_mipmaps(
+        mipmaps: &'a Mipmaps,
+        spread_mode: SpreadMode,
+        opacity: f32,
+        transform: Transform,
+    ) -> Shader<'a> {
+        Shader::Pattern(Pattern::with_mipmaps(
+            mipmaps,
+            spread_mode,
+            NormalizedF32::new_clamped(opacity),
+            transform.into(),
+        ))
+    }
+
+    pub(crate) fn with_mipmaps(
+        mipmaps: &'a Mipmaps,
+        spread_mode: SpreadMode,
+        opacity: NormalizedF32,
+        transform: PerspectiveTransform,
+    ) -> Self {
+        Pattern {
+            pixmap: mipmaps.pixmap(),
+            mipmaps: Some(mipmaps.clone()),
+            spread_mode,
+            quality: FilterQuality::Trilinear,
+            opacity,
+            transform,
+        }
+    }
+
+    pub(crate) fn new_perspective(
+        pixmap: PixmapRef<'a>,
+        spread_mode: SpreadMode,
+        quality: FilterQuality,
+        opacity: NormalizedF32,
+        transform: PerspectiveTransform,
+    ) -> Self {
+        let mipmaps = if quality == FilterQuality::Trilinear {
+            Mipmaps::new(pixmap)
+        } else {
+            None
+        };
+
+        Pattern {
+            pixmap,
+            mipmaps,
+            spread_mode,
+            quality,
+            opacity,
             transform,
-        })
+        }
+    }
+
+    /// Returns a pixmap that should be used by the pipeline.
+    pub(crate) fn source(&self) -> PixmapRef<'_> {
+        match self.mipmaps {
+            Some(ref mipmaps) => mipmaps.0.atlas.as_ref(),
+            None => self.pixmap,
+        }
     }
 
     pub(crate) fn push_stages(&self, p: &mut RasterPipelineBuilder) -> bool {
@@ -102,25 +202,29 @@ impl<'a> Pattern<'a> {
 
         p.push(pipeline::Stage::SeedShader);
 
-        p.push_transform(ts);
+        p.push_perspective(ts);
 
-        let mut quality = if ts.is_identity() || ts.is_translate() {
-            FilterQuality::Nearest
-        } else {
-            self.quality
-        };
+        let mut quality = self.quality;
+        if quality == FilterQuality::Trilinear && self.mipmaps.is_none() {
+            quality = FilterQuality::Bilinear;
+        }
 
-        if quality == FilterQuality::Bilinear {
-            if ts.is_translate() {
-                if ts.tx == ts.tx.trunc() && ts.ty == ts.ty.trunc() {
-                    // When the matrix is just an integer translate, bilerp == nearest neighbor.
-                    quality = FilterQuality::Nearest;
+        if let Some(ts) = ts.to_affine() {
+            // With mipmaps, the source pixmap is an atlas that only `Trilinear` can sample.
+            if quality != FilterQuality::Trilinear && (ts.is_identity() || ts.is_translate()) {
+                quality = FilterQuality::Nearest;
+            }
+
+            if quality == FilterQuality::Bilinear {
+                if ts.is_translate() {
+                    if ts.tx == ts.tx.trunc() && ts.ty == ts.ty.trunc() {
+                        // When the matrix is just an integer translate, bilerp == nearest neighbor.
+                        quality = FilterQuality::Nearest;
+                    }
                 }
             }
         }
 
-        // TODO: minimizing scale via mipmap
-
         match quality {
             FilterQuality::Nearest => {
                 p.ctx.limit_x = pipeline::TileCtx {
@@ -161,6 +265,16 @@ impl<'a> Pattern<'a> {
                 p.push(pipeline::Stage::Clamp0);
                 p.push(pipeline::Stage::ClampA);
             }
+            FilterQuality::Trilinear => {
+                if let Some(ref mipmaps) = self.mipmaps {
+                    // With perspective, the level is chosen using only the affine part.
+                    let det = (ts.sx * ts.sy - ts.kx * ts.ky).abs();
+                    // Mipmaps are only used for minification.
+                    let level = (0.5 * det.log2()).max(0.0);
+                    p.ctx.mipmap = mipmaps.push_levels(level, self.spread_mode);
+                    p.push(pipeline::Stage::Trilinear);
+                }
+            }
         }
 
         // Unlike Skia, we do not support global opacity and only Pattern allows it.
@@ -177,3 +291,109 @@ impl<'a> Pattern<'a> {
         true
     }
 }
+
+/// Pixmap mipmaps for [`FilterQuality::Trilinear`] sampling.
+///
+/// Generating mipmaps is expensive, therefore when the same pixmap is drawn multiple times,
+/// they should be created once and passed to [`Pattern::new_with_mipmaps`].
+///
+/// Cloning is cheap, since the data is shared.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Mipmaps(Arc<MipmapAtlas>);
+
+// All levels packed into a single pixmap.
+//
+// The base level is at the top, while all the smaller ones are placed
+// in a single row below it.
+#[derive(PartialEq, Debug)]
+struct MipmapAtlas {
+    atlas: Pixmap,
+    levels: Vec<IntRect>,
+}
+
+impl Mipmaps {
+    /// Generates mipmaps for a pixmap.
+    ///
+    /// The pixmap is copied.
+    pub fn new(pixmap: PixmapRef) -> Option<Self> {
+        let mut pixmaps = vec![pixmap.to_owned()];
+        loop {
+            let prev = pixmaps.last()?;
+            if prev.width() == 1 && prev.height() == 1 {
+                break;
+            }
+
+            let width = (prev.width() / 2).max(1);
+            let height = (prev.height() / 2).max(1);
+            let level = prev.resize(width, height, ResizeFilter::Box)?;
+            pixmaps.push(level);
+        }
+
+        let mut levels = Vec::with_capacity(pixmaps.len());
+        levels.push(pixmaps[0].size().to_int_rect(0, 0));
+        let mut x = 0;
+        for level in &pixmaps[1..] {
+            levels.push(level.size().to_int_rect(x, pixmap.height() as i32));
+            x += level.width() as i32;
+        }
+
+        let width = pixmap.width().max(x as u32);
+        let height = pixmap.height() + pixmaps.get(1).map_or(0, |p| p.height());
+        let mut atlas = Pixmap::new(width, height)?;
+        for (level, rect) in pixmaps.iter().zip(&levels) {
+            atlas.draw_pixmap(
+                rect.x(),
+                rect.y(),
+                level.as_ref(),
+                &PixmapPaint {
+                    blend_mode: BlendMode::Source,
+                    ..PixmapPaint::default()
+                },
+                Transform::identity(),
+                None,
+            );
+        }
+
+        Some(Mipmaps(Arc::new(MipmapAtlas { atlas, levels })))
+    }
+
+    /// Returns the base level, which is a copy of the original pixmap.
+    pub fn pixmap(&self) -> PixmapRef<'_> {
+        let atlas = self.0.atlas.as_ref();
+        let base = self.0.levels[0];
+        // The base level is in the top-left corner, therefore it's always valid.
+        PixmapRef::from_bytes_with_stride(atlas.data(), base.width(), base.height(), atlas.stride())
+            .unwrap()
+    }
+
+    fn push_levels(&self, level: f32, spread_mode: SpreadMode) -> pipeline::MipmapCtx {
+        let levels = &self.0.levels;
+        let last = levels.len() - 1;
+        let lower = (level.floor() as usize).min(last);
+        let t = if lower == last {
+            0.0
+        } else {
+            level - level.floor()
+        };
+        let base = levels[0];
+
+        let level_ctx = |index: usize| {
+            let rect = levels[index.min(last)];
+            pipeline::MipmapLevelCtx {
+                rect: Some(rect),
+                scale_x: rect.width() as f32 / base.width() as f32,
+                scale_y: rect.height() as f32 / base.height() as f32,
+                sampler: pipeline::SamplerCtx {
+                    spread_mode,
+                    inv_width: 1.0 / rect.width() as f32,
+                    inv_height: 1.0 / rect.height() as f32,
+                },
+            }
+        };
+
+        pipeline::MipmapCtx {
+            levels: [level_ctx(lower), level_ctx(lower + 1)],
+            t,
+        }
+    }
+}
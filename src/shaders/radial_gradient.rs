@@ -4,13 +4,15 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
+use alloc::vec;
 use alloc::vec::Vec;
 
 use tiny_skia_path::Scalar;
 
 use crate::{GradientStop, Point, Shader, SpreadMode, Transform};
 
-use super::gradient::{Gradient, DEGENERATE_THRESHOLD};
+use super::gradient::{average_gradient_color, Gradient, DEGENERATE_THRESHOLD};
+use crate::codec::{DecodeError, Reader, Writer};
 use crate::pipeline;
 use crate::pipeline::RasterPipelineBuilder;
 use crate::wide::u32x8;
@@ -20,7 +22,9 @@ use tiny_skia_path::NoStdFloat;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 struct FocalData {
-    r1: f32, // r1 after mapping focal point to (0, 0)
+    r1: f32,      // r1 after mapping focal point to (0, 0)
+    focal_x: f32, // f
+    is_swapped: bool,
 }
 
 impl FocalData {
@@ -37,21 +41,82 @@ impl FocalData {
     fn is_well_behaved(self) -> bool {
         !self.is_focal_on_circle() && self.r1 > 1.0
     }
+
+    fn is_natively_focal(self) -> bool {
+        self.focal_x.is_nearly_zero()
+    }
+
+    // Expects `r0` and `r1` to be already divided by the distance between centers.
+    //
+    // Adjusts `ts` so the focal point is mapped to (0, 0) and the end center to (1, 0).
+    fn new(mut r0: f32, mut r1: f32, ts: &mut Transform) -> Option<Self> {
+        let mut is_swapped = false;
+        let mut focal_x = r0 / (r0 - r1);
+        if (focal_x - 1.0).is_nearly_zero() {
+            // Swap r0 and r1.
+            *ts = ts.post_translate(-1.0, 0.0).post_scale(-1.0, 1.0);
+            core::mem::swap(&mut r0, &mut r1);
+            focal_x = 0.0; // because r0 is now 0
+            is_swapped = true;
+        }
+
+        // Map {focal point, (1, 0)} to {(0, 0), (1, 0)}.
+        let focal_ts = ts_from_poly_to_poly(
+            Point::from_xy(focal_x, 0.0),
+            Point::from_xy(1.0, 0.0),
+            Point::from_xy(0.0, 0.0),
+            Point::from_xy(1.0, 0.0),
+        )?;
+        *ts = ts.post_concat(focal_ts);
+
+        let focal_data = FocalData {
+            r1: r1 / (1.0 - focal_x).abs(), // focal_ts has a scale of 1/(1-f)
+            focal_x,
+            is_swapped,
+        };
+
+        // The following transformations are just to accelerate the shader computation by saving
+        // some arithmetic operations.
+        if focal_data.is_focal_on_circle() {
+            *ts = ts.post_scale(0.5, 0.5);
+        } else {
+            let r1 = focal_data.r1;
+            *ts = ts.post_scale(r1 / (r1 * r1 - 1.0), 1.0 / ((r1 * r1 - 1.0).abs()).sqrt());
+        }
+
+        Some(focal_data)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum GradientType {
+    /// Both circles share the same center.
+    ///
+    /// `bias` is the start radius offset, in units of the radii difference.
+    Radial { bias: f32, is_swapped: bool },
+    /// Both circles have the same radius.
+    ///
+    /// `r0` is the radius squared, in units of the distance between centers.
+    Strip { r0: f32 },
+    /// Everything else.
+    Focal(FocalData),
 }
 
 /// A radial gradient shader.
 ///
-/// This is not `SkRadialGradient` like in Skia, but rather `SkTwoPointConicalGradient`
-/// without the start radius.
+/// This is not `SkRadialGradient` like in Skia, but rather `SkTwoPointConicalGradient`.
 #[derive(Clone, PartialEq, Debug)]
 pub struct RadialGradient {
     pub(crate) base: Gradient,
-    focal_data: Option<FocalData>,
+    kind: GradientType,
 }
 
 impl RadialGradient {
     /// Creates a new radial gradient shader.
     ///
+    /// The gradient starts at the `start` point and ends at a circle centered at `end`
+    /// with a specified `radius`. Use [`RadialGradient::new_two_point`] to set the start radius.
+    ///
     /// Returns `Shader::SolidColor` when:
     /// - `stops.len()` == 1
     ///
@@ -68,10 +133,41 @@ impl RadialGradient {
         stops: Vec<GradientStop>,
         mode: SpreadMode,
         transform: Transform,
+    ) -> Option<Shader<'static>> {
+        if radius < 0.0 || radius.is_nearly_zero() {
+            return None;
+        }
+
+        Self::new_two_point(start, 0.0, end, radius, stops, mode, transform)
+    }
+
+    /// Creates a new two-point conical gradient shader.
+    ///
+    /// The gradient is defined by a start circle and an end circle,
+    /// like in CSS `radial-gradient` or SVG `radialGradient` with `fr`.
+    ///
+    /// Returns `Shader::SolidColor` when:
+    /// - `stops.len()` == 1
+    /// - both circles are the same and either `mode` is not `Pad` or the radius is zero
+    ///
+    /// Returns `None` when:
+    ///
+    /// - `stops` is empty
+    /// - `start_radius` < 0 or `end_radius` < 0
+    /// - `transform` is not invertible
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new_two_point(
+        start: Point,
+        start_radius: f32,
+        end: Point,
+        end_radius: f32,
+        stops: Vec<GradientStop>,
+        mode: SpreadMode,
+        transform: Transform,
     ) -> Option<Shader<'static>> {
         // From SkGradientShader::MakeTwoPointConical
 
-        if radius < 0.0 || radius.is_nearly_zero() {
+        if !(start_radius >= 0.0 && end_radius >= 0.0) {
             return None;
         }
 
@@ -96,15 +192,55 @@ impl RadialGradient {
             // an actual radial gradient (startRadius == 0),
             // or it is fully degenerate (startRadius == endRadius).
 
-            let inv = radius.invert();
+            if (start_radius - end_radius).is_nearly_zero_within_tolerance(DEGENERATE_THRESHOLD) {
+                // Degenerate case, where the interpolation region area approaches zero.
+                // The proper behavior depends on the spread mode, which is consistent with
+                // the default degenerate gradient behavior, except when mode = Pad and
+                // the radii > DEGENERATE_THRESHOLD.
+                if mode == SpreadMode::Pad && end_radius > DEGENERATE_THRESHOLD {
+                    // The interpolation region becomes an infinitely thin ring at the radius,
+                    // so the final gradient will be the first color repeated from t=0 to 1,
+                    // and then a hard stop switching to the last color at t=1.
+                    let first = stops[0].color;
+                    let last = stops[stops.len() - 1].color;
+                    let stops = vec![
+                        GradientStop::new(0.0, first),
+                        GradientStop::new(1.0, first),
+                        GradientStop::new(1.0, last),
+                    ];
+                    return Self::new_two_point(
+                        start, 0.0, start, end_radius, stops, mode, transform,
+                    );
+                }
+
+                return match mode {
+                    SpreadMode::Pad => Some(Shader::SolidColor(stops[stops.len() - 1].color)),
+                    SpreadMode::Reflect | SpreadMode::Repeat => {
+                        Some(Shader::SolidColor(average_gradient_color(&stops)))
+                    }
+                };
+            }
+
+            // From SkTwoPointConicalGradient::Create
+            //
+            // Unlike Skia, we map the radii difference to 1 and then
+            // offset t by the start radius, instead of scaling t afterwards.
+            let d_radius = end_radius - start_radius;
+            let inv = d_radius.abs().invert();
             let mut ts = Transform::from_translate(-start.x, -start.y);
             ts = ts.post_scale(inv, inv);
 
-            // We can treat this gradient as radial, which is faster. If we got here, we know
-            // that endRadius is not equal to 0, so this produces a meaningful gradient
+            // When the start radius is bigger, we compute t for the swapped circles.
+            let is_swapped = d_radius < 0.0;
+            let bias = if is_swapped {
+                -end_radius * inv
+            } else {
+                -start_radius * inv
+            };
+
             Some(Shader::RadialGradient(Self {
                 base: Gradient::new(stops, mode, transform, ts),
-                focal_data: None,
+                kind: GradientType::Radial { bias, is_swapped },
             }))
         } else {
             // From SkTwoPointConicalGradient::Create
@@ -116,42 +252,63 @@ impl RadialGradient {
             )?;
 
             let d_center = (start - end).length();
-            let r1 = radius / d_center;
-            let focal_data = FocalData { r1 };
-
-            // The following transformations are just to accelerate the shader computation by saving
-            // some arithmetic operations.
-            if focal_data.is_focal_on_circle() {
-                ts = ts.post_scale(0.5, 0.5);
+            let kind = if (end_radius - start_radius).is_nearly_zero() {
+                let scaled_r0 = start_radius / d_center;
+                GradientType::Strip {
+                    r0: scaled_r0 * scaled_r0,
+                }
             } else {
-                ts = ts.post_scale(r1 / (r1 * r1 - 1.0), 1.0 / ((r1 * r1 - 1.0).abs()).sqrt());
-            }
+                let focal_data =
+                    FocalData::new(start_radius / d_center, end_radius / d_center, &mut ts)?;
+                GradientType::Focal(focal_data)
+            };
 
             Some(Shader::RadialGradient(Self {
                 base: Gradient::new(stops, mode, transform, ts),
-                focal_data: Some(focal_data),
+                kind,
             }))
         }
     }
 
     pub(crate) fn push_stages(&self, p: &mut RasterPipelineBuilder) -> bool {
-        let p0 = self.focal_data.map_or(1.0, |focal_data| 1.0 / focal_data.r1);
+        let (p0, p1) = match self.kind {
+            GradientType::Radial { bias, .. } => (1.0, bias),
+            GradientType::Strip { r0 } => (r0, 0.0),
+            GradientType::Focal(focal_data) => (1.0 / focal_data.r1, focal_data.focal_x),
+        };
 
         p.ctx.two_point_conical_gradient = pipeline::TwoPointConicalGradientCtx {
             mask: u32x8::default(),
             p0,
+            p1,
         };
 
         self.base.push_stages(
             p,
-            &|p| {
-                if let Some(focal_data) = self.focal_data {
-                    // Unlike Skia, we have only the Focal radial gradient type.
+            &|p| match self.kind {
+                GradientType::Radial { bias, is_swapped } => {
+                    p.push(pipeline::Stage::XYToRadius);
+
+                    // Offset t by the start radius.
+                    if bias != 0.0 {
+                        p.push(pipeline::Stage::Alter2PtConicalCompensateFocal);
+                    }
 
+                    if is_swapped {
+                        p.push(pipeline::Stage::Alter2PtConicalUnswap);
+                    }
+                }
+                GradientType::Strip { .. } => {
+                    p.push(pipeline::Stage::XYTo2PtConicalStrip);
+                    p.push(pipeline::Stage::Mask2PtConicalNan);
+                }
+                GradientType::Focal(focal_data) => {
                     if focal_data.is_focal_on_circle() {
                         p.push(pipeline::Stage::XYTo2PtConicalFocalOnCircle);
                     } else if focal_data.is_well_behaved() {
                         p.push(pipeline::Stage::XYTo2PtConicalWellBehaved);
+                    } else if focal_data.is_swapped || 1.0 - focal_data.focal_x < 0.0 {
+                        p.push(pipeline::Stage::XYTo2PtConicalSmaller);
                     } else {
                         p.push(pipeline::Stage::XYTo2PtConicalGreater);
                     }
@@ -159,12 +316,26 @@ impl RadialGradient {
                     if !focal_data.is_well_behaved() {
                         p.push(pipeline::Stage::Mask2PtConicalDegenerates);
                     }
-                } else {
-                    p.push(pipeline::Stage::XYToRadius);
+
+                    if 1.0 - focal_data.focal_x < 0.0 {
+                        p.push(pipeline::Stage::NegateX);
+                    }
+
+                    if !focal_data.is_natively_focal() {
+                        p.push(pipeline::Stage::Alter2PtConicalCompensateFocal);
+                    }
+
+                    if focal_data.is_swapped {
+                        p.push(pipeline::Stage::Alter2PtConicalUnswap);
+                    }
                 }
             },
-            &|p| {
-                if let Some(focal_data) = self.focal_data {
+            &|p| match self.kind {
+                GradientType::Radial { .. } => {}
+                GradientType::Strip { .. } => {
+                    p.push(pipeline::Stage::ApplyVectorMask);
+                }
+                GradientType::Focal(focal_data) => {
                     if !focal_data.is_well_behaved() {
                         p.push(pipeline::Stage::ApplyVectorMask);
                     }
@@ -172,6 +343,48 @@ impl RadialGradient {
             },
         )
     }
+
+    pub(crate) fn encode(&self, w: &mut Writer) {
+        self.base.encode(w);
+        match self.kind {
+            GradientType::Radial { bias, is_swapped } => {
+                w.write_u8(0);
+                w.write_f32(bias);
+                w.write_bool(is_swapped);
+            }
+            GradientType::Strip { r0 } => {
+                w.write_u8(1);
+                w.write_f32(r0);
+            }
+            GradientType::Focal(focal_data) => {
+                w.write_u8(2);
+                w.write_f32(focal_data.r1);
+                w.write_f32(focal_data.focal_x);
+                w.write_bool(focal_data.is_swapped);
+            }
+        }
+    }
+
+    pub(crate) fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        let base = Gradient::decode(r)?;
+        let kind = match r.read_u8()? {
+            0 => GradientType::Radial {
+                bias: r.read_finite_f32()?,
+                is_swapped: r.read_bool()?,
+            },
+            1 => GradientType::Strip {
+                r0: r.read_finite_f32()?,
+            },
+            2 => GradientType::Focal(FocalData {
+                r1: r.read_finite_f32()?,
+                focal_x: r.read_finite_f32()?,
+                is_swapped: r.read_bool()?,
+            }),
+            _ => return Err(r.invalid()),
+        };
+
+        Ok(Self { base, kind })
+    }
 }
 
 fn ts_from_poly_to_poly(src1: Point, src2: Point, dst1: Point, dst2: Point) -> Option<Transform> {
@@ -0,0 +1,149 @@
+// Copyright 2006 The Android Open Source Project
+// Copyright 2020 Yevhenii Reizner
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use tiny_skia_path::Scalar;
+
+use crate::{GradientStop, Point, Shader, SpreadMode, Transform};
+
+use super::gradient::{average_gradient_color, Gradient, DEGENERATE_THRESHOLD};
+use crate::codec::{DecodeError, Reader, Writer};
+use crate::pipeline;
+use crate::pipeline::RasterPipelineBuilder;
+
+/// A sweep (also known as conic or angular) gradient shader.
+///
+/// Essentially a `SkSweepGradient`.
+///
+/// Colors are distributed clockwise around the `center`, starting at `start_angle`
+/// and ending at `end_angle`. Angles are in degrees, where 0 is the positive X axis.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SweepGradient {
+    pub(crate) base: Gradient,
+    t0: f32,
+    t1: f32,
+}
+
+impl SweepGradient {
+    /// Creates a new sweep gradient shader.
+    ///
+    /// Returns `Shader::SolidColor` when:
+    /// - `stops.len()` == 1
+    /// - `start_angle` and `end_angle` are very close and `mode` is not `Pad`
+    ///
+    /// Returns `None` when:
+    ///
+    /// - `stops` is empty
+    /// - `start_angle` > `end_angle`
+    /// - any angle is not finite
+    /// - `transform` is not invertible
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        center: Point,
+        start_angle: f32,
+        end_angle: f32,
+        stops: Vec<GradientStop>,
+        mut mode: SpreadMode,
+        transform: Transform,
+    ) -> Option<Shader<'static>> {
+        // From SkGradientShader::MakeSweep
+
+        if !start_angle.is_finite() || !end_angle.is_finite() || start_angle > end_angle {
+            return None;
+        }
+
+        if stops.is_empty() {
+            return None;
+        }
+
+        if stops.len() == 1 {
+            return Some(Shader::SolidColor(stops[0].color));
+        }
+
+        transform.invert()?;
+
+        if (start_angle - end_angle).is_nearly_zero_within_tolerance(DEGENERATE_THRESHOLD) {
+            // Degenerate gradient, which should follow default degenerate behavior unless it is
+            // clamped and the angle is greater than 0.
+            if mode == SpreadMode::Pad && end_angle > DEGENERATE_THRESHOLD {
+                // In this case, the first color is repeated from 0 to the angle, then a hardstop
+                // switches to the last color (all other colors are compressed to the infinitely
+                // thin interpolation region).
+                let first = stops[0].color;
+                let last = stops[stops.len() - 1].color;
+                let stops = vec![
+                    GradientStop::new(0.0, first),
+                    GradientStop::new(1.0, first),
+                    GradientStop::new(1.0, last),
+                ];
+                return Self::new(center, 0.0, end_angle, stops, mode, transform);
+            }
+
+            match mode {
+                SpreadMode::Pad => {
+                    return Some(Shader::SolidColor(stops[stops.len() - 1].color));
+                }
+                SpreadMode::Reflect | SpreadMode::Repeat => {
+                    return Some(Shader::SolidColor(average_gradient_color(&stops)));
+                }
+            }
+        }
+
+        if start_angle <= 0.0 && end_angle >= 360.0 {
+            // If the t-range includes [0,1], then we can always use clamping (presumably faster).
+            mode = SpreadMode::Pad;
+        }
+
+        let t0 = start_angle / 360.0;
+        let t1 = end_angle / 360.0;
+
+        Some(Shader::SweepGradient(Self {
+            base: Gradient::new(
+                stops,
+                mode,
+                transform,
+                Transform::from_translate(-center.x, -center.y),
+            ),
+            t0,
+            t1,
+        }))
+    }
+
+    pub(crate) const fn is_opaque(&self) -> bool {
+        self.base.colors_are_opaque
+    }
+
+    pub(crate) fn push_stages(&self, p: &mut RasterPipelineBuilder) -> bool {
+        p.ctx.sweep_gradient = pipeline::SweepGradientCtx {
+            t_bias: -self.t0,
+            t_scale: 1.0 / (self.t1 - self.t0),
+        };
+
+        self.base.push_stages(
+            p,
+            &|p| {
+                p.push(pipeline::Stage::XYToUnitAngle);
+            },
+            &|_| {},
+        )
+    }
+
+    pub(crate) fn encode(&self, w: &mut Writer) {
+        self.base.encode(w);
+        w.write_f32(self.t0);
+        w.write_f32(self.t1);
+    }
+
+    pub(crate) fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            base: Gradient::decode(r)?,
+            t0: r.read_finite_f32()?,
+            t1: r.read_finite_f32()?,
+        })
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use alloc::vec::Vec;
+
+use tiny_skia_path::{IntRect, Path, Rect, Transform};
+
+#[cfg(all(not(feature = "std"), feature = "no-std-float"))]
+use tiny_skia_path::NoStdFloat;
+
+use crate::color::premultiply_u8;
+use crate::{BlurEdgeMode, Color, FillRule, Mask, Paint, Pixmap, PixmapMut, PixmapPaint};
+
+/// A path shadow.
+///
+/// Used by [`PixmapMut::fill_path_with_shadow`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Shadow {
+    /// Horizontal offset in pixels.
+    ///
+    /// Not affected by the path transform.
+    ///
+    /// Default: 0
+    pub offset_x: f32,
+
+    /// Vertical offset in pixels.
+    ///
+    /// Not affected by the path transform.
+    ///
+    /// Default: 0
+    pub offset_y: f32,
+
+    /// Blur standard deviation in pixels.
+    ///
+    /// Not affected by the path transform.
+    ///
+    /// Default: 0
+    pub sigma: f32,
+
+    /// Shadow color.
+    ///
+    /// Default: black
+    pub color: Color,
+
+    /// Draws an inner shadow instead of a drop one.
+    ///
+    /// Inner shadow is drawn on top of the path and is clipped by it.
+    ///
+    /// Default: false
+    pub inner: bool,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Shadow {
+            offset_x: 0.0,
+            offset_y: 0.0,
+            sigma: 0.0,
+            color: Color::BLACK,
+            inner: false,
+        }
+    }
+}
+
+impl Pixmap {
+    /// Draws a filled path with a shadow.
+    ///
+    /// See [`PixmapMut::fill_path_with_shadow`] for details.
+    pub fn fill_path_with_shadow(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: Transform,
+        shadow: &Shadow,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut()
+            .fill_path_with_shadow(path, paint, fill_rule, transform, shadow, mask);
+    }
+}
+
+impl PixmapMut<'_> {
+    /// Draws a filled path with a shadow.
+    ///
+    /// A drop shadow is drawn before the path and an inner one after.
+    ///
+    /// The shadow is rendered into a temporary `Mask`, which covers only
+    /// the affected region and not the whole pixmap.
+    /// `paint` anti-aliasing and blend mode are used for the shadow as well.
+    pub fn fill_path_with_shadow(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: Transform,
+        shadow: &Shadow,
+        mask: Option<&Mask>,
+    ) {
+        if !shadow.inner {
+            self.draw_shadow(path, paint, fill_rule, transform, shadow, mask);
+        }
+
+        self.fill_path(path, paint, fill_rule, transform, mask);
+
+        if shadow.inner {
+            self.draw_shadow(path, paint, fill_rule, transform, shadow, mask);
+        }
+    }
+
+    fn draw_shadow(
+        &mut self,
+        path: &Path,
+        paint: &Paint,
+        fill_rule: FillRule,
+        transform: Transform,
+        shadow: &Shadow,
+        mask: Option<&Mask>,
+    ) {
+        if !(shadow.offset_x.is_finite() && shadow.offset_y.is_finite()) {
+            log::warn!("shadow offset must be finite");
+            return;
+        }
+
+        if !(shadow.sigma >= 0.0 && shadow.sigma.is_finite()) {
+            log::warn!("shadow sigma must be a non-negative number");
+            return;
+        }
+
+        let Some(path_bounds) = path.bounds().transform(transform) else {
+            log::warn!("path transformation failed");
+            return;
+        };
+
+        // Blur affects at most 3 sigmas around the shape.
+        // One more pixel to account for anti-aliasing and rounding.
+        let pad = (shadow.sigma * 3.0).ceil() as i32 + 1;
+
+        // Everything outside of the padded pixmap cannot affect it.
+        let Some(max_rect) = self.size().to_int_rect(0, 0).make_outset(pad, pad) else {
+            return;
+        };
+
+        let Some(shadow_bounds) = Rect::from_ltrb(
+            path_bounds.left() + shadow.offset_x,
+            path_bounds.top() + shadow.offset_y,
+            path_bounds.right() + shadow.offset_x,
+            path_bounds.bottom() + shadow.offset_y,
+        ) else {
+            return;
+        };
+
+        let rect = if shadow.inner {
+            // Inner shadow is visible only inside the path,
+            // but the area around the offset path affects it as well.
+            let Some(rect) = union(path_bounds.round_out(), shadow_bounds.round_out()) else {
+                return;
+            };
+            rect.make_outset(pad, pad)
+        } else {
+            shadow_bounds
+                .round_out()
+                .and_then(|r| r.make_outset(pad, pad))
+        };
+
+        let Some(rect) = rect.and_then(|r| r.intersect(&max_rect)) else {
+            return; // outside the pixmap
+        };
+
+        let Some(mut coverage) = Mask::new(rect.width(), rect.height()) else {
+            return;
+        };
+
+        let ts = transform.post_translate(
+            shadow.offset_x - rect.x() as f32,
+            shadow.offset_y - rect.y() as f32,
+        );
+        coverage.fill_path(path, fill_rule, paint.anti_alias, ts);
+
+        if shadow.inner {
+            coverage.invert();
+            // The area outside of the rect is outside of the offset path, therefore
+            // fully covered. Extending the edges will produce the same result.
+            coverage.blur(shadow.sigma, shadow.sigma, BlurEdgeMode::Clamp);
+
+            let Some(mut shape) = Mask::new(rect.width(), rect.height()) else {
+                return;
+            };
+            let ts = transform.post_translate(-rect.x() as f32, -rect.y() as f32);
+            shape.fill_path(path, fill_rule, paint.anti_alias, ts);
+            coverage.intersect(&shape);
+        } else {
+            coverage.blur(shadow.sigma, shadow.sigma, BlurEdgeMode::Transparent);
+        }
+
+        let Some(shadow_pixmap) = colorize(&coverage, shadow.color) else {
+            return;
+        };
+
+        let paint = PixmapPaint {
+            blend_mode: paint.blend_mode,
+            ..PixmapPaint::default()
+        };
+
+        self.draw_pixmap(
+            rect.x(),
+            rect.y(),
+            shadow_pixmap.as_ref(),
+            &paint,
+            Transform::identity(),
+            mask,
+        );
+    }
+}
+
+fn union(a: Option<IntRect>, b: Option<IntRect>) -> Option<IntRect> {
+    let (a, b) = (a?, b?);
+    IntRect::from_ltrb(
+        a.left().min(b.left()),
+        a.top().min(b.top()),
+        a.right().max(b.right()),
+        a.bottom().max(b.bottom()),
+    )
+}
+
+// Creates a pixmap filled with `color` using `mask` as coverage.
+fn colorize(mask: &Mask, color: Color) -> Option<Pixmap> {
+    let c = color.premultiply().to_color_u8();
+    let mut data = Vec::with_capacity(mask.data().len() * 4);
+    for a in mask.data() {
+        data.extend_from_slice(&[
+            premultiply_u8(c.red(), *a),
+            premultiply_u8(c.green(), *a),
+            premultiply_u8(c.blue(), *a),
+            premultiply_u8(c.alpha(), *a),
+        ]);
+    }
+
+    Pixmap::from_vec(data, mask.size())
+}
@@ -0,0 +1,174 @@
+// Copyright 2023 the tiny-skia authors
+//
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use tiny_skia_path::{PathBuilder, Point, Scalar, Transform};
+
+use crate::pipeline::{VertexColorCtx, VertexColors};
+use crate::{BlendMode, Color, Mask, Paint, Pixmap, PixmapMut};
+
+impl Pixmap {
+    /// Draws a triangle mesh.
+    ///
+    /// See [`PixmapMut::draw_vertices`] for details.
+    pub fn draw_vertices(
+        &mut self,
+        positions: &[Point],
+        colors: Option<&[Color]>,
+        tex_coords: Option<&[Point]>,
+        indices: Option<&[u16]>,
+        blend_mode: BlendMode,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        self.as_mut().draw_vertices(
+            positions, colors, tex_coords, indices, blend_mode, paint, transform, mask,
+        );
+    }
+}
+
+impl PixmapMut<'_> {
+    /// Draws a triangle mesh.
+    ///
+    /// Every three `indices` form a triangle. Without indices, every three `positions` do.
+    /// Trailing indices that do not form a triangle are ignored.
+    ///
+    /// `colors` and `tex_coords`, when set, must have the same length as `positions`
+    /// and are interpolated across each triangle. Texture coordinates are in the `paint`
+    /// shader coordinates, which is usually a `Pattern`. Without them, the shader
+    /// is mapped using `positions` instead, just like when filling a path.
+    ///
+    /// `blend_mode` combines `colors` (destination) with the `paint` shader (source).
+    /// Use `BlendMode::Destination` to ignore the shader and `BlendMode::Modulate`
+    /// to tint it. Ignored without `colors`.
+    ///
+    /// Triangles are filled one by one, so when `paint.anti_alias` is set,
+    /// edges shared by adjacent triangles can produce visible seams.
+    ///
+    /// Similar to `SkCanvas::drawVertices` in the triangles mode.
+    pub fn draw_vertices(
+        &mut self,
+        positions: &[Point],
+        colors: Option<&[Color]>,
+        tex_coords: Option<&[Point]>,
+        indices: Option<&[u16]>,
+        blend_mode: BlendMode,
+        paint: &Paint,
+        transform: Transform,
+        mask: Option<&Mask>,
+    ) {
+        let count = positions.len();
+        let colors_count = colors.map(|c| c.len()).unwrap_or(count);
+        let tex_coords_count = tex_coords.map(|t| t.len()).unwrap_or(count);
+        if colors_count != count || tex_coords_count != count {
+            log::warn!("vertex colors and texture coordinates must match positions");
+            return;
+        }
+
+        // Without texture coordinates, the shader is mapped using positions.
+        let mut shader_paint = paint.clone();
+        if tex_coords.is_none() {
+            shader_paint.shader.transform(transform);
+        }
+
+        let mut triangle = [0; 3];
+        let mut index = 0;
+        let mut next_index = || -> Option<usize> {
+            let i = match indices {
+                Some(indices) => usize::from(*indices.get(index)?),
+                None if index < count => index,
+                None => return None,
+            };
+            index += 1;
+            Some(i)
+        };
+
+        loop {
+            for i in &mut triangle {
+                match next_index() {
+                    Some(idx) if idx < count => *i = idx,
+                    Some(_) => {
+                        log::warn!("vertex index is out of bounds");
+                        return;
+                    }
+                    None => return,
+                }
+            }
+
+            let mut points = triangle.map(|i| positions[i]);
+            transform.map_points(&mut points);
+
+            let Some(path) = triangle_path(points) else {
+                continue;
+            };
+
+            let mut paint = shader_paint.clone();
+            if let Some(tex_coords) = tex_coords {
+                let Some(ts) = texture_transform(triangle.map(|i| tex_coords[i]), points) else {
+                    continue;
+                };
+                paint.shader.transform(ts);
+            }
+
+            let vertex_colors = colors.map(|colors| VertexColors {
+                ctx: vertex_color_ctx(points, triangle.map(|i| colors[i])),
+                blend_mode,
+            });
+
+            self.fill_triangle(&path, &paint, vertex_colors, mask);
+        }
+    }
+}
+
+fn triangle_path(points: [Point; 3]) -> Option<tiny_skia_path::Path> {
+    // Skip degenerate triangles.
+    let area = (points[1] - points[0]).cross(points[2] - points[0]);
+    if area.is_nearly_zero() || !area.is_finite() {
+        return None;
+    }
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(points[0].x, points[0].y);
+    pb.line_to(points[1].x, points[1].y);
+    pb.line_to(points[2].x, points[2].y);
+    pb.close();
+    pb.finish()
+}
+
+// Returns a transform that maps texture coordinates onto the triangle.
+fn texture_transform(tex_coords: [Point; 3], points: [Point; 3]) -> Option<Transform> {
+    let basis = |p: [Point; 3]| {
+        let (u, v) = (p[1] - p[0], p[2] - p[0]);
+        Transform::from_row(u.x, u.y, v.x, v.y, p[0].x, p[0].y)
+    };
+
+    Some(basis(points).pre_concat(basis(tex_coords).invert()?))
+}
+
+// Solves a linear function of device coordinates for each premultiplied color channel,
+// which is the same as interpolating colors using barycentric coordinates.
+fn vertex_color_ctx(points: [Point; 3], colors: [Color; 3]) -> VertexColorCtx {
+    let [p0, p1, p2] = points;
+    let (d1, d2) = (p1 - p0, p2 - p0);
+    let det = d1.cross(d2);
+
+    let colors = colors.map(|c| {
+        let c = c.premultiply();
+        [c.red(), c.green(), c.blue(), c.alpha()]
+    });
+    let channel = |i: usize| {
+        let (c0, c1, c2) = (colors[0][i], colors[1][i], colors[2][i]);
+        let dx = ((c1 - c0) * d2.y - (c2 - c0) * d1.y) / det;
+        let dy = ((c2 - c0) * d1.x - (c1 - c0) * d2.x) / det;
+        [dx, dy, c0 - dx * p0.x - dy * p0.y]
+    };
+
+    VertexColorCtx {
+        r: channel(0),
+        g: channel(1),
+        b: channel(2),
+        a: channel(3),
+    }
+}
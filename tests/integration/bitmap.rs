@@ -0,0 +1,318 @@
+use tiny_skia::*;
+
+// Lossless color types.
+const COLOR_TYPES: [ColorType; 5] = [
+    ColorType::Rgba8,
+    ColorType::Bgra8,
+    ColorType::Rgba16,
+    ColorType::RgbaF16,
+    ColorType::RgbaF32,
+];
+
+fn paths() -> (Path, Path, Stroke) {
+    let circle = PathBuilder::from_circle(100.0, 100.0, 70.0).unwrap();
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(20.0, 180.0);
+    pb.cubic_to(60.0, 20.0, 140.0, 220.0, 180.0, 20.0);
+    let curve = pb.finish().unwrap();
+
+    (circle, curve, Stroke { width: 8.0, ..Stroke::default() })
+}
+
+// 8-bit rendering rounds after each stage, so allow a small difference.
+fn assert_close(pixmap: &Pixmap, expected: &Pixmap) {
+    for (a, b) in pixmap.pixels().iter().zip(expected.pixels()) {
+        assert!((a.red() as i32 - b.red() as i32).abs() <= 1);
+        assert!((a.green() as i32 - b.green() as i32).abs() <= 1);
+        assert!((a.blue() as i32 - b.blue() as i32).abs() <= 1);
+        assert!((a.alpha() as i32 - b.alpha() as i32).abs() <= 1);
+    }
+}
+
+fn gradient() -> Shader<'static> {
+    LinearGradient::new(
+        Point::from_xy(10.0, 10.0),
+        Point::from_xy(190.0, 190.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn new() {
+    assert_eq!(Bitmap::new(100, 50, ColorType::Rgba16).unwrap().data().len(), 100 * 50 * 8);
+    assert_eq!(Bitmap::new(100, 50, ColorType::RgbaF32).unwrap().data().len(), 100 * 50 * 16);
+    assert!(Bitmap::new(0, 50, ColorType::RgbaF16).is_none());
+    assert!(Bitmap::new(i32::MAX as u32 / 8, 1, ColorType::RgbaF32).is_none());
+}
+
+#[test]
+fn from_vec() {
+    let size = IntSize::from_wh(10, 10).unwrap();
+    assert!(Bitmap::from_vec(vec![0; 800], size, ColorType::RgbaF16).is_some());
+    assert!(Bitmap::from_vec(vec![0; 400], size, ColorType::RgbaF16).is_none());
+}
+
+#[test]
+fn pixmap_roundtrip() {
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 200.0, 200.0).unwrap(), &Paint { shader: gradient(), ..Paint::default() }, Transform::identity(), None);
+
+    for color_type in COLOR_TYPES {
+        let bitmap = Bitmap::from_pixmap(pixmap.as_ref(), color_type);
+        assert_eq!(bitmap.color_type(), color_type);
+        assert_eq!(bitmap.to_pixmap(), pixmap);
+    }
+}
+
+#[test]
+fn fill() {
+    for color_type in COLOR_TYPES {
+        let mut bitmap = Bitmap::new(10, 10, color_type).unwrap();
+        bitmap.fill(Color::from_rgba(0.5, 0.25, 1.0, 0.5).unwrap());
+
+        let c = bitmap.pixel(9, 9).unwrap();
+        assert!((c.red() - 0.25).abs() < 0.003);
+        assert!((c.green() - 0.125).abs() < 0.003);
+        assert!((c.blue() - 0.5).abs() < 0.003);
+        assert!((c.alpha() - 0.5).abs() < 0.003);
+        assert!(bitmap.pixel(10, 0).is_none());
+    }
+}
+
+#[test]
+fn f32_values() {
+    let mut bitmap = Bitmap::new(1, 1, ColorType::RgbaF32).unwrap();
+    bitmap.fill(Color::from_rgba(0.5, 0.25, 1.0, 0.5).unwrap());
+
+    let data: Vec<f32> = bitmap.data().chunks(4).map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]])).collect();
+    assert_eq!(data, vec![0.25, 0.125, 0.5, 0.5]);
+}
+
+// Rendering into any color type must match the 8-bit rendering after conversion.
+#[test]
+fn draw_matches_pixmap() {
+    let paint = Paint {
+        shader: gradient(),
+        force_hq_pipeline: true,
+        ..Paint::default()
+    };
+
+    let (circle, curve, stroke) = paths();
+
+    let mut expected = Pixmap::new(200, 200).unwrap();
+    expected.fill(Color::from_rgba8(30, 40, 50, 255));
+    expected.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), None);
+    expected.stroke_path(&curve, &paint, &stroke, Transform::identity(), None);
+
+    for color_type in COLOR_TYPES {
+        let mut bitmap = Bitmap::new(200, 200, color_type).unwrap();
+        bitmap.fill(Color::from_rgba8(30, 40, 50, 255));
+        bitmap.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), None);
+        bitmap.stroke_path(&curve, &paint, &stroke, Transform::identity(), None);
+
+        assert_close(&bitmap.to_pixmap(), &expected);
+    }
+}
+
+#[test]
+fn no_banding() {
+    // A gradient that spans only 2 values in 8-bit.
+    let paint = Paint {
+        shader: LinearGradient::new(
+            Point::from_xy(0.0, 0.0),
+            Point::from_xy(200.0, 0.0),
+            vec![
+                GradientStop::new(0.0, Color::from_rgba8(0, 0, 0, 255)),
+                GradientStop::new(1.0, Color::from_rgba8(1, 1, 1, 255)),
+            ],
+            SpreadMode::Pad,
+            Transform::identity(),
+        ).unwrap(),
+        ..Paint::default()
+    };
+
+    let rect = Rect::from_xywh(0.0, 0.0, 200.0, 1.0).unwrap();
+    for color_type in [ColorType::Rgba16, ColorType::RgbaF32] {
+        let mut bitmap = Bitmap::new(200, 1, color_type).unwrap();
+        bitmap.fill_rect(rect, &paint, Transform::identity(), None);
+
+        let mut prev = -1.0;
+        for x in 0..200 {
+            let c = bitmap.pixel(x, 0).unwrap().red();
+            assert!(c > prev);
+            prev = c;
+        }
+    }
+}
+
+#[test]
+fn draw_pixmap() {
+    let mut image = Pixmap::new(50, 50).unwrap();
+    image.fill(Color::from_rgba8(50, 127, 150, 200));
+
+    let paint = PixmapPaint {
+        opacity: 0.5,
+        ..PixmapPaint::default()
+    };
+
+    let mut bitmap = Bitmap::new(100, 100, ColorType::RgbaF16).unwrap();
+    bitmap.fill(Color::WHITE);
+    bitmap.draw_pixmap(20, 20, image.as_ref(), &paint, Transform::identity(), None);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill(Color::WHITE);
+    pixmap.draw_pixmap(20, 20, image.as_ref(), &paint, Transform::identity(), None);
+
+    assert_eq!(bitmap.to_pixmap(), pixmap);
+}
+
+#[test]
+fn apply_mask() {
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(&PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap(), FillRule::Winding, true, Transform::identity());
+
+    let mut bitmap = Bitmap::new(100, 100, ColorType::Rgba16).unwrap();
+    bitmap.fill(Color::from_rgba8(50, 127, 150, 200));
+    bitmap.apply_mask(&mask);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill(Color::from_rgba8(50, 127, 150, 200));
+    pixmap.apply_mask(&mask);
+
+    assert_close(&bitmap.to_pixmap(), &pixmap);
+}
+
+#[test]
+fn parallel() {
+    let paint = Paint {
+        shader: gradient(),
+        parallel: true,
+        ..Paint::default()
+    };
+
+    let path = PathBuilder::from_circle(200.0, 300.0, 180.0).unwrap();
+
+    let mut expected = Bitmap::new(400, 600, ColorType::RgbaF32).unwrap();
+    expected.fill_path(&path, &Paint { parallel: false, ..paint.clone() }, FillRule::Winding, Transform::identity(), None);
+
+    let mut bitmap = Bitmap::new(400, 600, ColorType::RgbaF32).unwrap();
+    bitmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    assert_eq!(bitmap, expected);
+}
+
+#[test]
+fn bitmap_mut_from_bytes() {
+    let mut data = vec![0; 10 * 10 * 2];
+    assert!(BitmapMut::from_bytes(&mut data, 10, 10, ColorType::Rgb565).is_some());
+    assert!(BitmapMut::from_bytes(&mut data, 10, 11, ColorType::Rgb565).is_none());
+    assert!(BitmapMut::from_bytes(&mut data, 0, 10, ColorType::A8).is_none());
+}
+
+#[test]
+fn bgra8_matches_rgba8() {
+    let paint = Paint {
+        shader: gradient(),
+        ..Paint::default()
+    };
+
+    let (circle, curve, stroke) = paths();
+
+    let mut expected = Pixmap::new(200, 200).unwrap();
+    expected.fill(Color::from_rgba8(30, 40, 50, 255));
+    expected.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), None);
+    expected.stroke_path(&curve, &paint, &stroke, Transform::identity(), None);
+
+    let mut data = vec![0; 200 * 200 * 4];
+    let mut bitmap = BitmapMut::from_bytes(&mut data, 200, 200, ColorType::Bgra8).unwrap();
+    bitmap.fill(Color::from_rgba8(30, 40, 50, 255));
+    bitmap.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), None);
+    bitmap.stroke_path(&curve, &paint, &stroke, Transform::identity(), None);
+
+    for (bgra, rgba) in data.chunks(4).zip(expected.data().chunks(4)) {
+        assert_eq!(bgra, [rgba[2], rgba[1], rgba[0], rgba[3]]);
+    }
+}
+
+#[test]
+fn rgb565_fill() {
+    let mut data = vec![0; 4 * 2];
+    let mut bitmap = BitmapMut::from_bytes(&mut data, 2, 2, ColorType::Rgb565).unwrap();
+    bitmap.fill(Color::from_rgba8(255, 0, 0, 255));
+    assert_eq!(u16::from_ne_bytes([data[0], data[1]]), 0xF800);
+}
+
+#[test]
+fn gray8_fill() {
+    let mut bitmap = Bitmap::new(2, 2, ColorType::Gray8).unwrap();
+    bitmap.fill(Color::from_rgba8(255, 0, 0, 255));
+    assert_eq!(bitmap.data(), &[54, 54, 54, 54]);
+
+    let c = bitmap.pixel(0, 0).unwrap();
+    assert_eq!(c.green(), c.red());
+    assert_eq!(c.alpha(), 1.0);
+}
+
+#[test]
+fn a8_matches_mask() {
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(&path, FillRule::Winding, true, Transform::identity());
+
+    let mut bitmap = Bitmap::new(100, 100, ColorType::A8).unwrap();
+    bitmap.fill_path(&path, &Paint::default(), FillRule::Winding, Transform::identity(), None);
+
+    for (a, b) in bitmap.data().iter().zip(mask.data()) {
+        assert!((*a as i32 - *b as i32).abs() <= 1);
+    }
+}
+
+// Low and high quality pipelines must produce the same results, up to rounding.
+// Even for RGBA8 they can differ by 2.
+#[test]
+fn lowp_matches_highp() {
+    let (circle, curve, stroke) = paths();
+
+    for (color_type, tolerance) in [
+        (ColorType::Rgba8, 2.5 / 255.0),
+        (ColorType::Bgra8, 2.5 / 255.0),
+        (ColorType::Rgb565, 1.5 / 31.0),
+        (ColorType::Gray8, 2.5 / 255.0),
+        (ColorType::A8, 2.5 / 255.0),
+    ] {
+        let render = |force_hq_pipeline| {
+            let paint = Paint {
+                shader: Shader::SolidColor(Color::from_rgba8(220, 140, 75, 180)),
+                force_hq_pipeline,
+                ..Paint::default()
+            };
+
+            let mut bitmap = Bitmap::new(200, 200, color_type).unwrap();
+            bitmap.fill(Color::from_rgba8(30, 40, 50, 200));
+            bitmap.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), None);
+            bitmap.stroke_path(&curve, &paint, &stroke, Transform::identity(), None);
+            bitmap
+        };
+
+        let lowp = render(false);
+        let highp = render(true);
+        for y in 0..200 {
+            for x in 0..200 {
+                let a = lowp.pixel(x, y).unwrap();
+                let b = highp.pixel(x, y).unwrap();
+                assert!((a.red() - b.red()).abs() <= tolerance);
+                assert!((a.green() - b.green()).abs() <= tolerance);
+                assert!((a.blue() - b.blue()).abs() <= tolerance);
+                assert!((a.alpha() - b.alpha()).abs() <= tolerance);
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use tiny_skia::*;
+
+fn circle_pixmap() -> Pixmap {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let path = PathBuilder::from_circle(50.0, 50.0, 30.0).unwrap();
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    pixmap
+}
+
+fn edge_pixmap() -> Pixmap {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = false;
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 40.0, 60.0).unwrap(), &paint, Transform::identity(), None);
+    paint.set_color_rgba8(220, 140, 75, 180);
+    pixmap.fill_rect(Rect::from_xywh(70.0, 30.0, 30.0, 70.0).unwrap(), &paint, Transform::identity(), None);
+    pixmap
+}
+
+#[test]
+fn small_sigma() {
+    let mut pixmap = circle_pixmap();
+    pixmap.blur(1.5, 1.5, BlurEdgeMode::Transparent);
+
+    let expected = Pixmap::load_png("tests/images/blur/small-sigma.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn large_sigma() {
+    let mut pixmap = circle_pixmap();
+    pixmap.blur(5.0, 5.0, BlurEdgeMode::Transparent);
+
+    let expected = Pixmap::load_png("tests/images/blur/large-sigma.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn different_sigmas() {
+    // An even and an odd box size.
+    let mut pixmap = circle_pixmap();
+    pixmap.blur(4.0, 10.0, BlurEdgeMode::Transparent);
+
+    let expected = Pixmap::load_png("tests/images/blur/different-sigmas.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn x_only() {
+    let mut pixmap = circle_pixmap();
+    pixmap.blur(8.0, 0.0, BlurEdgeMode::Transparent);
+
+    let expected = Pixmap::load_png("tests/images/blur/x-only.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn edge_mode_clamp() {
+    let mut pixmap = edge_pixmap();
+    pixmap.blur(6.0, 6.0, BlurEdgeMode::Clamp);
+
+    let expected = Pixmap::load_png("tests/images/blur/edge-mode-clamp.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn edge_mode_wrap() {
+    let mut pixmap = edge_pixmap();
+    pixmap.blur(6.0, 6.0, BlurEdgeMode::Wrap);
+
+    let expected = Pixmap::load_png("tests/images/blur/edge-mode-wrap.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn solid_with_clamp() {
+    let mut pixmap = Pixmap::new(50, 50).unwrap();
+    pixmap.fill(Color::from_rgba8(50, 127, 150, 200));
+    let expected = pixmap.clone();
+
+    pixmap.blur(1.0, 7.0, BlurEdgeMode::Clamp);
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn zero_sigma() {
+    let mut pixmap = circle_pixmap();
+    pixmap.blur(0.0, 0.0, BlurEdgeMode::Transparent);
+    assert_eq!(pixmap, circle_pixmap());
+}
+
+#[test]
+fn negative_sigma() {
+    let mut pixmap = circle_pixmap();
+    pixmap.blur(-1.0, 2.0, BlurEdgeMode::Transparent);
+    assert_eq!(pixmap, circle_pixmap());
+}
+
+#[test]
+fn mask() {
+    let path = PathBuilder::from_circle(50.0, 50.0, 30.0).unwrap();
+    let mut mask = Mask::new(100, 99).unwrap(); // not a multiple of 4
+    mask.fill_path(&path, FillRule::Winding, true, Transform::identity());
+    mask.blur(3.0, 1.0, BlurEdgeMode::Transparent);
+
+    let expected = Mask::load_png("tests/images/blur/mask.png").unwrap();
+    assert_eq!(mask, expected);
+}
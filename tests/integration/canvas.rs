@@ -0,0 +1,250 @@
+use tiny_skia::*;
+
+#[test]
+fn transform_save_restore() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let rect = Rect::from_xywh(10.0, 10.0, 30.0, 20.0).unwrap();
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.translate(20.0, 10.0);
+    canvas.save();
+    canvas.scale(2.0, 2.0);
+    canvas.fill_rect(rect, &paint);
+    canvas.restore();
+    canvas.fill_rect(rect, &paint);
+    assert_eq!(canvas.save_count(), 0);
+
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    let ts = Transform::from_translate(20.0, 10.0);
+    expected.fill_rect(rect, &paint, ts.pre_scale(2.0, 2.0), None);
+    expected.fill_rect(rect, &paint, ts, None);
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn restore_without_save() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.translate(20.0, 10.0);
+    canvas.restore();
+    assert_eq!(canvas.transform(), Transform::from_translate(20.0, 10.0));
+}
+
+#[test]
+fn clip_rect() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = false;
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.rotate(15.0);
+    canvas.clip_rect(Rect::from_xywh(30.0, 10.0, 60.0, 50.0).unwrap(), true);
+    canvas.reset_transform();
+    canvas.fill_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(), &paint);
+
+    let expected = Pixmap::load_png("tests/images/canvas/clip-rect.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn clip_path_save_restore() {
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+
+    let circle = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.clip_path(&circle, FillRule::Winding, true);
+
+    canvas.save();
+    canvas.clip_rect(Rect::from_xywh(0.0, 0.0, 50.0, 100.0).unwrap(), false);
+    paint.set_color_rgba8(50, 127, 150, 200);
+    canvas.fill_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(), &paint);
+    canvas.restore();
+
+    // Only the circle clip is left.
+    paint.set_color_rgba8(220, 140, 75, 180);
+    canvas.fill_rect(Rect::from_xywh(40.0, 0.0, 60.0, 100.0).unwrap(), &paint);
+
+    canvas.reset_clip();
+    assert!(canvas.clip_mask().is_none());
+
+    let expected = Pixmap::load_png("tests/images/canvas/clip-path-save-restore.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn save_shares_clip() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.clip_rect(Rect::from_xywh(10.0, 10.0, 50.0, 50.0).unwrap(), true);
+    let clip = canvas.clip_mask().unwrap() as *const Mask;
+
+    // The clip is not copied when it doesn't change.
+    canvas.save();
+    canvas.translate(10.0, 10.0);
+    assert!(core::ptr::eq(clip, canvas.clip_mask().unwrap()));
+    canvas.restore();
+    assert!(core::ptr::eq(clip, canvas.clip_mask().unwrap()));
+
+    // And copied only when it does.
+    canvas.save();
+    canvas.clip_rect(Rect::from_xywh(0.0, 0.0, 20.0, 20.0).unwrap(), true);
+    assert!(!core::ptr::eq(clip, canvas.clip_mask().unwrap()));
+    canvas.restore();
+    assert!(core::ptr::eq(clip, canvas.clip_mask().unwrap()));
+}
+
+#[test]
+fn stroke_path_with_clip() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 10.0);
+    pb.line_to(90.0, 90.0);
+    pb.move_to(90.0, 10.0);
+    pb.line_to(10.0, 90.0);
+    let path = pb.finish().unwrap();
+
+    let mut stroke = Stroke::default();
+    stroke.width = 10.0;
+
+    let clip_rect = Rect::from_xywh(20.0, 20.0, 60.0, 60.0).unwrap();
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.clip_rect(clip_rect, true);
+    canvas.stroke_path(&path, &paint, &stroke);
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(&PathBuilder::from_rect(clip_rect), FillRule::Winding, true, Transform::identity());
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    expected.stroke_path(&path, &paint, &stroke, Transform::identity(), Some(&mask));
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn layer_opacity() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let circle1 = PathBuilder::from_circle(35.0, 50.0, 30.0).unwrap();
+    let circle2 = PathBuilder::from_circle(65.0, 50.0, 30.0).unwrap();
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+
+    let mut layer_paint = PixmapPaint::default();
+    layer_paint.opacity = 0.5;
+    canvas.save_layer(None, &layer_paint, None);
+    canvas.fill_path(&circle1, &paint, FillRule::Winding);
+    paint.set_color_rgba8(220, 140, 75, 255);
+    canvas.fill_path(&circle2, &paint, FillRule::Winding);
+    canvas.restore();
+
+    let expected = Pixmap::load_png("tests/images/canvas/layer-opacity.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn layer_bounds_with_transform() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.translate(50.0, 50.0);
+    canvas.rotate(30.0);
+    // Bounds are mapped by the current transform and then rounded out.
+    canvas.save_layer(Rect::from_xywh(-20.0, -20.0, 40.0, 40.0), &PixmapPaint::default(), None);
+    canvas.reset_transform();
+    canvas.fill_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(), &paint);
+    canvas.restore();
+    assert_eq!(canvas.transform(), Transform::from_translate(50.0, 50.0).pre_rotate(30.0));
+
+    let expected = Pixmap::load_png("tests/images/canvas/layer-bounds-with-transform.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn layer_blend_mode_and_mask() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(220, 140, 75, 255);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill(Color::from_rgba8(50, 127, 150, 255));
+
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(
+        &PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap(),
+        FillRule::Winding,
+        true,
+        Transform::identity(),
+    );
+
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.clip_rect(Rect::from_xywh(0.0, 0.0, 70.0, 100.0).unwrap(), false);
+
+    let mut layer_paint = PixmapPaint::default();
+    layer_paint.blend_mode = BlendMode::Multiply;
+    canvas.save_layer(Rect::from_xywh(20.0, 0.0, 80.0, 100.0), &layer_paint, Some(&mask));
+    canvas.fill_rect(Rect::from_xywh(0.0, 10.0, 100.0, 80.0).unwrap(), &paint);
+    canvas.restore();
+
+    let expected = Pixmap::load_png("tests/images/canvas/layer-blend-mode-and-mask.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn nested_layers() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+
+    let mut layer_paint = PixmapPaint::default();
+    layer_paint.opacity = 0.75;
+    canvas.save_layer(Rect::from_xywh(10.0, 10.0, 80.0, 80.0), &layer_paint, None);
+    canvas.fill_rect(Rect::from_xywh(0.0, 0.0, 60.0, 60.0).unwrap(), &paint);
+
+    canvas.save_layer(Rect::from_xywh(30.0, 30.0, 70.0, 70.0), &layer_paint, None);
+    canvas.clip_path(&PathBuilder::from_circle(60.0, 60.0, 30.0).unwrap(), FillRule::Winding, true);
+    paint.set_color_rgba8(220, 140, 75, 180);
+    canvas.fill_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(), &paint);
+    assert_eq!(canvas.save_count(), 2);
+
+    // Restores everything.
+    canvas.into_pixmap();
+
+    let expected = Pixmap::load_png("tests/images/canvas/nested-layers.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn layer_without_bounds_and_opacity() {
+    // A default layer must not affect the result.
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let mut canvas = Canvas::new(pixmap.as_mut());
+    canvas.save_layer(None, &PixmapPaint::default(), None);
+    canvas.fill_path(&path, &paint, FillRule::Winding);
+    canvas.restore();
+
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    expected.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    assert_eq!(pixmap, expected);
+}
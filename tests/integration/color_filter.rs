@@ -0,0 +1,167 @@
+use tiny_skia::*;
+
+const GRAYSCALE: [f32; 20] = [
+    0.2126, 0.7152, 0.0722, 0.0, 0.0,
+    0.2126, 0.7152, 0.0722, 0.0, 0.0,
+    0.2126, 0.7152, 0.0722, 0.0, 0.0,
+    0.0, 0.0, 0.0, 1.0, 0.0,
+];
+
+fn gradient() -> Shader<'static> {
+    LinearGradient::new(
+        Point::from_xy(10.0, 10.0),
+        Point::from_xy(190.0, 190.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(0.5, Color::from_rgba8(220, 140, 75, 255)),
+            GradientStop::new(1.0, Color::from_rgba8(40, 180, 55, 100)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .unwrap()
+}
+
+fn fill(paint: &Paint) -> Pixmap {
+    let path = PathBuilder::from_circle(100.0, 100.0, 90.0).unwrap();
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, paint, FillRule::Winding, Transform::identity(), None);
+    pixmap
+}
+
+#[test]
+fn matrix_solid_color() {
+    let paint = Paint {
+        shader: Shader::SolidColor(Color::from_rgba8(200, 100, 50, 255)),
+        color_filter: ColorFilter::matrix(GRAYSCALE),
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let mut pixmap = Pixmap::new(10, 10).unwrap();
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap(), &paint, Transform::identity(), None);
+
+    // 200 * 0.2126 + 100 * 0.7152 + 50 * 0.0722 = 117.66
+    assert_eq!(pixmap.pixel(5, 5).unwrap(), PremultipliedColorU8::from_rgba(118, 118, 118, 255).unwrap());
+}
+
+#[test]
+fn matrix_invalid() {
+    let mut matrix = GRAYSCALE;
+    matrix[3] = f32::NAN;
+    assert!(ColorFilter::matrix(matrix).is_none());
+}
+
+#[test]
+fn matrix_identity() {
+    let identity = [
+        1.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ];
+
+    let paint = Paint {
+        shader: gradient(),
+        force_hq_pipeline: true,
+        ..Paint::default()
+    };
+    let expected = fill(&paint);
+
+    let paint = Paint {
+        color_filter: ColorFilter::matrix(identity),
+        ..paint
+    };
+    assert_eq!(fill(&paint), expected);
+}
+
+#[test]
+fn matrix_grayscale() {
+    let paint = Paint {
+        shader: gradient(),
+        color_filter: ColorFilter::matrix(GRAYSCALE),
+        ..Paint::default()
+    };
+
+    let pixmap = fill(&paint);
+
+    let expected = Pixmap::load_png("tests/images/color_filter/matrix-grayscale.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn table() {
+    // Posterize.
+    let mut table = [0; 256];
+    for (i, v) in table.iter_mut().enumerate() {
+        *v = (i / 64 * 85) as u8;
+    }
+
+    let mut inverted = [0; 256];
+    for (i, v) in inverted.iter_mut().enumerate() {
+        *v = 255 - i as u8;
+    }
+
+    let paint = Paint {
+        shader: gradient(),
+        color_filter: Some(ColorFilter::table_rgba(Some(&table), Some(&inverted), Some(&table), None)),
+        ..Paint::default()
+    };
+
+    let pixmap = fill(&paint);
+
+    let expected = Pixmap::load_png("tests/images/color_filter/table.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn blend_tint() {
+    let paint = Paint {
+        shader: gradient(),
+        color_filter: Some(ColorFilter::blend(Color::from_rgba8(220, 40, 75, 255), BlendMode::SourceIn)),
+        ..Paint::default()
+    };
+
+    let pixmap = fill(&paint);
+
+    let expected = Pixmap::load_png("tests/images/color_filter/blend-tint.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn blend_destination() {
+    let paint = Paint {
+        shader: gradient(),
+        force_hq_pipeline: true,
+        ..Paint::default()
+    };
+    let expected = fill(&paint);
+
+    // The filtered color is the destination, so it should be left as is.
+    let paint = Paint {
+        color_filter: Some(ColorFilter::blend(Color::WHITE, BlendMode::Destination)),
+        ..paint
+    };
+    assert_eq!(fill(&paint), expected);
+}
+
+#[test]
+fn draw_pixmap() {
+    let mut image = Pixmap::new(100, 100).unwrap();
+    let paint = Paint {
+        shader: gradient(),
+        ..Paint::default()
+    };
+    image.fill_path(&PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap(), &paint, FillRule::Winding, Transform::identity(), None);
+
+    let paint = PixmapPaint {
+        color_filter: ColorFilter::matrix(GRAYSCALE),
+        ..PixmapPaint::default()
+    };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.draw_pixmap(0, 0, image.as_ref(), &paint, Transform::from_scale(2.0, 2.0), None);
+
+    let expected = Pixmap::load_png("tests/images/color_filter/draw-pixmap.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
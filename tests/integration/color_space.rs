@@ -0,0 +1,188 @@
+use tiny_skia::*;
+
+fn red_to_green() -> Shader<'static> {
+    LinearGradient::new(
+        Point::from_xy(10.0, 0.0),
+        Point::from_xy(190.0, 0.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(255, 0, 0, 255)),
+            GradientStop::new(1.0, Color::from_rgba8(0, 255, 0, 255)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .unwrap()
+}
+
+fn red_to_transparent_blue() -> Shader<'static> {
+    LinearGradient::new(
+        Point::from_xy(10.0, 0.0),
+        Point::from_xy(190.0, 0.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(255, 0, 0, 255)),
+            GradientStop::new(0.5, Color::from_rgba8(255, 200, 0, 255)),
+            GradientStop::new(1.0, Color::from_rgba8(0, 0, 255, 0)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .unwrap()
+}
+
+fn fill_rect(shader: Shader) -> Pixmap {
+    let paint = Paint {
+        shader,
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let rect = Rect::from_xywh(0.0, 0.0, 200.0, 50.0).unwrap();
+    let mut pixmap = Pixmap::new(200, 50).unwrap();
+    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    pixmap
+}
+
+fn pixel(pixmap: &Pixmap, x: u32) -> (u8, u8, u8, u8) {
+    let c = pixmap.pixel(x, 0).unwrap();
+    (c.red(), c.green(), c.blue(), c.alpha())
+}
+
+#[test]
+fn srgb_blend() {
+    let paint = Paint {
+        shader: Shader::SolidColor(Color::from_rgba(1.0, 1.0, 1.0, 0.5).unwrap()),
+        ..Paint::default()
+    };
+
+    let rect = Rect::from_xywh(0.0, 0.0, 4.0, 4.0).unwrap();
+    let mut pixmap = Pixmap::new(4, 4).unwrap();
+    pixmap.fill(Color::BLACK);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+
+    assert_eq!(pixmap.pixel(0, 0).unwrap().red(), 128);
+}
+
+#[test]
+fn linear_blend() {
+    let paint = Paint {
+        shader: Shader::SolidColor(Color::from_rgba(1.0, 1.0, 1.0, 0.5).unwrap()),
+        color_space: ColorSpace::LinearSrgb,
+        ..Paint::default()
+    };
+
+    let rect = Rect::from_xywh(0.0, 0.0, 4.0, 4.0).unwrap();
+    let mut pixmap = Pixmap::new(4, 4).unwrap();
+    pixmap.fill(Color::BLACK);
+    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+
+    // 50% of linear light is 188 in sRGB.
+    assert_eq!(pixmap.pixel(0, 0).unwrap().red(), 188);
+}
+
+#[test]
+fn linear_source_is_lossless() {
+    let color = Color::from_rgba8(50, 127, 150, 200);
+    let paint = Paint {
+        shader: Shader::SolidColor(color),
+        blend_mode: BlendMode::SourceOver,
+        color_space: ColorSpace::LinearSrgb,
+        ..Paint::default()
+    };
+
+    let rect = Rect::from_xywh(0.0, 0.0, 4.0, 4.0).unwrap();
+    let mut pixmap = Pixmap::new(4, 4).unwrap();
+    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), color.premultiply().to_color_u8());
+}
+
+#[test]
+fn linear_anti_aliasing() {
+    let paint = Paint {
+        shader: Shader::SolidColor(Color::WHITE),
+        color_space: ColorSpace::LinearSrgb,
+        ..Paint::default()
+    };
+
+    let path = PathBuilder::from_circle(100.0, 100.0, 70.0).unwrap();
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill(Color::BLACK);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/color_space/linear-anti-aliasing.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn linear_draw_pixmap() {
+    let mut image = Pixmap::new(100, 100).unwrap();
+    image.fill(Color::from_rgba8(50, 127, 150, 200));
+
+    let paint = PixmapPaint {
+        opacity: 0.5,
+        color_space: ColorSpace::LinearSrgb,
+        ..PixmapPaint::default()
+    };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill(Color::from_rgba8(220, 140, 75, 255));
+    pixmap.draw_pixmap(50, 50, image.as_ref(), &paint, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/color_space/linear-draw-pixmap.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn gradient_srgb_interpolation() {
+    let pixmap = fill_rect(red_to_green());
+    assert_eq!(pixel(&pixmap, 100), (127, 128, 0, 255));
+}
+
+#[test]
+fn gradient_linear_interpolation() {
+    let mut shader = red_to_green();
+    shader.set_gradient_interpolation(GradientInterpolation::LinearSrgb);
+
+    let pixmap = fill_rect(shader);
+    assert_eq!(pixel(&pixmap, 100), (187, 188, 0, 255));
+}
+
+#[test]
+fn gradient_linear_interpolation_with_transparency() {
+    let mut shader = red_to_transparent_blue();
+    shader.set_gradient_interpolation(GradientInterpolation::LinearSrgb);
+
+    let pixmap = fill_rect(shader);
+    let expected = Pixmap::load_png("tests/images/color_space/gradient-linear.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn gradient_premultiplied_linear_interpolation() {
+    let mut shader = red_to_transparent_blue();
+    shader.set_gradient_interpolation(GradientInterpolation::PremultipliedLinearSrgb);
+
+    let pixmap = fill_rect(shader);
+    let expected = Pixmap::load_png("tests/images/color_space/gradient-premultiplied-linear.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn gradient_premultiplied_no_color_bleeding() {
+    let mut shader = LinearGradient::new(
+        Point::from_xy(10.0, 0.0),
+        Point::from_xy(190.0, 0.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(255, 0, 0, 255)),
+            GradientStop::new(1.0, Color::from_rgba8(0, 0, 255, 0)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .unwrap();
+    shader.set_gradient_interpolation(GradientInterpolation::PremultipliedLinearSrgb);
+
+    let pixmap = fill_rect(shader);
+    // No blue from the transparent stop.
+    assert_eq!(pixel(&pixmap, 100), (127, 0, 0, 127));
+}
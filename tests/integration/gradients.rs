@@ -433,3 +433,239 @@ fn global_opacity() {
     let expected = Pixmap::load_png("tests/images/gradients/global-opacity.png").unwrap();
     assert_eq!(pixmap, expected);
 }
+
+#[test]
+fn concentric_two_point_radial() {
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = RadialGradient::new_two_point(
+        Point::from_xy(100.0, 100.0),
+        30.0,
+        Point::from_xy(100.0, 100.0),
+        90.0,
+        vec![
+            GradientStop::new(0.25, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(0.75, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/concentric-two-point-radial.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn concentric_two_point_radial_swapped() {
+    // A start radius bigger than the end one inverts the gradient direction.
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = RadialGradient::new_two_point(
+        Point::from_xy(100.0, 100.0),
+        90.0,
+        Point::from_xy(100.0, 100.0),
+        30.0,
+        vec![
+            GradientStop::new(0.25, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(0.75, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Reflect,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/concentric-two-point-radial-swapped.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn strip_two_point_radial() {
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = RadialGradient::new_two_point(
+        Point::from_xy(60.0, 100.0),
+        40.0,
+        Point::from_xy(140.0, 100.0),
+        40.0,
+        vec![
+            GradientStop::new(0.25, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(0.75, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/strip-two-point-radial.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn focal_two_point_radial() {
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = RadialGradient::new_two_point(
+        Point::from_xy(90.0, 110.0),
+        20.0,
+        Point::from_xy(110.0, 90.0),
+        80.0,
+        vec![
+            GradientStop::new(0.25, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(0.75, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/focal-two-point-radial.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn smaller_two_point_radial() {
+    // The start circle is bigger and outside of the end circle.
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = RadialGradient::new_two_point(
+        Point::from_xy(50.0, 100.0),
+        40.0,
+        Point::from_xy(150.0, 100.0),
+        10.0,
+        vec![
+            GradientStop::new(0.25, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(0.75, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/smaller-two-point-radial.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn sweep_gradient_lq() {
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = SweepGradient::new(
+        Point::from_xy(100.0, 100.0),
+        0.0,
+        360.0,
+        vec![
+            GradientStop::new(0.00, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(0.50, Color::from_rgba8(220, 140, 75, 180)),
+            GradientStop::new(1.00, Color::from_rgba8(50, 127, 150, 200)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/sweep-lq.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn sweep_gradient_hq() {
+    let mut paint = Paint::default();
+    paint.force_hq_pipeline = true;
+    paint.anti_alias = false;
+    paint.shader = SweepGradient::new(
+        Point::from_xy(100.0, 100.0),
+        0.0,
+        360.0,
+        vec![
+            GradientStop::new(0.00, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(0.50, Color::from_rgba8(220, 140, 75, 180)),
+            GradientStop::new(1.00, Color::from_rgba8(50, 127, 150, 200)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/sweep-hq.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn sweep_gradient_partial_repeat() {
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = SweepGradient::new(
+        Point::from_xy(100.0, 100.0),
+        45.0,
+        135.0,
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Repeat,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/sweep-partial-repeat.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn sweep_gradient_degenerate_pad() {
+    // A zero-length sweep in Pad mode produces a hard stop at the end angle.
+    let mut paint = Paint::default();
+    paint.anti_alias = false;
+    paint.shader = SweepGradient::new(
+        Point::from_xy(100.0, 100.0),
+        90.0,
+        90.0,
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/gradients/sweep-degenerate-pad.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
@@ -0,0 +1,87 @@
+use tiny_skia::*;
+
+fn star() -> Path {
+    let mut pb = PathBuilder::new();
+    pb.move_to(50.0, 5.0);
+    pb.line_to(78.0, 90.0);
+    pb.line_to(5.0, 35.0);
+    pb.line_to(95.0, 35.0);
+    pb.line_to(22.0, 90.0);
+    pb.close();
+    pb.push_circle(50.0, 50.0, 10.0);
+    pb.move_to(10.0, 70.0);
+    pb.cubic_to(30.0, 120.0, 70.0, 20.0, 90.0, 70.0);
+    pb.quad_to(50.0, 0.0, 10.0, 70.0);
+    pb.finish().unwrap()
+}
+
+// Counts pixels, which centers are hit-tested differently from the rasterizer output.
+fn count_mismatches(pixmap: &Pixmap, hit: impl Fn(Point) -> bool) -> usize {
+    let mut count = 0;
+    for y in 0..pixmap.height() {
+        for x in 0..pixmap.width() {
+            let filled = pixmap.pixel(x, y).unwrap().alpha() != 0;
+            if filled != hit(Point::from_xy(x as f32 + 0.5, y as f32 + 0.5)) {
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+#[test]
+fn contains_matches_fill() {
+    let path = star();
+    let paint = Paint {
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    for fill_rule in [FillRule::Winding, FillRule::EvenOdd] {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        pixmap.fill_path(&path, &paint, fill_rule, Transform::identity(), None);
+
+        // The rasterizer flattens curves and uses fixed-point edges,
+        // so allow a few edge pixels to differ.
+        let mismatches = count_mismatches(&pixmap, |p| path.contains(p, fill_rule));
+        assert!(mismatches < 25, "{:?}: {}", fill_rule, mismatches);
+    }
+}
+
+#[test]
+fn stroke_contains_matches_stroke() {
+    let path = star();
+    let paint = Paint {
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    for line_join in [
+        LineJoin::Miter,
+        LineJoin::MiterClip,
+        LineJoin::Round,
+        LineJoin::Bevel,
+    ] {
+        for line_cap in [LineCap::Butt, LineCap::Round, LineCap::Square] {
+            let stroke = Stroke {
+                width: 6.0,
+                line_join,
+                line_cap,
+                ..Stroke::default()
+            };
+
+            let mut pixmap = Pixmap::new(100, 100).unwrap();
+            pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+
+            let mismatches = count_mismatches(&pixmap, |p| path.stroke_contains(p, &stroke, 0.0));
+            assert!(
+                mismatches < 40,
+                "{:?} {:?}: {}",
+                line_join,
+                line_cap,
+                mismatches
+            );
+        }
+    }
+}
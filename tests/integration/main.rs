@@ -1,11 +1,29 @@
+#[rustfmt::skip] mod bitmap;
+#[rustfmt::skip] mod blur;
 #[rustfmt::skip] mod mask;
+#[rustfmt::skip] mod canvas;
+#[rustfmt::skip] mod color_filter;
+#[rustfmt::skip] mod color_space;
 #[rustfmt::skip] mod dash;
 #[rustfmt::skip] mod fill;
 #[rustfmt::skip] mod gradients;
 #[rustfmt::skip] mod hairline;
+#[rustfmt::skip] mod hit_test;
+#[rustfmt::skip] mod parallel;
+#[rustfmt::skip] mod patch;
 #[rustfmt::skip] mod path;
+#[rustfmt::skip] mod path_effect;
+#[rustfmt::skip] mod path_measure;
+#[rustfmt::skip] mod path_ops;
+#[rustfmt::skip] mod perspective;
 #[rustfmt::skip] mod pattern;
+#[rustfmt::skip] mod picture;
 #[rustfmt::skip] mod pixmap;
 #[rustfmt::skip] mod png;
+#[rustfmt::skip] mod resize;
+#[rustfmt::skip] mod rrect;
+#[rustfmt::skip] mod shadow;
 #[rustfmt::skip] mod skia_dash;
+#[rustfmt::skip] mod stride;
 #[rustfmt::skip] mod stroke;
+#[rustfmt::skip] mod vertices;
@@ -0,0 +1,150 @@
+use tiny_skia::*;
+
+// Renders using both modes and checks that the results are identical.
+fn assert_parallel(draw: impl Fn(&mut Pixmap, bool) + Sync) {
+    let mut serial = Pixmap::new(300, 500).unwrap();
+    serial.fill(Color::from_rgba8(255, 255, 255, 255));
+    let mut parallel = serial.clone();
+
+    draw(&mut serial, false);
+
+    // Make sure the pixmap is split even on a single core machine.
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+    pool.install(|| draw(&mut parallel, true));
+
+    assert_eq!(serial, parallel);
+}
+
+fn star() -> Path {
+    let mut pb = PathBuilder::new();
+    pb.move_to(150.0, 10.0);
+    pb.line_to(240.0, 490.0);
+    pb.line_to(10.0, 160.0);
+    pb.line_to(290.0, 160.0);
+    pb.line_to(60.0, 490.0);
+    pb.close();
+    pb.push_circle(150.0, 250.0, 100.3);
+    pb.finish().unwrap()
+}
+
+#[test]
+fn fill_path() {
+    for anti_alias in [true, false] {
+        assert_parallel(|pixmap, parallel| {
+            let paint = Paint {
+                shader: Shader::SolidColor(Color::from_rgba8(50, 127, 150, 200)),
+                anti_alias,
+                parallel,
+                ..Paint::default()
+            };
+
+            pixmap.fill_path(&star(), &paint, FillRule::EvenOdd, Transform::from_rotate_at(3.0, 150.0, 250.0), None);
+        });
+    }
+}
+
+#[test]
+fn fill_path_gradient_with_mask() {
+    let mut mask = Mask::new(300, 500).unwrap();
+    mask.fill_path(
+        &PathBuilder::from_circle(150.0, 250.0, 130.0).unwrap(),
+        FillRule::Winding,
+        true,
+        Transform::identity(),
+    );
+
+    assert_parallel(|pixmap, parallel| {
+        let paint = Paint {
+            shader: LinearGradient::new(
+                Point::from_xy(0.0, 0.0),
+                Point::from_xy(100.0, 500.0),
+                vec![
+                    GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+                    GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+                ],
+                SpreadMode::Reflect,
+                Transform::identity(),
+            ).unwrap(),
+            blend_mode: BlendMode::Multiply,
+            parallel,
+            ..Paint::default()
+        };
+
+        pixmap.fill_path(&star(), &paint, FillRule::Winding, Transform::identity(), Some(&mask));
+    });
+}
+
+#[test]
+fn stroke_path() {
+    assert_parallel(|pixmap, parallel| {
+        let paint = Paint {
+            shader: Shader::SolidColor(Color::from_rgba8(50, 127, 150, 200)),
+            parallel,
+            ..Paint::default()
+        };
+
+        let stroke = Stroke { width: 7.0, ..Stroke::default() };
+        pixmap.stroke_path(&star(), &paint, &stroke, Transform::identity(), None);
+    });
+}
+
+#[test]
+fn stroke_hairline() {
+    for anti_alias in [true, false] {
+        assert_parallel(|pixmap, parallel| {
+            let paint = Paint {
+                shader: Shader::SolidColor(Color::from_rgba8(50, 127, 150, 200)),
+                anti_alias,
+                parallel,
+                ..Paint::default()
+            };
+
+            // A nearly horizontal line crossing a band boundary.
+            let mut pb = PathBuilder::new();
+            pb.push_path(&star());
+            pb.move_to(10.0, 120.0);
+            pb.line_to(290.0, 130.0);
+            let path = pb.finish().unwrap();
+
+            let stroke = Stroke { width: 0.5, line_cap: LineCap::Round, ..Stroke::default() };
+            pixmap.stroke_path(&path, &paint, &stroke, Transform::from_scale(1.01, 0.99), None);
+        });
+    }
+}
+
+#[test]
+fn draw_pixmap() {
+    let mut image = Pixmap::new(100, 100).unwrap();
+    image.fill_path(
+        &PathBuilder::from_circle(50.0, 50.0, 45.0).unwrap(),
+        &Paint::default(),
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+
+    for transform in [Transform::identity(), Transform::from_row(2.5, 0.3, -0.2, 4.0, 20.0, 30.0)] {
+        assert_parallel(|pixmap, parallel| {
+            let paint = PixmapPaint {
+                quality: FilterQuality::Bicubic,
+                opacity: 0.8,
+                parallel,
+                ..PixmapPaint::default()
+            };
+
+            pixmap.draw_pixmap(50, 70, image.as_ref(), &paint, transform, None);
+        });
+    }
+}
+
+#[test]
+fn small_pixmap() {
+    // Too small to be split, but must still work.
+    let mut serial = Pixmap::new(20, 20).unwrap();
+    let mut parallel = serial.clone();
+    let rect = Rect::from_xywh(2.5, 2.5, 15.0, 15.0).unwrap();
+    serial.fill_rect(rect, &Paint::default(), Transform::identity(), None);
+    let paint = Paint { parallel: true, ..Paint::default() };
+    parallel.fill_rect(rect, &paint, Transform::identity(), None);
+    assert_eq!(serial, parallel);
+}
@@ -0,0 +1,202 @@
+use tiny_skia::*;
+
+// A wavy patch from Skia's `patch_primitive` GM.
+fn cubics() -> [Point; 12] {
+    [
+        // top
+        Point::from_xy(10.0, 10.0), Point::from_xy(40.0, 0.0), Point::from_xy(60.0, 20.0), Point::from_xy(90.0, 10.0),
+        // right
+        Point::from_xy(80.0, 40.0), Point::from_xy(100.0, 60.0), Point::from_xy(90.0, 90.0),
+        // bottom
+        Point::from_xy(60.0, 80.0), Point::from_xy(40.0, 100.0), Point::from_xy(10.0, 90.0),
+        // left
+        Point::from_xy(20.0, 60.0), Point::from_xy(0.0, 40.0),
+    ]
+}
+
+fn colors() -> [Color; 4] {
+    [
+        Color::from_rgba8(255, 0, 0, 255),
+        Color::from_rgba8(0, 255, 0, 255),
+        Color::from_rgba8(0, 0, 255, 255),
+        Color::from_rgba8(255, 255, 0, 255),
+    ]
+}
+
+fn checkerboard() -> Pixmap {
+    let mut pixmap = Pixmap::new(10, 10).unwrap();
+    let paint = Paint {
+        shader: Shader::SolidColor(Color::from_rgba8(50, 50, 50, 255)),
+        ..Paint::default()
+    };
+    pixmap.fill(Color::WHITE);
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 5.0, 5.0).unwrap(), &paint, Transform::identity(), None);
+    pixmap.fill_rect(Rect::from_xywh(5.0, 5.0, 5.0, 5.0).unwrap(), &paint, Transform::identity(), None);
+    pixmap
+}
+
+#[test]
+fn colors_only() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_patch(&cubics(), Some(&colors()), None, BlendMode::Destination,
+                      &Paint::default(), Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/patch/colors-only.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn texture_only() {
+    let texture = checkerboard();
+    let paint = Paint {
+        shader: Pattern::new(texture.as_ref(), SpreadMode::Repeat, FilterQuality::Bilinear, 1.0, Transform::identity()),
+        ..Paint::default()
+    };
+
+    let tex_coords = [
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(40.0, 0.0),
+        Point::from_xy(40.0, 40.0),
+        Point::from_xy(0.0, 40.0),
+    ];
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_patch(&cubics(), None, Some(&tex_coords), BlendMode::Modulate,
+                      &paint, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/patch/texture-only.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn colors_modulate_texture() {
+    let texture = checkerboard();
+    let paint = Paint {
+        shader: Pattern::new(texture.as_ref(), SpreadMode::Repeat, FilterQuality::Bilinear, 1.0, Transform::identity()),
+        ..Paint::default()
+    };
+
+    let tex_coords = [
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(40.0, 0.0),
+        Point::from_xy(40.0, 40.0),
+        Point::from_xy(0.0, 40.0),
+    ];
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_patch(&cubics(), Some(&colors()), Some(&tex_coords), BlendMode::Modulate,
+                      &paint, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/patch/colors-modulate-texture.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn destination_ignores_shader() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(0, 0, 0, 255);
+
+    let mut pixmap1 = Pixmap::new(100, 100).unwrap();
+    pixmap1.draw_patch(&cubics(), Some(&colors()), None, BlendMode::Destination,
+                       &paint, Transform::identity(), None);
+
+    let mut pixmap2 = Pixmap::new(100, 100).unwrap();
+    pixmap2.draw_patch(&cubics(), Some(&colors()), None, BlendMode::Destination,
+                       &Paint::default(), Transform::identity(), None);
+
+    assert_eq!(pixmap1, pixmap2);
+}
+
+#[test]
+fn without_colors_uses_shader() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_patch(&cubics(), None, None, BlendMode::Modulate,
+                      &paint, Transform::identity(), None);
+
+    // Patches are not anti-aliased, so the color is either fully there or not.
+    assert_eq!(pixmap.pixel(50, 50).unwrap(), ColorU8::from_rgba(50, 127, 150, 255).premultiply());
+    assert_eq!(pixmap.pixel(2, 2).unwrap(), PremultipliedColorU8::TRANSPARENT);
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0 || p.alpha() == 255));
+}
+
+#[test]
+fn uniform_colors() {
+    let color = Color::from_rgba8(50, 127, 150, 200);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_patch(&cubics(), Some(&[color; 4]), None, BlendMode::Destination,
+                      &Paint::default(), Transform::identity(), None);
+
+    let expected = color.premultiply().to_color_u8();
+    assert_eq!(pixmap.pixel(50, 50).unwrap(), expected);
+    assert_eq!(pixmap.pixel(20, 80).unwrap(), expected);
+}
+
+#[test]
+fn with_transform() {
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.draw_patch(&cubics(), Some(&colors()), None, BlendMode::Destination,
+                      &Paint::default(), Transform::from_row(1.5, 0.3, -0.3, 1.5, 40.0, 20.0), None);
+
+    let expected = Pixmap::load_png("tests/images/patch/with-transform.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn with_mask() {
+    let mut mask = Mask::new(100, 100).unwrap();
+    mask.fill_path(
+        &PathBuilder::from_circle(50.0, 50.0, 30.0).unwrap(),
+        FillRule::Winding,
+        true,
+        Transform::default(),
+    );
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_patch(&cubics(), Some(&colors()), None, BlendMode::Destination,
+                      &Paint::default(), Transform::identity(), Some(&mask));
+
+    let expected = Pixmap::load_png("tests/images/patch/with-mask.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn on_canvas() {
+    let mut pixmap1 = Pixmap::new(200, 200).unwrap();
+    pixmap1.draw_patch(&cubics(), Some(&colors()), None, BlendMode::Destination,
+                       &Paint::default(), Transform::from_scale(2.0, 2.0), None);
+
+    let mut pixmap2 = Pixmap::new(200, 200).unwrap();
+    let mut canvas = Canvas::new(pixmap2.as_mut());
+    canvas.scale(2.0, 2.0);
+    canvas.draw_patch(&cubics(), Some(&colors()), None, BlendMode::Destination, &Paint::default());
+
+    assert_eq!(pixmap1, pixmap2);
+}
+
+#[test]
+fn large_pixmap_is_tiled() {
+    // A straight patch with a horizontal gradient, crossing the 8191px tile boundary.
+    let cubics = [
+        Point::from_xy(8100.0, 0.0), Point::from_xy(8166.0, 0.0), Point::from_xy(8233.0, 0.0), Point::from_xy(8300.0, 0.0),
+        Point::from_xy(8300.0, 3.0), Point::from_xy(8300.0, 7.0), Point::from_xy(8300.0, 10.0),
+        Point::from_xy(8233.0, 10.0), Point::from_xy(8166.0, 10.0), Point::from_xy(8100.0, 10.0),
+        Point::from_xy(8100.0, 7.0), Point::from_xy(8100.0, 3.0),
+    ];
+    let red = Color::from_rgba8(255, 0, 0, 255);
+    let blue = Color::from_rgba8(0, 0, 255, 255);
+
+    let mut pixmap = Pixmap::new(8300, 10).unwrap();
+    pixmap.draw_patch(&cubics, Some(&[red, blue, blue, red]), None, BlendMode::Destination,
+                      &Paint::default(), Transform::identity(), None);
+
+    let before = pixmap.pixel(8190, 5).unwrap();
+    let after = pixmap.pixel(8192, 5).unwrap();
+    assert_eq!(before.alpha(), 255);
+    assert_eq!(after.alpha(), 255);
+    assert!(before.red() > after.red() && before.red() - after.red() <= 4);
+    assert!(after.blue() > before.blue() && after.blue() - before.blue() <= 4);
+}
@@ -278,3 +278,159 @@ fn tight_bounds_1() {
     assert_eq!(path.bounds(), Rect::from_ltrb(50.0, 45.0, 150.0, 135.0).unwrap());
     assert_eq!(tight_bounds, Rect::from_ltrb(50.0, 65.0, 150.0, 135.0).unwrap());
 }
+
+fn segments(path: &Path) -> String {
+    let s = format!("{:?}", path);
+    s["Path { segments: \"".len()..s.find("\", bounds").unwrap()].to_string()
+}
+
+fn assert_bounds(path: &Path, left: f32, top: f32, right: f32, bottom: f32) {
+    let bounds = path.bounds();
+    assert!((bounds.left() - left).abs() < 0.001, "{:?}", bounds);
+    assert!((bounds.top() - top).abs() < 0.001, "{:?}", bounds);
+    assert!((bounds.right() - right).abs() < 0.001, "{:?}", bounds);
+    assert!((bounds.bottom() - bottom).abs() < 0.001, "{:?}", bounds);
+}
+
+#[test]
+fn arc_to() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 50.0);
+    pb.arc_to(40.0, 40.0, 0.0, false, true, 90.0, 50.0);
+    let path = pb.finish().unwrap();
+
+    assert_bounds(&path, 10.0, 10.0, 90.0, 50.0);
+    assert_eq!(path.points().last(), Some(&Point::from_xy(90.0, 50.0)));
+}
+
+#[test]
+fn arc_to_large_arc() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(50.0, 10.0);
+    pb.arc_to(40.0, 40.0, 0.0, true, false, 90.0, 50.0);
+    let path = pb.finish().unwrap();
+
+    // Three quarters of a circle, counter-clockwise.
+    assert_bounds(&path, 10.0, 10.0, 90.0, 90.0);
+    assert_eq!(path.points().last(), Some(&Point::from_xy(90.0, 50.0)));
+}
+
+#[test]
+fn arc_to_small_radii() {
+    // Radii are too small and must be scaled up to 40.
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 50.0);
+    pb.arc_to(10.0, 10.0, 0.0, false, true, 90.0, 50.0);
+    let path1 = pb.finish().unwrap();
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 50.0);
+    pb.arc_to(40.0, 40.0, 0.0, false, true, 90.0, 50.0);
+    let path2 = pb.finish().unwrap();
+
+    assert_eq!(path1, path2);
+}
+
+#[test]
+fn arc_to_rotated() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 50.0);
+    pb.arc_to(40.0, 20.0, 90.0, false, true, 90.0, 50.0);
+    let path = pb.finish().unwrap();
+
+    // A vertical ellipse with radii scaled up to 80 and 40.
+    assert_bounds(&path, 10.0, -30.0, 90.0, 50.0);
+    assert_eq!(path.points().last(), Some(&Point::from_xy(90.0, 50.0)));
+}
+
+#[test]
+fn arc_to_zero_radius() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 50.0);
+    pb.arc_to(0.0, 40.0, 0.0, false, true, 90.0, 50.0);
+    let path = pb.finish().unwrap();
+    assert_eq!(segments(&path), "M 10 50 L 90 50");
+}
+
+#[test]
+fn tangent_arc_to() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.tangent_arc_to(100.0, 0.0, 100.0, 100.0, 20.0);
+    let path = pb.finish().unwrap();
+
+    assert_eq!(segments(&path),
+               "M 0 0 L 80 0 Q 83.97824 0 87.653656 1.5224091 Q 91.32907 3.0448182 94.14213 5.857864 \
+                Q 96.95516 8.670909 98.47758 12.34633 Q 99.999985 16.021751 100 20");
+}
+
+#[test]
+fn tangent_arc_to_collinear() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.tangent_arc_to(50.0, 0.0, 100.0, 0.0, 20.0);
+    let path = pb.finish().unwrap();
+    assert_eq!(segments(&path), "M 0 0 L 50 0");
+}
+
+#[test]
+fn arc_full_circle() {
+    let mut pb = PathBuilder::new();
+    pb.arc(50.0, 50.0, 40.0, 0.0, 360.0, false);
+    let path = pb.finish().unwrap();
+
+    assert_bounds(&path, 10.0, 10.0, 90.0, 90.0);
+    assert_eq!(path.points().first(), Some(&Point::from_xy(90.0, 50.0)));
+    assert_eq!(path.points().last(), Some(&Point::from_xy(90.0, 50.0)));
+    // Same as any other angle difference larger than 360.
+    let mut pb = PathBuilder::new();
+    pb.arc(50.0, 50.0, 40.0, 0.0, 400.0, false);
+    assert_eq!(pb.finish().unwrap(), path);
+}
+
+#[test]
+fn arc_zero_length() {
+    // Angles are taken modulo 360 degrees when the difference is negative.
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.arc(50.0, 50.0, 40.0, 360.0, 0.0, false);
+    let path = pb.finish().unwrap();
+    assert_eq!(segments(&path), "M 0 0 L 90 50");
+
+    // But not in the opposite direction.
+    let mut pb = PathBuilder::new();
+    pb.arc(50.0, 50.0, 40.0, 360.0, 0.0, true);
+    let path = pb.finish().unwrap();
+    assert_bounds(&path, 10.0, 10.0, 90.0, 90.0);
+}
+
+#[test]
+fn arc_ccw() {
+    let mut pb = PathBuilder::new();
+    pb.arc(50.0, 50.0, 40.0, 0.0, 90.0, true);
+    let path = pb.finish().unwrap();
+
+    // Three quarters of a circle.
+    assert_bounds(&path, 10.0, 10.0, 90.0, 90.0);
+    assert_eq!(path.points().first(), Some(&Point::from_xy(90.0, 50.0)));
+    assert_eq!(path.points().last(), Some(&Point::from_xy(50.0, 90.0)));
+}
+
+#[test]
+fn arc_after_line() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.arc(50.0, 50.0, 40.0, 0.0, 90.0, false);
+    let path = pb.finish().unwrap();
+
+    assert_eq!(segments(&path),
+               "M 0 0 L 90 50 Q 90 57.95649 86.95518 65.30733 Q 83.91036 72.65817 78.28427 78.28427 \
+                Q 72.65817 83.91036 65.30733 86.95517 Q 57.95649 90 50 90");
+}
+
+#[test]
+fn arc_negative_radius() {
+    let mut pb = PathBuilder::new();
+    pb.arc(50.0, 50.0, -40.0, 0.0, 90.0, false);
+    assert!(pb.is_empty());
+}
@@ -0,0 +1,88 @@
+use tiny_skia::*;
+
+fn star() -> Path {
+    let mut pb = PathBuilder::new();
+    pb.move_to(100.0, 20.0);
+    pb.line_to(147.0, 165.0);
+    pb.line_to(24.0, 75.0);
+    pb.line_to(176.0, 75.0);
+    pb.line_to(53.0, 165.0);
+    pb.close();
+    pb.finish().unwrap()
+}
+
+fn stroke(path: &Path) -> Pixmap {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let stroke = Stroke {
+        width: 6.0,
+        ..Stroke::default()
+    };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.stroke_path(path, &paint, &stroke, Transform::identity(), None);
+    pixmap
+}
+
+#[test]
+fn corner() {
+    let effect = PathEffect::corner(20.0).unwrap();
+    let path = star().apply_effect(&effect, 1.0).unwrap();
+    let pixmap = stroke(&path);
+
+    let expected = Pixmap::load_png("tests/images/path_effect/corner.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn discrete() {
+    let effect = PathEffect::discrete(8.0, 4.0, 7).unwrap();
+    let path = PathBuilder::from_circle(100.0, 100.0, 70.0).unwrap();
+    let path = path.apply_effect(&effect, 1.0).unwrap();
+    let pixmap = stroke(&path);
+
+    let expected = Pixmap::load_png("tests/images/path_effect/discrete.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn path_1d() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(-6.0, -6.0);
+    pb.line_to(6.0, 0.0);
+    pb.line_to(-6.0, 6.0);
+    pb.close();
+    let arrow = pb.finish().unwrap();
+
+    let effect = PathEffect::path_1d(arrow, 20.0, 0.0, Path1DStyle::Rotate).unwrap();
+    let path = PathBuilder::from_circle(100.0, 100.0, 70.0).unwrap();
+    let path = path.apply_effect(&effect, 1.0).unwrap();
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+
+    let expected = Pixmap::load_png("tests/images/path_effect/path-1d.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn trim_and_dash() {
+    let trim = PathEffect::trim(0.1, 0.7).unwrap();
+    let dash = PathEffect::dash(StrokeDash::new(vec![20.0, 10.0], 0.0).unwrap());
+    let effect = PathEffect::compose(dash, trim);
+    let path = star().apply_effect(&effect, 1.0).unwrap();
+    let pixmap = stroke(&path);
+
+    let expected = Pixmap::load_png("tests/images/path_effect/trim-and-dash.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
@@ -0,0 +1,165 @@
+use tiny_skia::*;
+
+fn segments(path: &Path) -> String {
+    let s = format!("{:?}", path);
+    s["Path { segments: \"".len()..s.find("\", bounds").unwrap()].to_string()
+}
+
+fn assert_point(p: Point, x: f32, y: f32) {
+    assert!((p.x - x).abs() < 0.01 && (p.y - y).abs() < 0.01, "{:?} != ({}, {})", p, x, y);
+}
+
+#[test]
+fn line() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 20.0);
+    pb.line_to(40.0, 60.0);
+    let path = pb.finish().unwrap();
+
+    let contours: Vec<_> = PathMeasure::new(&path, false, 1.0).collect();
+    assert_eq!(contours.len(), 1);
+    assert_eq!(contours[0].length(), 50.0);
+    assert!(!contours[0].is_closed());
+
+    let (pos, tan) = contours[0].pos_tan(25.0).unwrap();
+    assert_eq!(pos, Point::from_xy(25.0, 40.0));
+    assert_point(tan, 0.6, 0.8);
+}
+
+#[test]
+fn multiple_contours() {
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_xywh(0.0, 0.0, 10.0, 20.0).unwrap());
+    pb.move_to(50.0, 50.0);
+    pb.line_to(50.0, 50.0); // zero-length contour
+    pb.move_to(0.0, 0.0);
+    pb.line_to(0.0, 5.0);
+    let path = pb.finish().unwrap();
+
+    let lengths: Vec<_> = PathMeasure::new(&path, false, 1.0).map(|c| c.length()).collect();
+    assert_eq!(lengths, &[60.0, 5.0]);
+
+    let closed: Vec<_> = PathMeasure::new(&path, false, 1.0).map(|c| c.is_closed()).collect();
+    assert_eq!(closed, &[true, false]);
+}
+
+#[test]
+fn force_closed() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(30.0, 0.0);
+    pb.line_to(30.0, 40.0);
+    let path = pb.finish().unwrap();
+
+    let contour = PathMeasure::new(&path, false, 1.0).next().unwrap();
+    assert_eq!(contour.length(), 70.0);
+    assert!(!contour.is_closed());
+
+    let contour = PathMeasure::new(&path, true, 1.0).next().unwrap();
+    assert_eq!(contour.length(), 120.0);
+    assert!(contour.is_closed());
+
+    let (pos, tan) = contour.pos_tan(95.0).unwrap();
+    assert_eq!(pos, Point::from_xy(15.0, 20.0));
+    assert_point(tan, -0.6, -0.8);
+}
+
+#[test]
+fn circle() {
+    let path = PathBuilder::from_circle(100.0, 100.0, 50.0).unwrap();
+    let contour = PathMeasure::new(&path, false, 1.0).next().unwrap();
+    assert!((contour.length() - 2.0 * std::f32::consts::PI * 50.0).abs() < 0.5);
+    assert!(contour.is_closed());
+
+    // The circle starts at the rightmost point and goes clockwise.
+    let quarter = contour.length() / 4.0;
+    let (pos, tan) = contour.pos_tan(quarter).unwrap();
+    assert_point(pos, 100.0, 150.0);
+    assert_point(tan, -1.0, 0.0);
+
+    let (pos, tan) = contour.pos_tan(quarter * 2.5).unwrap();
+    let d = 50.0 * std::f32::consts::FRAC_1_SQRT_2;
+    assert_point(pos, 100.0 - d, 100.0 - d);
+    assert_point(tan, std::f32::consts::FRAC_1_SQRT_2, -std::f32::consts::FRAC_1_SQRT_2);
+}
+
+#[test]
+fn pos_tan_out_of_range() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(10.0, 0.0);
+    let path = pb.finish().unwrap();
+
+    let contour = PathMeasure::new(&path, false, 1.0).next().unwrap();
+    assert_eq!(contour.pos_tan(-5.0).unwrap().0, Point::from_xy(0.0, 0.0));
+    assert_eq!(contour.pos_tan(15.0).unwrap().0, Point::from_xy(10.0, 0.0));
+}
+
+#[test]
+fn segment() {
+    let path = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 10.0, 20.0).unwrap());
+    let contour = PathMeasure::new(&path, false, 1.0).next().unwrap();
+
+    assert_eq!(segments(&contour.segment(5.0, 25.0).unwrap()), "M 5 0 L 10 0 L 10 15");
+    assert_eq!(segments(&contour.segment(-10.0, 5.0).unwrap()), "M 0 0 L 5 0");
+    assert_eq!(segments(&contour.segment(50.0, 100.0).unwrap()), "M 0 10 L 0 0");
+    assert!(contour.segment(10.0, 5.0).is_none());
+}
+
+#[test]
+fn curve_segment() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.cubic_to(0.0, 50.0, 100.0, 50.0, 100.0, 0.0);
+    let path = pb.finish().unwrap();
+
+    let contour = PathMeasure::new(&path, false, 1.0).next().unwrap();
+    let half = contour.length() / 2.0;
+    let part = contour.segment(0.0, half).unwrap();
+    assert_eq!(part.points().len(), 4);
+    // The curve is symmetric, therefore it must end at its middle.
+    assert_point(part.points()[3], 50.0, 37.5);
+
+    let (pos, tan) = contour.pos_tan(half).unwrap();
+    assert_point(pos, 50.0, 37.5);
+    assert_point(tan, 1.0, 0.0);
+}
+
+#[test]
+fn push_segment() {
+    let path = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap());
+    let contour = PathMeasure::new(&path, false, 1.0).next().unwrap();
+
+    let mut pb = PathBuilder::new();
+    contour.push_segment(35.0, 40.0, true, &mut pb);
+    contour.push_segment(0.0, 5.0, false, &mut pb);
+    let path = pb.finish().unwrap();
+    assert_eq!(segments(&path), "M 0 5 L 0 0 L 5 0");
+}
+
+#[test]
+fn force_closed_to_completion() {
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_xywh(0.0, 0.0, 10.0, 20.0).unwrap());
+    pb.move_to(0.0, 0.0);
+    pb.line_to(30.0, 0.0);
+    pb.line_to(30.0, 40.0);
+    let path = pb.finish().unwrap();
+
+    let lengths: Vec<_> = PathMeasure::new(&path, true, 1.0).map(|c| c.length()).collect();
+    assert_eq!(lengths, &[60.0, 120.0]);
+}
+
+#[test]
+fn skip_non_finite_contour() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(3.0e38, 0.0);
+    pb.line_to(0.0, 0.0);
+    pb.move_to(0.0, 0.0);
+    pb.line_to(0.0, 5.0);
+    let path = pb.finish().unwrap();
+
+    let lengths: Vec<_> = PathMeasure::new(&path, false, 1.0).map(|c| c.length()).collect();
+    assert_eq!(lengths, &[5.0]);
+}
@@ -0,0 +1,263 @@
+use tiny_skia::*;
+
+fn rect(x: f32, y: f32, w: f32, h: f32) -> Path {
+    PathBuilder::from_rect(Rect::from_xywh(x, y, w, h).unwrap())
+}
+
+fn segments(path: &Path) -> String {
+    let s = format!("{:?}", path);
+    s["Path { segments: \"".len()..s.find("\", bounds").unwrap()].to_string()
+}
+
+fn contours(path: &Path) -> usize {
+    path.segments().filter(|s| matches!(s, PathSegment::MoveTo(_))).count()
+}
+
+fn op(a: &Path, b: &Path, op: PathOp) -> Option<Path> {
+    a.op(b, op, FillRule::Winding, FillRule::Winding, 1.0)
+}
+
+#[test]
+fn union() {
+    let a = rect(0.0, 0.0, 20.0, 20.0);
+    let b = rect(10.0, 10.0, 20.0, 20.0);
+    let path = op(&a, &b, PathOp::Union).unwrap();
+    assert_eq!(segments(&path), "M 0 0 L 20 0 L 20 10 L 30 10 L 30 30 L 10 30 L 10 20 L 0 20 Z");
+}
+
+#[test]
+fn intersect() {
+    let a = rect(0.0, 0.0, 20.0, 20.0);
+    let b = rect(10.0, 10.0, 20.0, 20.0);
+    let path = op(&a, &b, PathOp::Intersect).unwrap();
+    assert_eq!(segments(&path), "M 10 10 L 20 10 L 20 20 L 10 20 Z");
+}
+
+#[test]
+fn difference() {
+    let a = rect(0.0, 0.0, 20.0, 20.0);
+    let b = rect(10.0, 10.0, 20.0, 20.0);
+    let path = op(&a, &b, PathOp::Difference).unwrap();
+    assert_eq!(segments(&path), "M 0 0 L 20 0 L 20 10 L 10 10 L 10 20 L 0 20 Z");
+
+    let path = op(&a, &b, PathOp::ReverseDifference).unwrap();
+    assert_eq!(segments(&path), "M 20 10 L 30 10 L 30 30 L 10 30 L 10 20 L 20 20 Z");
+}
+
+#[test]
+fn xor() {
+    let a = rect(0.0, 0.0, 20.0, 20.0);
+    let b = rect(10.0, 10.0, 20.0, 20.0);
+    let path = op(&a, &b, PathOp::Xor).unwrap();
+    assert_eq!(segments(&path),
+               "M 0 0 L 20 0 L 20 10 L 10 10 L 10 20 L 0 20 Z \
+                M 20 10 L 30 10 L 30 30 L 10 30 L 10 20 L 20 20 Z");
+}
+
+#[test]
+fn hole() {
+    let a = rect(0.0, 0.0, 30.0, 30.0);
+    let b = rect(10.0, 10.0, 10.0, 10.0);
+    let path = op(&a, &b, PathOp::Difference).unwrap();
+    // The hole has an opposite direction.
+    assert_eq!(segments(&path), "M 0 0 L 30 0 L 30 30 L 0 30 Z M 10 10 L 10 20 L 20 20 L 20 10 Z");
+}
+
+#[test]
+fn shared_edges() {
+    let a = rect(0.0, 0.0, 10.0, 10.0);
+    let b = rect(10.0, 0.0, 10.0, 10.0);
+    let path = op(&a, &b, PathOp::Union).unwrap();
+    assert_eq!(segments(&path), "M 0 0 L 20 0 L 20 10 L 0 10 Z");
+
+    assert!(op(&a, &b, PathOp::Intersect).is_none());
+}
+
+#[test]
+fn disjoint() {
+    let a = rect(0.0, 0.0, 10.0, 10.0);
+    let b = rect(20.0, 20.0, 10.0, 10.0);
+    assert!(op(&a, &b, PathOp::Intersect).is_none());
+    assert_eq!(segments(&op(&a, &b, PathOp::Difference).unwrap()), "M 0 0 L 10 0 L 10 10 L 0 10 Z");
+    assert_eq!(segments(&op(&a, &b, PathOp::Union).unwrap()),
+               "M 0 0 L 10 0 L 10 10 L 0 10 Z M 20 20 L 30 20 L 30 30 L 20 30 Z");
+}
+
+#[test]
+fn identical() {
+    let a = rect(0.0, 0.0, 10.0, 10.0);
+    assert_eq!(segments(&op(&a, &a, PathOp::Union).unwrap()), "M 0 0 L 10 0 L 10 10 L 0 10 Z");
+    assert_eq!(segments(&op(&a, &a, PathOp::Intersect).unwrap()), "M 0 0 L 10 0 L 10 10 L 0 10 Z");
+    assert!(op(&a, &a, PathOp::Difference).is_none());
+    assert!(op(&a, &a, PathOp::Xor).is_none());
+}
+
+fn star() -> Path {
+    let mut pb = PathBuilder::new();
+    pb.move_to(50.0, 7.0);
+    pb.line_to(75.0, 93.0);
+    pb.line_to(7.0, 38.0);
+    pb.line_to(93.0, 38.0);
+    pb.line_to(25.0, 93.0);
+    pb.close();
+    pb.finish().unwrap()
+}
+
+#[test]
+fn simplify_winding() {
+    let path = star().simplify(FillRule::Winding, 1.0).unwrap();
+    // A star outline without self-intersections.
+    assert_eq!(segments(&path),
+               "M 50 7 L 59.011627 38 L 93 38 L 65.4818 60.25737 L 75 93 L 50 72.77941 \
+                L 25 93 L 34.518204 60.25737 L 7 38 L 40.988373 38 Z");
+}
+
+#[test]
+fn simplify_even_odd() {
+    let path = star().simplify(FillRule::EvenOdd, 1.0).unwrap();
+    // Five triangles, without the center.
+    assert_eq!(contours(&path), 5);
+}
+
+#[test]
+fn simplify_reversed_contours() {
+    // Two overlapping rectangles with an opposite direction.
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_xywh(0.0, 0.0, 20.0, 20.0).unwrap());
+    pb.move_to(10.0, 10.0);
+    pb.line_to(10.0, 30.0);
+    pb.line_to(30.0, 30.0);
+    pb.line_to(30.0, 10.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    // Overlapping area has a zero winding.
+    let winding = path.simplify(FillRule::Winding, 1.0).unwrap();
+    let even_odd = path.simplify(FillRule::EvenOdd, 1.0).unwrap();
+    assert_eq!(winding, even_odd);
+    assert_eq!(contours(&winding), 2);
+}
+
+#[test]
+fn simplify_zero_area() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(10.0, 10.0);
+    pb.line_to(20.0, 20.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+    assert!(path.simplify(FillRule::Winding, 1.0).is_none());
+}
+
+fn fill(path: &Path, fill_rule: FillRule) -> Pixmap {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(path, &paint, fill_rule, Transform::identity(), None);
+    pixmap
+}
+
+#[test]
+fn curves() {
+    let a = PathBuilder::from_circle(80.0, 100.0, 60.0).unwrap();
+    let b = {
+        let mut pb = PathBuilder::new();
+        pb.move_to(70.0, 30.0);
+        pb.quad_to(200.0, 30.0, 180.0, 170.0);
+        pb.cubic_to(130.0, 210.0, 100.0, 80.0, 70.0, 170.0);
+        pb.close();
+        pb.finish().unwrap()
+    };
+
+    let path = op(&a, &b, PathOp::Xor).unwrap();
+    // Must be the same for any fill rule.
+    assert_eq!(fill(&path, FillRule::Winding), fill(&path, FillRule::EvenOdd));
+
+    let pixmap = fill(&path, FillRule::Winding);
+    let expected = Pixmap::load_png("tests/images/path-ops/curves.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn even_odd_operand() {
+    let mut pb = PathBuilder::new();
+    pb.push_circle(100.0, 100.0, 80.0);
+    pb.push_circle(100.0, 100.0, 40.0);
+    let a = pb.finish().unwrap();
+    let b = rect(90.0, 0.0, 20.0, 200.0);
+
+    let path = a.op(&b, PathOp::Difference, FillRule::EvenOdd, FillRule::Winding, 1.0).unwrap();
+    let pixmap = fill(&path, FillRule::Winding);
+    let expected = Pixmap::load_png("tests/images/path-ops/even-odd-operand.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn resolution_scale() {
+    let circle = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    let path1 = circle.simplify(FillRule::Winding, 1.0).unwrap();
+    let path2 = circle.simplify(FillRule::Winding, 10.0).unwrap();
+
+    // A scaled up circle must be flattened using more segments.
+    assert!(path2.len() > path1.len() * 2);
+    assert!(circle.simplify(FillRule::Winding, 0.0).is_none());
+}
+
+// Counts segments crossing each other, not counting touching ones.
+fn crossings(path: &Path) -> usize {
+    let mut lines = Vec::new();
+    let (mut start, mut last) = (Point::zero(), Point::zero());
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => { start = p; last = p; }
+            PathSegment::LineTo(p) => { lines.push((last, p)); last = p; }
+            PathSegment::Close => { lines.push((last, start)); last = start; }
+            _ => unreachable!(),
+        }
+    }
+
+    let side = |a: Point, b: Point, p: Point| {
+        f64::from(b.x - a.x) * f64::from(p.y - a.y) - f64::from(b.y - a.y) * f64::from(p.x - a.x)
+    };
+
+    let mut count = 0;
+    for (i, (a0, a1)) in lines.iter().enumerate() {
+        for (b0, b1) in &lines[i + 1..] {
+            if side(*b0, *b1, *a0) * side(*b0, *b1, *a1) < 0.0
+                && side(*a0, *a1, *b0) * side(*a0, *a1, *b1) < 0.0 {
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+#[test]
+fn no_crossings_after_snapping() {
+    // A far away point makes the snapping grid coarse enough
+    // for snapped intersection points to produce new crossings.
+    let points = [
+        (6.7580075, 11.187374), (64.53965, 8.108158), (54.676323, 45.089516), (72.10578, 89.013756),
+        (80.20594, 48.228176), (63.544254, 78.42857), (62.836826, 38.578373), (40.243195, 71.8939),
+        (95.8009, 69.84461), (18.233694, 33.062855), (92.051735, 96.221176), (64.44063, 55.955727),
+        (63.21307, 61.94072), (96.42974, 97.46659), (36.43368, 89.513214), (31.46545, 34.833508),
+        (21.288399, 89.600494), (99.32135, 64.27659), (52.934223, 62.01547), (50.67352, 58.055275),
+        (28.729275, 81.18706), (10.42858, 92.515045), (32.143482, 3.594026), (60.41332, 20.852154),
+        (36.263023, 93.86088), (71.15519, 74.77801), (49.002434, 86.86416), (22.716522, 62.202614),
+        (27.031834, 0.52483845), (86.276474, 29.047874), (43.442146, 50.666203), (1000000.0, 50.0),
+    ];
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(points[0].0, points[0].1);
+    for (x, y) in &points[1..] {
+        pb.line_to(*x, *y);
+    }
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    let simplified = path.simplify(FillRule::EvenOdd, 1.0).unwrap();
+    assert_eq!(crossings(&simplified), 0);
+}
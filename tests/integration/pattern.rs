@@ -251,3 +251,112 @@ fn filter_bicubic() {
     let expected = Pixmap::load_png("tests/images/pattern/filter-bicubic.png").unwrap();
     assert_eq!(pixmap, expected);
 }
+
+#[test]
+fn filter_trilinear() {
+    let triangle = crate_triangle();
+
+    let paint = Paint {
+        shader: Pattern::new(
+            triangle.as_ref(),
+            SpreadMode::Repeat,
+            FilterQuality::Trilinear,
+            1.0,
+            Transform::from_row(0.3, 0.0, -0.1, -0.2, 5.0, 1.0),
+        ),
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/pattern/filter-trilinear.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn trilinear_prebuilt_mipmaps() {
+    let triangle = crate_triangle();
+    let mipmaps = Mipmaps::new(triangle.as_ref()).unwrap();
+    assert_eq!(mipmaps.pixmap(), triangle.as_ref());
+
+    let paint = Paint {
+        shader: Pattern::new_with_mipmaps(
+            &mipmaps,
+            SpreadMode::Repeat,
+            1.0,
+            Transform::from_row(0.3, 0.0, -0.1, -0.2, 5.0, 1.0),
+        ),
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let path = PathBuilder::from_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/pattern/filter-trilinear.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+// Mipmaps are not used for magnification.
+#[test]
+fn trilinear_upscale() {
+    let triangle = crate_triangle();
+
+    let render = |quality| {
+        let paint = Paint {
+            shader: Pattern::new(
+                triangle.as_ref(),
+                SpreadMode::Repeat,
+                quality,
+                1.0,
+                Transform::from_row(1.5, 0.0, -0.4, -0.8, 5.0, 1.0),
+            ),
+            anti_alias: false,
+            ..Paint::default()
+        };
+
+        let mut pixmap = Pixmap::new(200, 200).unwrap();
+        pixmap.fill_rect(Rect::from_ltrb(10.0, 10.0, 190.0, 190.0).unwrap(), &paint, Transform::identity(), None);
+        pixmap
+    };
+
+    assert_eq!(render(FilterQuality::Trilinear), render(FilterQuality::Bilinear));
+}
+
+#[test]
+fn trilinear_downscale_checkerboard() {
+    let mut checkerboard = Pixmap::new(64, 64).unwrap();
+    for (i, pixel) in checkerboard.pixels_mut().iter_mut().enumerate() {
+        if (i % 64 + i / 64) % 2 == 0 {
+            *pixel = PremultipliedColorU8::from_rgba(255, 255, 255, 255).unwrap();
+        } else {
+            *pixel = PremultipliedColorU8::from_rgba(0, 0, 0, 255).unwrap();
+        }
+    }
+
+    let render = |quality| {
+        let mut pixmap = Pixmap::new(40, 40).unwrap();
+        let paint = PixmapPaint { quality, ..PixmapPaint::default() };
+        pixmap.draw_pixmap(0, 0, checkerboard.as_ref(), &paint, Transform::from_scale(0.137, 0.137), None);
+        pixmap
+    };
+
+    // Without mipmaps, a downscaled checkerboard turns into a moiré pattern,
+    // while with them it's just gray.
+    let pixmap = render(FilterQuality::Trilinear);
+    for y in 1..8 {
+        for x in 1..8 {
+            let c = pixmap.pixel(x, y).unwrap();
+            assert!((120..=135).contains(&c.red()), "{:?} at {}x{}", c, x, y);
+        }
+    }
+
+    let pixmap = render(FilterQuality::Bilinear);
+    assert!(pixmap.pixels()[..8].iter().any(|c| !(120..=135).contains(&c.red())));
+}
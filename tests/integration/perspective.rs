@@ -0,0 +1,305 @@
+use tiny_skia::*;
+
+fn card() -> [Point; 4] {
+    [
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(100.0, 0.0),
+        Point::from_xy(100.0, 100.0),
+        Point::from_xy(0.0, 100.0),
+    ]
+}
+
+// A card rotated around the vertical axis.
+fn tilted() -> PerspectiveTransform {
+    PerspectiveTransform::from_quad_to_quad(&card(), &[
+        Point::from_xy(30.0, 20.0),
+        Point::from_xy(170.0, 50.0),
+        Point::from_xy(170.0, 150.0),
+        Point::from_xy(30.0, 180.0),
+    ]).unwrap()
+}
+
+fn assert_point(p: Point, x: f32, y: f32) {
+    assert!((p.x - x).abs() < 0.01 && (p.y - y).abs() < 0.01, "{:?} != ({}, {})", p, x, y);
+}
+
+#[test]
+fn quad_to_quad() {
+    let ts = tilted();
+    assert!(!ts.is_affine());
+
+    let mut points = card();
+    ts.map_points(&mut points);
+    assert_point(points[0], 30.0, 20.0);
+    assert_point(points[1], 170.0, 50.0);
+    assert_point(points[2], 170.0, 150.0);
+    assert_point(points[3], 30.0, 180.0);
+
+    // The center is shifted towards the far edge.
+    let mut center = Point::from_xy(50.0, 50.0);
+    ts.map_point(&mut center);
+    assert!(center.x > 100.0);
+    assert!((center.y - 100.0).abs() < 0.01);
+}
+
+#[test]
+fn quad_to_quad_affine() {
+    let dst = card().map(|p| Point::from_xy(p.x * 2.0 + 10.0, p.y + 5.0));
+    let ts = PerspectiveTransform::from_quad_to_quad(&card(), &dst).unwrap();
+    assert_eq!(ts.to_affine(), Some(Transform::from_row(2.0, 0.0, 0.0, 1.0, 10.0, 5.0)));
+}
+
+#[test]
+fn quad_to_quad_degenerate() {
+    let dst = [
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(50.0, 0.0),
+        Point::from_xy(100.0, 0.0),
+        Point::from_xy(0.0, 100.0),
+    ];
+    assert!(PerspectiveTransform::from_quad_to_quad(&card(), &dst).is_none());
+}
+
+#[test]
+fn invert() {
+    let ts = tilted();
+    let inv = ts.invert().unwrap();
+    let mut p = Point::from_xy(170.0, 150.0);
+    inv.map_point(&mut p);
+    assert_point(p, 100.0, 100.0);
+}
+
+#[test]
+fn concat() {
+    let ts = PerspectiveTransform::from(Transform::from_translate(10.0, 20.0)).post_concat(tilted());
+    let mut p = Point::from_xy(-10.0, -20.0);
+    ts.map_point(&mut p);
+    assert_point(p, 30.0, 20.0);
+}
+
+#[test]
+fn path_affine() {
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    let ts = Transform::from_row(1.5, 0.3, -0.2, 0.8, 10.0, 20.0);
+    assert_eq!(path.clone().transform_perspective(ts.into()), path.transform(ts));
+}
+
+#[test]
+fn path_lines() {
+    let path = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+    let path = path.transform_perspective(tilted()).unwrap();
+    assert_eq!(path.points().len(), 4);
+    let bounds = path.bounds();
+    assert_point(Point::from_xy(bounds.left(), bounds.top()), 30.0, 20.0);
+    assert_point(Point::from_xy(bounds.right(), bounds.bottom()), 170.0, 180.0);
+}
+
+#[test]
+fn path_curves() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 50.0);
+    pb.quad_to(50.0, 0.0, 100.0, 50.0);
+    pb.cubic_to(70.0, 100.0, 30.0, 100.0, 0.0, 50.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    let ts = tilted();
+    let projected = path.transform_perspective(ts).unwrap();
+
+    // Every segment end must be mapped exactly.
+    let mut start = Point::from_xy(0.0, 50.0);
+    let mut quad_end = Point::from_xy(100.0, 50.0);
+    ts.map_point(&mut start);
+    ts.map_point(&mut quad_end);
+    let points = projected.points();
+    assert_point(points[0], start.x, start.y);
+    assert!(points.iter().any(|p| (p.x - quad_end.x).abs() < 0.01 && (p.y - quad_end.y).abs() < 0.01));
+    assert_point(*points.last().unwrap(), start.x, start.y);
+
+    // The cubic is subdivided.
+    assert!(projected.segments().filter(|s| matches!(s, PathSegment::CubicTo(..))).count() > 1);
+}
+
+#[test]
+fn path_behind_viewer() {
+    // `w` becomes negative at x > 100.
+    let ts = PerspectiveTransform::from_row(1.0, 0.0, 0.0, 1.0, 0.0, 0.0, -0.01, 0.0, 1.0);
+    let path = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 150.0, 50.0).unwrap());
+    let projected = path.transform_perspective(ts).unwrap();
+
+    // The part behind the viewer is clipped away and the contour stays closed.
+    assert!(projected.points().iter().all(|p| p.is_finite() && p.x >= 0.0));
+    assert_eq!(projected.points().len(), 4);
+    assert_eq!(projected.segments().filter(|s| matches!(s, PathSegment::Close)).count(), 1);
+    assert_point(projected.points()[0], 0.0, 0.0);
+    assert_point(projected.points()[3], 0.0, 50.0);
+
+    let path = PathBuilder::from_rect(Rect::from_xywh(110.0, 0.0, 40.0, 50.0).unwrap());
+    assert!(path.transform_perspective(ts).is_none());
+}
+
+#[test]
+fn fill_path_partially_behind_viewer() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let ts = PerspectiveTransform::from_row(1.0, 0.0, 0.0, 1.0, 0.0, 0.0, -0.01, 0.0, 1.0);
+    let path = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 150.0, 50.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path_perspective(&path, &paint, FillRule::Winding, ts, None);
+
+    let color = ColorU8::from_rgba(50, 127, 150, 255).premultiply();
+    assert_eq!(pixmap.pixel(10, 10).unwrap(), color);
+    assert_eq!(pixmap.pixel(190, 40).unwrap(), color);
+    assert_eq!(pixmap.pixel(10, 190).unwrap(), PremultipliedColorU8::TRANSPARENT);
+}
+
+#[test]
+fn curves_behind_viewer() {
+    let ts = PerspectiveTransform::from_row(1.0, 0.0, 0.0, 1.0, 0.0, 0.0, -0.01, 0.0, 1.0);
+    let path = PathBuilder::from_circle(100.0, 50.0, 30.0).unwrap();
+    let projected = path.transform_perspective(ts).unwrap();
+    assert!(projected.points().iter().all(|p| p.is_finite()));
+
+    // The leftmost point is in front of the viewer and is preserved.
+    let mut left = Point::from_xy(70.0, 50.0);
+    ts.map_point(&mut left);
+    assert!((projected.bounds().left() - left.x).abs() < 0.01);
+
+    // An open contour is split instead of being closed.
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(150.0, 0.0);
+    pb.line_to(0.0, 50.0);
+    let projected = pb.finish().unwrap().transform_perspective(ts).unwrap();
+    assert_eq!(projected.segments().filter(|s| matches!(s, PathSegment::MoveTo(..))).count(), 2);
+    assert_eq!(projected.segments().filter(|s| matches!(s, PathSegment::Close)).count(), 0);
+}
+
+#[test]
+fn fill_path() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let mut pb = PathBuilder::new();
+    pb.push_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+    pb.push_circle(50.0, 50.0, 30.0);
+    let path = pb.finish().unwrap();
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path_perspective(&path, &paint, FillRule::EvenOdd, tilted(), None);
+
+    let expected = Pixmap::load_png("tests/images/perspective/fill-path.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn fill_path_affine() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    let ts = Transform::from_row(1.5, 0.3, -0.2, 0.8, 10.0, 20.0);
+
+    let mut pixmap1 = Pixmap::new(200, 200).unwrap();
+    pixmap1.fill_path(&path, &paint, FillRule::Winding, ts, None);
+
+    let mut pixmap2 = Pixmap::new(200, 200).unwrap();
+    pixmap2.fill_path_perspective(&path, &paint, FillRule::Winding, ts.into(), None);
+
+    assert_eq!(pixmap1, pixmap2);
+}
+
+#[test]
+fn stroke_path() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = true;
+
+    let path = PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap();
+    let stroke = Stroke { width: 10.0, ..Stroke::default() };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.stroke_path_perspective(&path, &paint, &stroke, tilted(), None);
+
+    let expected = Pixmap::load_png("tests/images/perspective/stroke-path.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn linear_gradient() {
+    let paint = Paint {
+        shader: LinearGradient::new(
+            Point::from_xy(0.0, 0.0),
+            Point::from_xy(100.0, 0.0),
+            vec![
+                GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 255)),
+                GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 255)),
+            ],
+            SpreadMode::Repeat,
+            Transform::from_scale(0.25, 1.0),
+        ).unwrap(),
+        anti_alias: true,
+        ..Paint::default()
+    };
+
+    let path = PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_path_perspective(&path, &paint, FillRule::Winding, tilted(), None);
+
+    let expected = Pixmap::load_png("tests/images/perspective/linear-gradient.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+fn checkerboard() -> Pixmap {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill(Color::WHITE);
+    for y in 0..5 {
+        for x in 0..5 {
+            if (x + y) % 2 == 0 {
+                let rect = Rect::from_xywh(x as f32 * 20.0, y as f32 * 20.0, 20.0, 20.0).unwrap();
+                pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+            }
+        }
+    }
+
+    pixmap
+}
+
+#[test]
+fn draw_pixmap() {
+    let card = checkerboard();
+
+    let paint = PixmapPaint {
+        quality: FilterQuality::Bilinear,
+        ..PixmapPaint::default()
+    };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.draw_pixmap_perspective(0, 0, card.as_ref(), &paint, tilted(), None);
+
+    let expected = Pixmap::load_png("tests/images/perspective/draw-pixmap.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn draw_pixmap_affine() {
+    let card = checkerboard();
+    let paint = PixmapPaint::default();
+    let ts = Transform::from_row(1.2, 0.5, 0.5, 1.2, 0.0, 0.0);
+
+    let mut pixmap1 = Pixmap::new(200, 200).unwrap();
+    pixmap1.draw_pixmap(10, 10, card.as_ref(), &paint, ts, None);
+
+    let mut pixmap2 = Pixmap::new(200, 200).unwrap();
+    pixmap2.draw_pixmap_perspective(10, 10, card.as_ref(), &paint, ts.into(), None);
+
+    assert_eq!(pixmap1, pixmap2);
+}
@@ -0,0 +1,443 @@
+use tiny_skia::*;
+
+fn checkerboard() -> Pixmap {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let mut pixmap = Pixmap::new(20, 20).unwrap();
+    pixmap.fill(Color::WHITE);
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap(), &paint, Transform::identity(), None);
+    pixmap.fill_rect(Rect::from_xywh(10.0, 10.0, 10.0, 10.0).unwrap(), &paint, Transform::identity(), None);
+    pixmap
+}
+
+fn circle_mask(size: u32) -> Mask {
+    let mut mask = Mask::new(size, size).unwrap();
+    let r = size as f32 / 2.0;
+    mask.fill_path(&PathBuilder::from_circle(r, r, r * 0.8).unwrap(), FillRule::Winding, true, Transform::identity());
+    mask
+}
+
+// Issues the same draw calls either to a pixmap or to a recorder.
+trait Target {
+    fn fill_rect(&mut self, rect: Rect, paint: &Paint, transform: Transform, mask: Option<&Mask>);
+    fn fill_path(&mut self, path: &Path, paint: &Paint, fill_rule: FillRule, transform: Transform, mask: Option<&Mask>);
+    fn stroke_path(&mut self, path: &Path, paint: &Paint, stroke: &Stroke, transform: Transform, mask: Option<&Mask>);
+    fn draw_pixmap(&mut self, x: i32, y: i32, pixmap: PixmapRef, paint: &PixmapPaint, transform: Transform, mask: Option<&Mask>);
+}
+
+impl Target for Pixmap {
+    fn fill_rect(&mut self, rect: Rect, paint: &Paint, transform: Transform, mask: Option<&Mask>) {
+        Pixmap::fill_rect(self, rect, paint, transform, mask)
+    }
+
+    fn fill_path(&mut self, path: &Path, paint: &Paint, fill_rule: FillRule, transform: Transform, mask: Option<&Mask>) {
+        Pixmap::fill_path(self, path, paint, fill_rule, transform, mask)
+    }
+
+    fn stroke_path(&mut self, path: &Path, paint: &Paint, stroke: &Stroke, transform: Transform, mask: Option<&Mask>) {
+        Pixmap::stroke_path(self, path, paint, stroke, transform, mask)
+    }
+
+    fn draw_pixmap(&mut self, x: i32, y: i32, pixmap: PixmapRef, paint: &PixmapPaint, transform: Transform, mask: Option<&Mask>) {
+        Pixmap::draw_pixmap(self, x, y, pixmap, paint, transform, mask)
+    }
+}
+
+impl Target for PictureRecorder {
+    fn fill_rect(&mut self, rect: Rect, paint: &Paint, transform: Transform, mask: Option<&Mask>) {
+        PictureRecorder::fill_rect(self, rect, paint, transform, mask)
+    }
+
+    fn fill_path(&mut self, path: &Path, paint: &Paint, fill_rule: FillRule, transform: Transform, mask: Option<&Mask>) {
+        PictureRecorder::fill_path(self, path, paint, fill_rule, transform, mask)
+    }
+
+    fn stroke_path(&mut self, path: &Path, paint: &Paint, stroke: &Stroke, transform: Transform, mask: Option<&Mask>) {
+        PictureRecorder::stroke_path(self, path, paint, stroke, transform, mask)
+    }
+
+    fn draw_pixmap(&mut self, x: i32, y: i32, pixmap: PixmapRef, paint: &PixmapPaint, transform: Transform, mask: Option<&Mask>) {
+        PictureRecorder::draw_pixmap(self, x, y, pixmap, paint, transform, mask)
+    }
+}
+
+fn draw(target: &mut dyn Target, ts: Transform, mask: Option<&Mask>) {
+    let image = checkerboard();
+
+    let paint = Paint {
+        shader: Shader::SolidColor(Color::from_rgba8(220, 140, 75, 255)),
+        ..Paint::default()
+    };
+    target.fill_rect(Rect::from_xywh(5.0, 5.0, 90.0, 90.0).unwrap(), &paint, ts, None);
+
+    let paint = Paint {
+        shader: LinearGradient::new(
+            Point::from_xy(10.0, 10.0),
+            Point::from_xy(90.0, 90.0),
+            vec![
+                GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+                GradientStop::new(1.0, Color::from_rgba8(0, 0, 0, 255)),
+            ],
+            SpreadMode::Pad,
+            Transform::identity(),
+        ).unwrap(),
+        ..Paint::default()
+    };
+    let path = PathBuilder::from_circle(50.0, 50.0, 35.0).unwrap();
+    target.fill_path(&path, &paint, FillRule::Winding, ts, mask);
+
+    let paint = Paint {
+        shader: Pattern::new(image.as_ref(), SpreadMode::Repeat, FilterQuality::Bilinear, 0.8, Transform::identity()),
+        blend_mode: BlendMode::Multiply,
+        ..Paint::default()
+    };
+    let stroke = Stroke { width: 6.0, ..Stroke::default() };
+    target.stroke_path(&path, &paint, &stroke, ts, None);
+
+    target.draw_pixmap(40, 40, image.as_ref(), &PixmapPaint::default(), ts.pre_rotate_at(30.0, 50.0, 50.0), mask);
+}
+
+fn record(mask: Option<&Mask>) -> Picture {
+    let mut recorder = PictureRecorder::new(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+    draw(&mut recorder, Transform::identity(), mask);
+    recorder.finish()
+}
+
+#[test]
+fn playback() {
+    let mask = circle_mask(100);
+    let picture = record(Some(&mask));
+    assert_eq!(picture.len(), 4);
+
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    draw(&mut expected, Transform::identity(), Some(&mask));
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_picture(&picture, Transform::identity());
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn playback_with_transform() {
+    let picture = record(None);
+    let ts = Transform::from_row(2.0, 0.2, -0.3, 1.5, 20.0, 10.0);
+
+    let mut expected = Pixmap::new(250, 200).unwrap();
+    draw(&mut expected, ts, None);
+
+    let mut pixmap = Pixmap::new(250, 200).unwrap();
+    pixmap.draw_picture(&picture, ts);
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn apply_mask() {
+    let mask = circle_mask(100);
+    let mut recorder = PictureRecorder::new(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+    recorder.fill_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(), &Paint::default(), Transform::identity(), None);
+    recorder.apply_mask(&mask);
+    let picture = recorder.finish();
+
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    expected.fill_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(), &Paint::default(), Transform::identity(), None);
+    expected.apply_mask(&mask);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_picture(&picture, Transform::identity());
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn playback_with_translated_mask() {
+    let mask = circle_mask(100);
+    let picture = record(Some(&mask));
+    let ts = Transform::from_translate(20.0, 30.0);
+
+    let mut translated_mask = Mask::new(150, 150).unwrap();
+    translated_mask.fill_path(&PathBuilder::from_circle(50.0, 50.0, 40.0).unwrap(), FillRule::Winding, true, ts);
+
+    let mut expected = Pixmap::new(150, 150).unwrap();
+    draw(&mut expected, ts, Some(&translated_mask));
+
+    let mut pixmap = Pixmap::new(150, 150).unwrap();
+    pixmap.draw_picture(&picture, ts);
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn playback_with_scaled_mask() {
+    let mask = circle_mask(100);
+    let mut recorder = PictureRecorder::new(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+    recorder.fill_rect(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(), &Paint::default(), Transform::identity(), Some(&mask));
+    recorder.apply_mask(&mask);
+    let picture = recorder.finish();
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.draw_picture(&picture, Transform::from_scale(2.0, 2.0));
+
+    // The mask circle is scaled to a radius of 80.
+    assert_eq!(pixmap.pixel(100, 100).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(100, 175).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(100, 190).unwrap().alpha(), 0);
+    assert_eq!(pixmap.pixel(10, 10).unwrap().alpha(), 0);
+}
+
+#[test]
+fn record_deduplicates_images() {
+    let image = checkerboard();
+    let mut other_image = checkerboard();
+    other_image.fill(Color::BLACK);
+    let mask = circle_mask(100);
+
+    let record = |images: &[&Pixmap]| {
+        let mut recorder = PictureRecorder::new(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+        for image in images {
+            recorder.draw_pixmap(0, 0, image.as_ref(), &PixmapPaint::default(), Transform::identity(), Some(&mask));
+        }
+        recorder.finish().encode().len()
+    };
+
+    // An image and a mask are stored only once, unless the content is different.
+    let image_len = image.data().len();
+    assert!(record(&[&image, &image.clone(), &image]) < record(&[&image]) + image_len);
+    assert!(record(&[&image, &other_image]) >= record(&[&image]) + image_len);
+}
+
+#[test]
+fn playback_trilinear() {
+    let image = checkerboard();
+    let mipmaps = Mipmaps::new(image.as_ref()).unwrap();
+
+    let draw = |target: &mut dyn Target| {
+        let paint = Paint {
+            shader: Pattern::new_with_mipmaps(&mipmaps, SpreadMode::Repeat, 1.0, Transform::from_scale(0.3, 0.3)),
+            ..Paint::default()
+        };
+        target.fill_rect(Rect::from_xywh(0.0, 0.0, 50.0, 50.0).unwrap(), &paint, Transform::identity(), None);
+
+        let paint = PixmapPaint { quality: FilterQuality::Trilinear, ..PixmapPaint::default() };
+        target.draw_pixmap(50, 50, image.as_ref(), &paint, Transform::from_scale(0.4, 0.3), None);
+    };
+
+    let mut recorder = PictureRecorder::new(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+    draw(&mut recorder);
+    let picture = recorder.finish();
+
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    draw(&mut expected);
+
+    for picture in [picture.clone(), Picture::decode(&picture.encode()).unwrap()] {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        pixmap.draw_picture(&picture, Transform::identity());
+        assert_eq!(pixmap, expected);
+    }
+}
+
+#[test]
+fn cull_on_record() {
+    let mut recorder = PictureRecorder::new(Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap());
+    let paint = Paint::default();
+
+    // Outside.
+    recorder.fill_rect(Rect::from_xywh(120.0, 0.0, 10.0, 10.0).unwrap(), &paint, Transform::identity(), None);
+    recorder.fill_rect(Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap(), &paint, Transform::from_translate(0.0, -20.0), None);
+    // Partially inside.
+    recorder.fill_rect(Rect::from_xywh(95.0, 95.0, 10.0, 10.0).unwrap(), &paint, Transform::identity(), None);
+    // Moved inside by a transform.
+    recorder.fill_rect(Rect::from_xywh(120.0, 0.0, 10.0, 10.0).unwrap(), &paint, Transform::from_translate(-50.0, 0.0), None);
+
+    // A thick stroke reaches the cull rect.
+    let path = PathBuilder::from_rect(Rect::from_xywh(104.0, 0.0, 10.0, 10.0).unwrap());
+    let stroke = Stroke { width: 10.0, ..Stroke::default() };
+    recorder.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    let stroke = Stroke { width: 1.0, ..Stroke::default() };
+    recorder.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+
+    assert_eq!(recorder.finish().len(), 3);
+}
+
+#[test]
+fn cull_on_playback() {
+    let picture = record(None);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_picture(&picture, Transform::from_translate(150.0, 0.0));
+    pixmap.draw_picture(&picture, Transform::from_translate(0.0, -150.0));
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0));
+
+    // Partially visible.
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    draw(&mut expected, Transform::from_translate(-60.0, 70.0), None);
+    pixmap.draw_picture(&picture, Transform::from_translate(-60.0, 70.0));
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn playback_on_threads() {
+    let picture = record(None);
+
+    let mut expected = Pixmap::new(200, 200).unwrap();
+    draw(&mut expected, Transform::from_scale(2.0, 2.0), None);
+
+    std::thread::scope(|s| {
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut pixmap = Pixmap::new(200, 200).unwrap();
+                    pixmap.draw_picture(&picture, Transform::from_scale(2.0, 2.0));
+                    pixmap
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    });
+}
+
+fn all_features() -> Picture {
+    let image = checkerboard();
+    let mask = circle_mask(30);
+    let rect = Rect::from_xywh(0.0, 0.0, 30.0, 30.0).unwrap();
+    let stops = vec![
+        GradientStop::new(0.2, Color::from_rgba8(50, 127, 150, 200)),
+        GradientStop::new(0.5, Color::from_rgba8(220, 140, 75, 255)),
+        GradientStop::new(0.7, Color::from_rgba8(0, 0, 0, 255)),
+    ];
+
+    let shaders = [
+        Shader::SolidColor(Color::from_rgba8(50, 127, 150, 200)),
+        LinearGradient::new(Point::from_xy(0.0, 0.0), Point::from_xy(30.0, 20.0), stops.clone(), SpreadMode::Pad, Transform::identity()).unwrap(),
+        RadialGradient::new(Point::from_xy(15.0, 15.0), Point::from_xy(15.0, 15.0), 12.0, stops.clone(), SpreadMode::Reflect, Transform::identity()).unwrap(),
+        RadialGradient::new_two_point(Point::from_xy(10.0, 10.0), 2.0, Point::from_xy(15.0, 15.0), 12.0, stops.clone(), SpreadMode::Pad, Transform::identity()).unwrap(),
+        RadialGradient::new_two_point(Point::from_xy(5.0, 15.0), 5.0, Point::from_xy(25.0, 15.0), 5.0, stops.clone(), SpreadMode::Pad, Transform::identity()).unwrap(),
+        SweepGradient::new(Point::from_xy(15.0, 15.0), 30.0, 270.0, stops, SpreadMode::Repeat, Transform::from_scale(1.5, 1.0)).unwrap(),
+        Pattern::new(image.as_ref(), SpreadMode::Repeat, FilterQuality::Bicubic, 0.8, Transform::from_rotate(20.0)),
+        Pattern::new(image.as_ref(), SpreadMode::Reflect, FilterQuality::Trilinear, 0.9, Transform::from_scale(0.3, 0.4)),
+    ];
+
+    let mut recorder = PictureRecorder::new(rect);
+    let mut pb = PathBuilder::new();
+    pb.move_to(2.0, 2.0);
+    pb.quad_to(28.0, 2.0, 28.0, 15.0);
+    pb.cubic_to(28.0, 30.0, 10.0, 20.0, 2.0, 28.0);
+    pb.close();
+    pb.line_to(15.0, 15.0);
+    let path = pb.finish().unwrap();
+
+    let filters = [
+        None,
+        ColorFilter::matrix([0.2, 0.7, 0.1, 0.0, 0.1, 0.2, 0.7, 0.1, 0.0, 0.1, 0.2, 0.7, 0.1, 0.0, 0.1, 0.0, 0.0, 0.0, 0.8, 0.0]),
+        Some(ColorFilter::table_rgba(Some(&[255; 256]), None, Some(&[0; 256]), None)),
+        Some(ColorFilter::blend(Color::from_rgba8(220, 140, 75, 180), BlendMode::Multiply)),
+    ];
+
+    let interpolations = [
+        GradientInterpolation::Srgb,
+        GradientInterpolation::LinearSrgb,
+        GradientInterpolation::PremultipliedLinearSrgb,
+    ];
+
+    for (i, mut shader) in shaders.iter().cloned().enumerate() {
+        shader.set_gradient_interpolation(interpolations[i % interpolations.len()]);
+        let paint = Paint {
+            shader,
+            color_filter: filters[i % filters.len()].clone(),
+            blend_mode: BlendMode::Luminosity,
+            color_space: if i % 2 == 0 { ColorSpace::Srgb } else { ColorSpace::LinearSrgb },
+            anti_alias: false,
+            force_hq_pipeline: true,
+            ..Paint::default()
+        };
+
+        recorder.fill_rect(rect, &paint, Transform::identity(), Some(&mask));
+        recorder.fill_path(&path, &paint, FillRule::EvenOdd, Transform::from_scale(0.8, 0.9), None);
+
+        let stroke = Stroke {
+            width: 3.0,
+            miter_limit: 2.0,
+            line_cap: LineCap::Square,
+            line_join: LineJoin::MiterClip,
+            dash: StrokeDash::new(vec![5.0, 2.0, 1.0, 2.0], -3.0),
+        };
+        recorder.stroke_path(&path, &paint, &stroke, Transform::identity(), Some(&mask));
+    }
+
+    let paint = PixmapPaint {
+        opacity: 0.5,
+        blend_mode: BlendMode::Xor,
+        quality: FilterQuality::Bilinear,
+        color_filter: Some(ColorFilter::blend(Color::from_rgba8(50, 127, 150, 200), BlendMode::SourceIn)),
+        color_space: ColorSpace::LinearSrgb,
+        parallel: true,
+    };
+    recorder.draw_pixmap(-3, 4, image.as_ref(), &paint, Transform::from_skew(0.2, 0.1), None);
+    recorder.apply_mask(&mask);
+    recorder.finish()
+}
+
+#[test]
+fn encode_decode() {
+    let picture = all_features();
+    let data = picture.encode();
+    let decoded = Picture::decode(&data).unwrap();
+    assert_eq!(decoded.len(), picture.len());
+    assert_eq!(decoded.cull_rect(), picture.cull_rect());
+    assert_eq!(decoded.encode(), data);
+
+    let mut expected = Pixmap::new(30, 30).unwrap();
+    expected.draw_picture(&picture, Transform::identity());
+
+    let mut pixmap = Pixmap::new(30, 30).unwrap();
+    pixmap.draw_picture(&decoded, Transform::identity());
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn decode_invalid() {
+    assert_eq!(Picture::decode(&[]).unwrap_err(), DecodeError::InvalidSignature);
+    assert_eq!(Picture::decode(b"PNG").unwrap_err(), DecodeError::InvalidSignature);
+
+    let mut data = all_features().encode();
+    data[4] = 9;
+    assert_eq!(Picture::decode(&data).unwrap_err(), DecodeError::UnsupportedVersion(9));
+    data[4] = 2;
+    assert_eq!(Picture::decode(&data).unwrap_err(), DecodeError::UnsupportedVersion(2));
+
+    let mut data = all_features().encode();
+    data.push(0);
+    assert_eq!(Picture::decode(&data).unwrap_err(), DecodeError::TrailingData);
+
+    // A NaN cull rect.
+    let mut data = all_features().encode();
+    data[6..10].copy_from_slice(&f32::NAN.to_le_bytes());
+    assert_eq!(Picture::decode(&data).unwrap_err(), DecodeError::InvalidData { offset: 22 });
+}
+
+#[test]
+fn decode_truncated() {
+    let data = all_features().encode();
+    for len in 0..data.len() {
+        assert!(Picture::decode(&data[..len]).is_err());
+    }
+}
+
+#[test]
+fn decode_corrupted() {
+    // Must never panic.
+    let data = all_features().encode();
+    for i in 0..data.len() {
+        for byte in [0x00, 0xFF] {
+            let mut data = data.clone();
+            data[i] = byte;
+            let _ = Picture::decode(&data);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use tiny_skia::*;
+
+const FILTERS: [ResizeFilter; 3] = [ResizeFilter::Box, ResizeFilter::Mitchell, ResizeFilter::Lanczos3];
+
+fn image() -> Pixmap {
+    let mut pixmap = Pixmap::new(60, 40).unwrap();
+    pixmap.fill(Color::from_rgba8(50, 127, 150, 255));
+
+    let paint = Paint {
+        shader: Shader::SolidColor(Color::from_rgba8(220, 140, 75, 180)),
+        anti_alias: true,
+        ..Paint::default()
+    };
+    let path = PathBuilder::from_circle(25.0, 20.0, 15.0).unwrap();
+    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+
+    let stroke = Stroke { width: 2.0, ..Stroke::default() };
+    let mut pb = PathBuilder::new();
+    pb.move_to(5.0, 35.0);
+    pb.line_to(55.0, 5.0);
+    let path = pb.finish().unwrap();
+    pixmap.stroke_path(&path, &Paint::default(), &stroke, Transform::identity(), None);
+
+    pixmap.fill_rect(Rect::from_xywh(44.0, 20.0, 12.0, 16.0).unwrap(), &Paint::default(), Transform::identity(), None);
+    pixmap
+}
+
+#[test]
+fn zero_size() {
+    let image = image();
+    assert!(image.resize(0, 10, ResizeFilter::Box).is_none());
+    assert!(image.resize(10, 0, ResizeFilter::Box).is_none());
+}
+
+#[test]
+fn box_downscale() {
+    let mut image = Pixmap::new(4, 2).unwrap();
+    let colors = [
+        [0, 0, 0, 0], [200, 100, 40, 200], [10, 20, 30, 40], [30, 20, 10, 40],
+        [100, 0, 0, 200], [0, 0, 0, 0], [10, 20, 30, 40], [30, 20, 10, 40],
+    ];
+    for (pixel, c) in image.pixels_mut().iter_mut().zip(colors) {
+        *pixel = PremultipliedColorU8::from_rgba(c[0], c[1], c[2], c[3]).unwrap();
+    }
+
+    let pixmap = image.resize(2, 1, ResizeFilter::Box).unwrap();
+    assert_eq!(pixmap.pixel(0, 0).unwrap(), PremultipliedColorU8::from_rgba(75, 25, 10, 100).unwrap());
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), PremultipliedColorU8::from_rgba(20, 20, 20, 40).unwrap());
+}
+
+#[test]
+fn same_size() {
+    let image = image();
+    assert_eq!(image.resize(60, 40, ResizeFilter::Box).unwrap(), image);
+    assert_eq!(image.resize(60, 40, ResizeFilter::Lanczos3).unwrap(), image);
+}
+
+#[test]
+fn solid_color() {
+    let mut image = Pixmap::new(37, 23).unwrap();
+    image.fill(Color::from_rgba8(50, 127, 150, 200));
+    let expected = image.pixel(0, 0).unwrap();
+
+    for filter in FILTERS {
+        for (width, height) in [(5, 3), (100, 71), (37, 1)] {
+            let pixmap = image.resize(width, height, filter).unwrap();
+            assert!(pixmap.pixels().iter().all(|c| *c == expected), "{:?} {}x{}", filter, width, height);
+        }
+    }
+}
+
+// Lanczos and Mitchell filters overshoot, but the result must remain premultiplied.
+#[test]
+fn stays_premultiplied() {
+    let image = image();
+    for filter in FILTERS {
+        for (width, height) in [(13, 9), (170, 130)] {
+            let pixmap = image.resize(width, height, filter).unwrap();
+            for c in pixmap.pixels() {
+                assert!(c.red() <= c.alpha() && c.green() <= c.alpha() && c.blue() <= c.alpha());
+            }
+        }
+    }
+}
+
+#[test]
+fn strided_source() {
+    let image = image();
+    let mut framebuffer = Pixmap::new(100, 50).unwrap();
+    framebuffer.fill(Color::from_rgba8(255, 0, 255, 255));
+    framebuffer.draw_pixmap(10, 5, image.as_ref(), &PixmapPaint { blend_mode: BlendMode::Source, ..PixmapPaint::default() }, Transform::identity(), None);
+
+    let offset = (5 * 100 + 10) * 4;
+    let pixmap = PixmapRef::from_bytes_with_stride(&framebuffer.data()[offset..], 60, 40, 400).unwrap();
+    for filter in FILTERS {
+        assert_eq!(pixmap.resize(25, 17, filter), image.resize(25, 17, filter));
+    }
+}
+
+#[test]
+fn box_down() {
+    let pixmap = image().resize(23, 15, ResizeFilter::Box).unwrap();
+    let expected = Pixmap::load_png("tests/images/resize/box-down.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn box_up() {
+    let pixmap = image().resize(150, 100, ResizeFilter::Box).unwrap();
+    let expected = Pixmap::load_png("tests/images/resize/box-up.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn mitchell_down() {
+    let pixmap = image().resize(23, 15, ResizeFilter::Mitchell).unwrap();
+    let expected = Pixmap::load_png("tests/images/resize/mitchell-down.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn mitchell_up() {
+    let pixmap = image().resize(150, 100, ResizeFilter::Mitchell).unwrap();
+    let expected = Pixmap::load_png("tests/images/resize/mitchell-up.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn lanczos3_down() {
+    let pixmap = image().resize(23, 15, ResizeFilter::Lanczos3).unwrap();
+    let expected = Pixmap::load_png("tests/images/resize/lanczos3-down.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn lanczos3_up() {
+    let pixmap = image().resize(150, 100, ResizeFilter::Lanczos3).unwrap();
+    let expected = Pixmap::load_png("tests/images/resize/lanczos3-up.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
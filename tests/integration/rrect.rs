@@ -0,0 +1,242 @@
+use tiny_skia::*;
+
+fn rrect() -> RRect {
+    let radii = [
+        Point::from_xy(40.0, 20.0),
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(60.0, 60.0),
+        Point::from_xy(10.0, 10.0),
+    ];
+    RRect::from_rect_radii(Rect::from_xywh(20.3, 20.7, 160.0, 140.0).unwrap(), radii).unwrap()
+}
+
+fn circular_rrect() -> RRect {
+    let radii = [
+        Point::from_xy(30.0, 30.0),
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(50.0, 50.0),
+        Point::from_xy(3.0, 3.0),
+    ];
+    RRect::from_rect_radii(Rect::from_xywh(20.3, 20.7, 160.0, 140.0).unwrap(), radii).unwrap()
+}
+
+#[test]
+fn radii_scale_down() {
+    let rrect = RRect::from_rect_xy(Rect::from_xywh(0.0, 0.0, 100.0, 50.0).unwrap(), 50.0, 50.0).unwrap();
+    assert_eq!(rrect.radii(), [Point::from_xy(25.0, 25.0); 4]);
+
+    let radii = [
+        Point::from_xy(80.0, 10.0),
+        Point::from_xy(80.0, 10.0),
+        Point::from_xy(20.0, 10.0),
+        Point::from_xy(20.0, 10.0),
+    ];
+    let rrect = RRect::from_rect_radii(Rect::from_xywh(0.0, 0.0, 100.0, 50.0).unwrap(), radii).unwrap();
+    assert_eq!(rrect.radii(), [
+        Point::from_xy(50.0, 6.25),
+        Point::from_xy(50.0, 6.25),
+        Point::from_xy(12.5, 6.25),
+        Point::from_xy(12.5, 6.25),
+    ]);
+}
+
+#[test]
+fn contains() {
+    let rrect = RRect::from_rect_xy(Rect::from_xywh(0.0, 0.0, 100.0, 50.0).unwrap(), 20.0, 10.0).unwrap();
+    assert!(rrect.contains(Point::from_xy(50.0, 25.0)));
+    assert!(rrect.contains(Point::from_xy(50.0, 0.0)));
+    assert!(rrect.contains(Point::from_xy(0.0, 25.0)));
+    assert!(rrect.contains(Point::from_xy(20.0, 0.0)));
+    assert!(!rrect.contains(Point::from_xy(1.0, 1.0)));
+    assert!(!rrect.contains(Point::from_xy(99.0, 49.0)));
+    assert!(!rrect.contains(Point::from_xy(101.0, 25.0)));
+
+    assert!(rrect.contains_rect(&Rect::from_xywh(10.0, 5.0, 80.0, 40.0).unwrap()));
+    assert!(!rrect.contains_rect(&Rect::from_xywh(1.0, 5.0, 80.0, 40.0).unwrap()));
+    assert!(!rrect.contains_rect(&Rect::from_xywh(10.0, 5.0, 80.0, 60.0).unwrap()));
+}
+
+#[test]
+fn inset() {
+    let radii = [
+        Point::from_xy(20.0, 10.0),
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(5.0, 5.0),
+        Point::from_xy(20.0, 20.0),
+    ];
+    let rrect = RRect::from_rect_radii(Rect::from_xywh(0.0, 0.0, 100.0, 50.0).unwrap(), radii).unwrap();
+
+    let inset = rrect.inset(5.0, 5.0).unwrap();
+    assert_eq!(inset.rect(), Rect::from_ltrb(5.0, 5.0, 95.0, 45.0).unwrap());
+    assert_eq!(inset.radii(), [
+        Point::from_xy(15.0, 5.0),
+        Point::zero(),
+        Point::zero(),
+        Point::from_xy(15.0, 15.0),
+    ]);
+
+    let outset = rrect.outset(5.0, 5.0).unwrap();
+    assert_eq!(outset.rect(), Rect::from_ltrb(-5.0, -5.0, 105.0, 55.0).unwrap());
+    assert_eq!(outset.radii(), [
+        Point::from_xy(25.0, 15.0),
+        Point::zero(),
+        Point::from_xy(10.0, 10.0),
+        Point::from_xy(25.0, 25.0),
+    ]);
+
+    assert!(rrect.inset(60.0, 5.0).is_none());
+}
+
+#[test]
+fn transform() {
+    let rrect = circular_rrect();
+    assert!(rrect.transform(Transform::from_rotate(30.0)).is_none());
+
+    let transformed = rrect.transform(Transform::from_row(2.0, 0.0, 0.0, 0.5, 10.0, 20.0)).unwrap();
+    assert_eq!(transformed.radii()[0], Point::from_xy(60.0, 15.0));
+}
+
+#[test]
+fn push_rrect() {
+    let rrect = RRect::from_rect_radii(Rect::from_xywh(0.0, 0.0, 100.0, 50.0).unwrap(), [
+        Point::from_xy(10.0, 10.0),
+        Point::zero(),
+        Point::zero(),
+        Point::zero(),
+    ]).unwrap();
+    let path = PathBuilder::from_rrect(rrect).unwrap();
+    assert_eq!(path.bounds(), rrect.rect());
+
+    let segments: Vec<_> = path.segments().collect();
+    assert_eq!(segments.len(), 8);
+    assert_eq!(segments[0], PathSegment::MoveTo(Point::from_xy(10.0, 0.0)));
+    assert_eq!(segments[1], PathSegment::LineTo(Point::from_xy(100.0, 0.0)));
+    assert_eq!(segments[2], PathSegment::LineTo(Point::from_xy(100.0, 50.0)));
+    assert_eq!(segments[3], PathSegment::LineTo(Point::from_xy(0.0, 50.0)));
+    assert_eq!(segments[4], PathSegment::LineTo(Point::from_xy(0.0, 10.0)));
+    assert!(matches!(segments[5], PathSegment::QuadTo(..)));
+    assert!(matches!(segments[6], PathSegment::QuadTo(..)));
+    assert_eq!(segments[7], PathSegment::Close);
+
+    // Square corners produce a rect.
+    let rrect = RRect::from_rect(Rect::from_xywh(0.0, 0.0, 100.0, 50.0).unwrap());
+    assert_eq!(PathBuilder::from_rrect(rrect).unwrap(), PathBuilder::from_rect(rrect.rect()));
+}
+
+fn paint(anti_alias: bool) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 200);
+    paint.anti_alias = anti_alias;
+    paint
+}
+
+#[test]
+fn fill() {
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_rrect(rrect(), &paint(false), Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/rrect/fill.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn fill_aa() {
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_rrect(rrect(), &paint(true), Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/rrect/fill-aa.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn fill_scaled() {
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    let ts = Transform::from_row(-0.5, 0.0, 0.0, 1.2, 150.0, -10.0);
+    pixmap.fill_rrect(rrect(), &paint(true), ts, None);
+
+    let expected = Pixmap::load_png("tests/images/rrect/fill-scaled.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn fill_rotated() {
+    // Falls back to a path.
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    let ts = Transform::from_rotate_at(30.0, 100.0, 100.0).pre_scale(0.8, 0.8);
+    pixmap.fill_rrect(rrect(), &paint(true), ts, None);
+
+    let mut expected = Pixmap::new(200, 200).unwrap();
+    let path = PathBuilder::from_rrect(rrect()).unwrap();
+    expected.fill_path(&path, &paint(true), FillRule::Winding, ts, None);
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn fill_gradient() {
+    let mut paint = paint(true);
+    paint.shader = LinearGradient::new(
+        Point::from_xy(20.0, 20.0),
+        Point::from_xy(180.0, 160.0),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+            GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    ).unwrap();
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.fill_rrect(rrect(), &paint, Transform::from_translate(-10.0, 10.0), None);
+
+    let expected = Pixmap::load_png("tests/images/rrect/fill-gradient.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn stroke() {
+    let stroke = Stroke {
+        width: 8.0,
+        ..Stroke::default()
+    };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.stroke_rrect(circular_rrect(), &paint(true), &stroke, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/rrect/stroke.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn stroke_round_join() {
+    // Square corners with a round join fall back to a path.
+    let stroke = Stroke {
+        width: 8.0,
+        line_join: LineJoin::Round,
+        ..Stroke::default()
+    };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.stroke_rrect(circular_rrect(), &paint(true), &stroke, Transform::identity(), None);
+
+    let mut expected = Pixmap::new(200, 200).unwrap();
+    let path = PathBuilder::from_rrect(circular_rrect()).unwrap();
+    expected.stroke_path(&path, &paint(true), &stroke, Transform::identity(), None);
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn stroke_wide() {
+    // The stroke covers the whole rect.
+    let rrect = RRect::from_rect_xy(Rect::from_xywh(50.0, 50.0, 100.0, 100.0).unwrap(), 20.0, 20.0).unwrap();
+    let stroke = Stroke {
+        width: 120.0,
+        ..Stroke::default()
+    };
+
+    let mut pixmap = Pixmap::new(200, 200).unwrap();
+    pixmap.stroke_rrect(rrect, &paint(true), &stroke, Transform::identity(), None);
+
+    let mut expected = Pixmap::new(200, 200).unwrap();
+    expected.fill_rrect(rrect.outset(60.0, 60.0).unwrap(), &paint(true), Transform::identity(), None);
+    assert_eq!(pixmap, expected);
+}
@@ -0,0 +1,117 @@
+use tiny_skia::*;
+
+fn star() -> Path {
+    let mut pb = PathBuilder::new();
+    pb.move_to(50.0, 10.0);
+    pb.line_to(74.0, 84.0);
+    pb.line_to(12.0, 38.0);
+    pb.line_to(88.0, 38.0);
+    pb.line_to(26.0, 84.0);
+    pb.close();
+    pb.finish().unwrap()
+}
+
+#[test]
+fn drop_shadow() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let shadow = Shadow {
+        offset_x: 4.0,
+        offset_y: 6.0,
+        sigma: 3.0,
+        color: Color::from_rgba8(0, 0, 0, 180),
+        inner: false,
+    };
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_path_with_shadow(&star(), &paint, FillRule::Winding, Transform::identity(), &shadow, None);
+
+    let expected = Pixmap::load_png("tests/images/shadow/drop-shadow.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn drop_shadow_without_blur() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let shadow = Shadow {
+        offset_x: -5.0,
+        offset_y: 5.0,
+        color: Color::from_rgba8(220, 140, 75, 255),
+        ..Shadow::default()
+    };
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_path_with_shadow(&star(), &paint, FillRule::EvenOdd, Transform::identity(), &shadow, None);
+
+    let expected = Pixmap::load_png("tests/images/shadow/drop-shadow-without-blur.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn drop_shadow_with_transform() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    // Offset and blur are not affected by the transform.
+    let shadow = Shadow {
+        offset_x: 10.0,
+        offset_y: 10.0,
+        sigma: 5.0,
+        color: Color::from_rgba8(0, 0, 0, 180),
+        inner: false,
+    };
+
+    // Partially outside the pixmap.
+    let ts = Transform::from_row(1.5, 0.0, 0.0, 1.5, -20.0, -20.0);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_path_with_shadow(&star(), &paint, FillRule::Winding, ts, &shadow, None);
+
+    let expected = Pixmap::load_png("tests/images/shadow/drop-shadow-with-transform.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn inner_shadow() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let shadow = Shadow {
+        offset_x: 3.0,
+        offset_y: 4.0,
+        sigma: 2.5,
+        color: Color::from_rgba8(0, 0, 0, 200),
+        inner: true,
+    };
+
+    let path = PathBuilder::from_circle(50.0, 50.0, 35.0).unwrap();
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_path_with_shadow(&path, &paint, FillRule::Winding, Transform::identity(), &shadow, None);
+
+    let expected = Pixmap::load_png("tests/images/shadow/inner-shadow.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn shadow_outside_pixmap() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(50, 127, 150, 255);
+
+    let shadow = Shadow {
+        offset_x: 500.0,
+        sigma: 2.0,
+        ..Shadow::default()
+    };
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill_path_with_shadow(&star(), &paint, FillRule::Winding, Transform::identity(), &shadow, None);
+
+    let mut expected = Pixmap::new(100, 100).unwrap();
+    expected.fill_path(&star(), &paint, FillRule::Winding, Transform::identity(), None);
+
+    assert_eq!(pixmap, expected);
+}
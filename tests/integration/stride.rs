@@ -0,0 +1,214 @@
+use tiny_skia::*;
+
+// A 200x150 region at (40, 30) inside a 300x250 framebuffer.
+const STRIDE: usize = 300 * 4;
+const OFFSET: usize = (30 * 300 + 40) * 4;
+
+fn paint() -> Paint<'static> {
+    Paint {
+        shader: LinearGradient::new(
+            Point::from_xy(10.0, 10.0),
+            Point::from_xy(190.0, 140.0),
+            vec![
+                GradientStop::new(0.0, Color::from_rgba8(50, 127, 150, 200)),
+                GradientStop::new(1.0, Color::from_rgba8(220, 140, 75, 180)),
+            ],
+            SpreadMode::Pad,
+            Transform::identity(),
+        ).unwrap(),
+        ..Paint::default()
+    }
+}
+
+fn draw(pixmap: &mut PixmapMut, paint: &Paint) {
+    let circle = PathBuilder::from_circle(100.0, 75.0, 60.0).unwrap();
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(10.0, 140.0);
+    pb.cubic_to(60.0, 0.0, 140.0, 200.0, 190.0, 10.0);
+    let curve = pb.finish().unwrap();
+
+    pixmap.fill(Color::from_rgba8(30, 40, 50, 255));
+    pixmap.fill_path(&circle, paint, FillRule::Winding, Transform::identity(), None);
+    pixmap.stroke_path(&curve, paint, &Stroke { width: 8.0, ..Stroke::default() }, Transform::identity(), None);
+    pixmap.fill_rect(Rect::from_xywh(150.0, 100.0, 60.0, 60.0).unwrap(), paint, Transform::identity(), None);
+    // A memset.
+    pixmap.fill_rect(Rect::from_xywh(-10.0, 120.0, 50.0, 50.0).unwrap(), &Paint::default(), Transform::identity(), None);
+}
+
+fn expected(paint: &Paint) -> Pixmap {
+    let mut pixmap = Pixmap::new(200, 150).unwrap();
+    draw(&mut pixmap.as_mut(), paint);
+    pixmap
+}
+
+fn framebuffer() -> Pixmap {
+    let mut pixmap = Pixmap::new(300, 250).unwrap();
+    pixmap.fill(Color::from_rgba8(255, 0, 255, 255));
+    pixmap
+}
+
+fn assert_outside_untouched(framebuffer: &Pixmap) {
+    let background = PremultipliedColorU8::from_rgba(255, 0, 255, 255).unwrap();
+    for y in 0..250 {
+        for x in 0..300 {
+            if !(40..240).contains(&x) || !(30..180).contains(&y) {
+                assert_eq!(framebuffer.pixel(x, y).unwrap(), background);
+            }
+        }
+    }
+}
+
+#[test]
+fn from_bytes_with_stride() {
+    let mut data = vec![0; 100 * 4 * 9 + 50 * 4];
+    assert!(PixmapMut::from_bytes_with_stride(&mut data, 50, 10, 400).is_some());
+    assert!(PixmapMut::from_bytes_with_stride(&mut data, 50, 11, 400).is_none());
+    assert!(PixmapMut::from_bytes_with_stride(&mut data, 50, 10, 196).is_none());
+    assert!(PixmapMut::from_bytes_with_stride(&mut data, 50, 10, 202).is_none());
+    assert!(PixmapMut::from_bytes_with_stride(&mut data, 0, 10, 400).is_none());
+    assert_eq!(PixmapRef::from_bytes_with_stride(&data, 50, 10, 400).unwrap().stride(), 400);
+    assert_eq!(PixmapRef::from_bytes(&data, 50, 10).unwrap().stride(), 200);
+}
+
+#[test]
+fn draw_into_subregion() {
+    let paint = paint();
+
+    let mut framebuffer = framebuffer();
+    let mut pixmap = PixmapMut::from_bytes_with_stride(&mut framebuffer.data_mut()[OFFSET..], 200, 150, STRIDE).unwrap();
+    draw(&mut pixmap, &paint);
+
+    assert_eq!(pixmap.to_owned(), expected(&paint));
+    assert_outside_untouched(&framebuffer);
+}
+
+#[test]
+fn draw_into_subregion_parallel() {
+    let paint = Paint { parallel: true, ..paint() };
+
+    let mut framebuffer = framebuffer();
+    let mut pixmap = PixmapMut::from_bytes_with_stride(&mut framebuffer.data_mut()[OFFSET..], 200, 150, STRIDE).unwrap();
+    draw(&mut pixmap, &paint);
+
+    assert_eq!(pixmap.to_owned(), expected(&paint));
+    assert_outside_untouched(&framebuffer);
+}
+
+#[test]
+fn draw_into_subregion_with_mask() {
+    let mut mask = Mask::new(200, 150).unwrap();
+    mask.fill_path(&PathBuilder::from_circle(100.0, 75.0, 50.0).unwrap(), FillRule::Winding, true, Transform::identity());
+
+    let mut expected = expected(&paint());
+    expected.apply_mask(&mask);
+
+    let mut framebuffer = framebuffer();
+    let mut pixmap = PixmapMut::from_bytes_with_stride(&mut framebuffer.data_mut()[OFFSET..], 200, 150, STRIDE).unwrap();
+    draw(&mut pixmap, &paint());
+    pixmap.apply_mask(&mask);
+
+    assert_eq!(pixmap.to_owned(), expected);
+    assert_outside_untouched(&framebuffer);
+}
+
+#[test]
+fn canvas() {
+    let paint = paint();
+
+    let mut expected = Pixmap::new(200, 150).unwrap();
+    expected.fill(Color::from_rgba8(255, 0, 255, 255));
+    let mut framebuffer = framebuffer();
+    let pixmap = PixmapMut::from_bytes_with_stride(&mut framebuffer.data_mut()[OFFSET..], 200, 150, STRIDE).unwrap();
+
+    for pixmap in [expected.as_mut(), pixmap] {
+        let mut canvas = Canvas::new(pixmap);
+        canvas.clip_rect(Rect::from_xywh(20.0, 20.0, 160.0, 110.0).unwrap(), true);
+        canvas.save_layer(None, &PixmapPaint { opacity: 0.5, ..PixmapPaint::default() }, None);
+        canvas.fill_path(&PathBuilder::from_circle(100.0, 75.0, 60.0).unwrap(), &paint, FillRule::Winding);
+        canvas.restore();
+    }
+
+    assert_eq!(PixmapRef::from_bytes_with_stride(&framebuffer.data()[OFFSET..], 200, 150, STRIDE).unwrap().to_owned(), expected);
+    assert_outside_untouched(&framebuffer);
+}
+
+#[test]
+fn blur() {
+    let mut expected = expected(&paint());
+    expected.blur(3.0, 5.0, BlurEdgeMode::Clamp);
+
+    let mut framebuffer = framebuffer();
+    let mut pixmap = PixmapMut::from_bytes_with_stride(&mut framebuffer.data_mut()[OFFSET..], 200, 150, STRIDE).unwrap();
+    draw(&mut pixmap, &paint());
+    pixmap.blur(3.0, 5.0, BlurEdgeMode::Clamp);
+
+    assert_eq!(pixmap.to_owned(), expected);
+    assert_outside_untouched(&framebuffer);
+}
+
+#[test]
+fn accessors() {
+    let expected = expected(&paint());
+
+    let mut framebuffer = framebuffer();
+    let mut pixmap = PixmapMut::from_bytes_with_stride(&mut framebuffer.data_mut()[OFFSET..], 200, 150, STRIDE).unwrap();
+    draw(&mut pixmap, &paint());
+    let pixmap = pixmap.as_ref();
+
+    assert_eq!(pixmap.pixel(199, 149), expected.pixel(199, 149));
+    assert_eq!(pixmap.pixel(100, 75), expected.pixel(100, 75));
+    assert!(pixmap.pixel(200, 0).is_none());
+    assert!(pixmap.rows().flatten().eq(expected.pixels()));
+
+    let rect = IntRect::from_xywh(30, 20, 100, 90).unwrap();
+    assert_eq!(pixmap.clone_rect(rect), expected.clone_rect(rect));
+
+    for mask_type in [MaskType::Alpha, MaskType::Luminance] {
+        assert_eq!(Mask::from_pixmap(pixmap, mask_type), Mask::from_pixmap(expected.as_ref(), mask_type));
+    }
+}
+
+// Patterns must respect the source stride.
+#[test]
+fn draw_pixmap_from_subregion() {
+    let expected_src = expected(&paint());
+
+    let mut framebuffer = framebuffer();
+    let mut src = PixmapMut::from_bytes_with_stride(&mut framebuffer.data_mut()[OFFSET..], 200, 150, STRIDE).unwrap();
+    draw(&mut src, &paint());
+
+    let paint = PixmapPaint {
+        quality: FilterQuality::Bicubic,
+        ..PixmapPaint::default()
+    };
+    let ts = Transform::from_row(1.3, 0.2, -0.1, 1.1, 10.0, 5.0);
+
+    let mut expected = Pixmap::new(300, 250).unwrap();
+    expected.draw_pixmap(0, 0, expected_src.as_ref(), &paint, ts, None);
+
+    let mut pixmap = Pixmap::new(300, 250).unwrap();
+    pixmap.draw_pixmap(0, 0, src.as_ref(), &paint, ts, None);
+
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn bitmap() {
+    let mut expected = Bitmap::new(200, 150, ColorType::Rgb565).unwrap();
+    expected.fill(Color::from_rgba8(30, 40, 50, 255));
+    expected.fill_path(&PathBuilder::from_circle(100.0, 75.0, 60.0).unwrap(), &paint(), FillRule::Winding, Transform::identity(), None);
+
+    // Two bytes of padding after each row.
+    let stride = 200 * 2 + 2;
+    let mut data = vec![0xAA; stride * 150];
+    let mut bitmap = BitmapMut::from_bytes_with_stride(&mut data, 200, 150, stride, ColorType::Rgb565).unwrap();
+    assert!(BitmapMut::from_bytes_with_stride(&mut vec![0; 1000], 200, 150, 401, ColorType::Rgb565).is_none());
+    bitmap.fill(Color::from_rgba8(30, 40, 50, 255));
+    bitmap.fill_path(&PathBuilder::from_circle(100.0, 75.0, 60.0).unwrap(), &paint(), FillRule::Winding, Transform::identity(), None);
+
+    for (row, expected_row) in data.chunks(stride).zip(expected.data().chunks(400)) {
+        assert_eq!(&row[..400], expected_row);
+        assert_eq!(&row[400..], &[0xAA, 0xAA]);
+    }
+}
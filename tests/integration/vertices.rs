@@ -0,0 +1,330 @@
+use tiny_skia::*;
+
+fn triangle() -> [Point; 3] {
+    [
+        Point::from_xy(50.0, 10.0),
+        Point::from_xy(90.0, 85.0),
+        Point::from_xy(10.0, 70.0),
+    ]
+}
+
+fn triangle_colors() -> [Color; 3] {
+    [
+        Color::from_rgba8(255, 0, 0, 255),
+        Color::from_rgba8(0, 255, 0, 255),
+        Color::from_rgba8(0, 0, 255, 255),
+    ]
+}
+
+fn checkerboard() -> Pixmap {
+    let mut pixmap = Pixmap::new(10, 10).unwrap();
+    let paint = Paint {
+        shader: Shader::SolidColor(Color::from_rgba8(50, 50, 50, 255)),
+        ..Paint::default()
+    };
+    pixmap.fill(Color::WHITE);
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 5.0, 5.0).unwrap(), &paint, Transform::identity(), None);
+    pixmap.fill_rect(Rect::from_xywh(5.0, 5.0, 5.0, 5.0).unwrap(), &paint, Transform::identity(), None);
+    pixmap
+}
+
+// A quad made of two triangles.
+fn quad() -> ([Point; 4], [u16; 6]) {
+    let positions = [
+        Point::from_xy(20.0, 10.0),
+        Point::from_xy(90.0, 25.0),
+        Point::from_xy(80.0, 90.0),
+        Point::from_xy(10.0, 70.0),
+    ];
+    (positions, [0, 1, 2, 0, 2, 3])
+}
+
+#[test]
+fn colors() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&triangle(), Some(&triangle_colors()), None, None, BlendMode::Destination,
+                         &Paint::default(), Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/vertices/colors.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn colors_without_anti_aliasing() {
+    let paint = Paint {
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&triangle(), Some(&triangle_colors()), None, None, BlendMode::Destination,
+                         &paint, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/vertices/colors-without-anti-aliasing.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn semi_transparent_colors() {
+    let colors = [
+        Color::from_rgba8(255, 0, 0, 255),
+        Color::from_rgba8(0, 255, 0, 127),
+        Color::from_rgba8(0, 0, 255, 0),
+    ];
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.fill(Color::WHITE);
+    pixmap.draw_vertices(&triangle(), Some(&colors), None, None, BlendMode::Destination,
+                         &Paint::default(), Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/vertices/semi-transparent-colors.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn heatmap() {
+    // A 5x5 grid of vertices with a value per vertex.
+    let values = [
+        0.0, 0.1, 0.3, 0.1, 0.0,
+        0.1, 0.4, 0.7, 0.3, 0.1,
+        0.2, 0.6, 1.0, 0.5, 0.2,
+        0.1, 0.3, 0.6, 0.3, 0.1,
+        0.0, 0.1, 0.2, 0.1, 0.0,
+    ];
+
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    for (i, v) in values.iter().enumerate() {
+        positions.push(Point::from_xy((i % 5) as f32 * 25.0, (i / 5) as f32 * 25.0));
+        colors.push(Color::from_rgba(*v, 0.2, 1.0 - *v, 1.0).unwrap());
+    }
+
+    let mut indices = Vec::new();
+    for y in 0..4 {
+        for x in 0..4 {
+            let i = y * 5 + x;
+            indices.extend_from_slice(&[i, i + 1, i + 5, i + 1, i + 6, i + 5]);
+        }
+    }
+
+    let paint = Paint {
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&positions, Some(&colors), None, Some(&indices), BlendMode::Destination,
+                         &paint, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/vertices/heatmap.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn texture() {
+    let texture = checkerboard();
+    let paint = Paint {
+        shader: Pattern::new(texture.as_ref(), SpreadMode::Repeat, FilterQuality::Bilinear, 1.0, Transform::identity()),
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let (positions, indices) = quad();
+    let tex_coords = [
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(30.0, 0.0),
+        Point::from_xy(30.0, 30.0),
+        Point::from_xy(0.0, 30.0),
+    ];
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&positions, None, Some(&tex_coords), Some(&indices), BlendMode::Modulate,
+                         &paint, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/vertices/texture.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn colors_modulate_texture() {
+    let texture = checkerboard();
+    let paint = Paint {
+        shader: Pattern::new(texture.as_ref(), SpreadMode::Repeat, FilterQuality::Bilinear, 1.0, Transform::identity()),
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let (positions, indices) = quad();
+    let tex_coords = [
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(30.0, 0.0),
+        Point::from_xy(30.0, 30.0),
+        Point::from_xy(0.0, 30.0),
+    ];
+    let colors = [
+        Color::from_rgba8(255, 0, 0, 255),
+        Color::from_rgba8(0, 255, 0, 255),
+        Color::from_rgba8(0, 0, 255, 255),
+        Color::from_rgba8(255, 255, 0, 255),
+    ];
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&positions, Some(&colors), Some(&tex_coords), Some(&indices), BlendMode::Modulate,
+                         &paint, Transform::identity(), None);
+
+    let expected = Pixmap::load_png("tests/images/vertices/colors-modulate-texture.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn shader_without_tex_coords() {
+    let texture = checkerboard();
+    let paint = Paint {
+        shader: Pattern::new(texture.as_ref(), SpreadMode::Repeat, FilterQuality::Nearest, 1.0, Transform::identity()),
+        anti_alias: false,
+        ..Paint::default()
+    };
+
+    let (positions, indices) = quad();
+
+    // Without texture coordinates, the shader is mapped the same way as when filling a path.
+    let mut pixmap1 = Pixmap::new(100, 100).unwrap();
+    pixmap1.draw_vertices(&positions, None, None, Some(&indices), BlendMode::Modulate,
+                          &paint, Transform::from_scale(0.5, 0.5), None);
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(positions[0].x, positions[0].y);
+    for p in &positions[1..] {
+        pb.line_to(p.x, p.y);
+    }
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    let mut pixmap2 = Pixmap::new(100, 100).unwrap();
+    pixmap2.fill_path(&path, &paint, FillRule::Winding, Transform::from_scale(0.5, 0.5), None);
+
+    assert_eq!(pixmap1, pixmap2);
+}
+
+#[test]
+fn destination_ignores_shader() {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(0, 0, 0, 255);
+
+    let mut pixmap1 = Pixmap::new(100, 100).unwrap();
+    pixmap1.draw_vertices(&triangle(), Some(&triangle_colors()), None, None, BlendMode::Destination,
+                          &paint, Transform::identity(), None);
+
+    let mut pixmap2 = Pixmap::new(100, 100).unwrap();
+    pixmap2.draw_vertices(&triangle(), Some(&triangle_colors()), None, None, BlendMode::Destination,
+                          &Paint::default(), Transform::identity(), None);
+
+    assert_eq!(pixmap1, pixmap2);
+}
+
+#[test]
+fn uniform_colors() {
+    let color = Color::from_rgba8(50, 127, 150, 200);
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&triangle(), Some(&[color; 3]), None, None, BlendMode::Destination,
+                         &Paint::default(), Transform::identity(), None);
+
+    assert_eq!(pixmap.pixel(50, 50).unwrap(), color.premultiply().to_color_u8());
+}
+
+#[test]
+fn without_indices() {
+    let (positions, indices) = quad();
+    let colors = [
+        Color::from_rgba8(255, 0, 0, 255),
+        Color::from_rgba8(0, 255, 0, 255),
+        Color::from_rgba8(0, 0, 255, 255),
+        Color::from_rgba8(255, 255, 0, 255),
+    ];
+
+    let mut pixmap1 = Pixmap::new(100, 100).unwrap();
+    pixmap1.draw_vertices(&positions, Some(&colors), None, Some(&indices), BlendMode::Destination,
+                          &Paint::default(), Transform::identity(), None);
+
+    let unindexed_positions: Vec<_> = indices.iter().map(|i| positions[*i as usize]).collect();
+    let unindexed_colors: Vec<_> = indices.iter().map(|i| colors[*i as usize]).collect();
+    let mut pixmap2 = Pixmap::new(100, 100).unwrap();
+    pixmap2.draw_vertices(&unindexed_positions, Some(&unindexed_colors), None, None, BlendMode::Destination,
+                          &Paint::default(), Transform::identity(), None);
+
+    assert_eq!(pixmap1, pixmap2);
+}
+
+#[test]
+fn trailing_indices_are_ignored() {
+    let (positions, indices) = quad();
+
+    let mut pixmap1 = Pixmap::new(100, 100).unwrap();
+    pixmap1.draw_vertices(&positions, None, None, Some(&indices[..3]), BlendMode::Destination,
+                          &Paint::default(), Transform::identity(), None);
+
+    let mut pixmap2 = Pixmap::new(100, 100).unwrap();
+    pixmap2.draw_vertices(&positions, None, None, Some(&indices[..5]), BlendMode::Destination,
+                          &Paint::default(), Transform::identity(), None);
+
+    assert_eq!(pixmap1, pixmap2);
+}
+
+#[test]
+fn index_out_of_bounds() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&triangle(), None, None, Some(&[0, 1, 3]), BlendMode::Destination,
+                         &Paint::default(), Transform::identity(), None);
+
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0));
+}
+
+#[test]
+fn colors_count_mismatch() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&triangle(), Some(&triangle_colors()[..2]), None, None, BlendMode::Destination,
+                         &Paint::default(), Transform::identity(), None);
+
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0));
+}
+
+#[test]
+fn degenerate_triangle() {
+    let positions = [
+        Point::from_xy(10.0, 10.0),
+        Point::from_xy(50.0, 50.0),
+        Point::from_xy(90.0, 90.0),
+    ];
+
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&positions, Some(&triangle_colors()), None, None, BlendMode::Destination,
+                         &Paint::default(), Transform::identity(), None);
+
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0));
+}
+
+#[test]
+fn with_transform() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    pixmap.draw_vertices(&triangle(), Some(&triangle_colors()), None, None, BlendMode::Destination,
+                         &Paint::default(), Transform::from_row(0.8, 0.3, -0.3, 0.8, 30.0, 5.0), None);
+
+    let expected = Pixmap::load_png("tests/images/vertices/with-transform.png").unwrap();
+    assert_eq!(pixmap, expected);
+}
+
+#[test]
+fn on_canvas() {
+    let mut pixmap1 = Pixmap::new(200, 200).unwrap();
+    pixmap1.draw_vertices(&triangle(), Some(&triangle_colors()), None, None, BlendMode::Destination,
+                          &Paint::default(), Transform::from_scale(2.0, 2.0), None);
+
+    let mut pixmap2 = Pixmap::new(200, 200).unwrap();
+    let mut canvas = Canvas::new(pixmap2.as_mut());
+    canvas.scale(2.0, 2.0);
+    canvas.draw_vertices(&triangle(), Some(&triangle_colors()), None, None, BlendMode::Destination,
+                         &Paint::default());
+
+    assert_eq!(pixmap1, pixmap2);
+}